@@ -1,3 +1,4 @@
 pub(crate) mod canvas_msg_bus;
 pub(crate) mod clipboard_worker;
 pub(crate) mod command_msg_bus;
+pub(crate) mod fractal_worker;
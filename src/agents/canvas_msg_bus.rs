@@ -46,5 +46,7 @@ pub enum ControlMsgRequest {
     FractalStarted,
     FractalProgress(String),
     FractalPaused,
+    CyclingStarted,
+    CyclingStopped,
     CanvasSelectMsg((u32, u32, u32, u32)),
 }
@@ -14,7 +14,9 @@ use gloo::timers::future::TimeoutFuture;
 use std::fmt::{Debug, Formatter};
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen(inline_js = "export const ClipboardItem = window.ClipboardItem")]
+// the global constructor, an inline_js snippet would stop the no-modules worker bundle from
+// building
+#[wasm_bindgen]
 extern "C" {
     pub type ClipboardItem;
 
@@ -45,4 +45,7 @@ pub enum CommandRequest {
     Start,
     Stop,
     Clear,
+    /// Animate the palette of the drawn escape time fractal
+    StartCycling,
+    StopCycling,
 }
@@ -0,0 +1,252 @@
+use serde::{Deserialize, Serialize};
+use web_sys::window;
+use yew::Callback;
+use yew_agent::{Agent, AgentLink, Bridge, Bridged, HandlerId, Private, Public};
+
+use crate::components::root::Config;
+use crate::work::{
+    fractal::{FractalCalculator, Points, MAX_POINTS},
+    stats::Stats,
+};
+
+// FractalWorker
+// Calculates spans of pixels of a fractal in a web worker, the main thread only assembles and
+// draws the results.
+// The worker bundle is the same wasm package built with --target no-modules, see Makefile.toml.
+
+const WORKER_RESOURCE: &str = "worker/worker.js";
+const MAX_WORKERS: usize = 16;
+
+#[derive(Serialize, Deserialize)]
+pub enum WorkerRequest {
+    Init {
+        job: u32,
+        config: Box<Config>,
+        width: u32,
+        height: u32,
+    },
+    /// `count` pixels from pixel `start` on, pixels are counted row by row
+    Calculate { start: u32, count: u32 },
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WorkerResponse {
+    job: u32,
+    start: u32,
+    values: Vec<u32>,
+    angles: Vec<f32>,
+    iterations: usize,
+    duration: f64,
+}
+
+#[derive(Default)]
+struct WorkerState {
+    job: u32,
+    calculator: Option<FractalCalculator>,
+}
+
+impl WorkerState {
+    fn handle_request(&mut self, msg: WorkerRequest) -> Option<WorkerResponse> {
+        match msg {
+            WorkerRequest::Init {
+                job,
+                config,
+                width,
+                height,
+            } => {
+                self.job = job;
+                self.calculator = Some(FractalCalculator::new(&config, width, height));
+                None
+            }
+            WorkerRequest::Calculate { start, count } => {
+                let calculator = self.calculator.as_ref()?;
+                let start_time = js_sys::Date::now();
+                let (values, angles, iterations) = calculator.calculate_span(start, count);
+                Some(WorkerResponse {
+                    job: self.job,
+                    start,
+                    values,
+                    angles,
+                    iterations,
+                    duration: js_sys::Date::now() - start_time,
+                })
+            }
+        }
+    }
+}
+
+/// The agent the main thread bridges to, every bridge spawns its own web worker
+pub struct FractalWorker {
+    link: AgentLink<FractalWorker>,
+    state: WorkerState,
+}
+
+impl Agent for FractalWorker {
+    type Reach = Private<Self>;
+    type Message = ();
+    type Input = WorkerRequest;
+    type Output = WorkerResponse;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        Self {
+            link,
+            state: WorkerState::default(),
+        }
+    }
+
+    fn update(&mut self, _msg: Self::Message) {}
+
+    fn handle_input(&mut self, msg: Self::Input, id: HandlerId) {
+        if let Some(res) = self.state.handle_request(msg) {
+            self.link.respond(id, res);
+        }
+    }
+
+    fn name_of_resource() -> &'static str {
+        WORKER_RESOURCE
+    }
+
+    fn resource_path_is_relative() -> bool {
+        true
+    }
+}
+
+/// The agent registered inside the web worker.
+/// yew-agent only implements Threaded for public agents, this one speaks the same protocol
+/// as the private FractalWorker bridges.
+pub struct FractalWorkerHost {
+    link: AgentLink<FractalWorkerHost>,
+    state: WorkerState,
+}
+
+impl Agent for FractalWorkerHost {
+    type Reach = Public<Self>;
+    type Message = ();
+    type Input = WorkerRequest;
+    type Output = WorkerResponse;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        info!("Creating fractal worker");
+        Self {
+            link,
+            state: WorkerState::default(),
+        }
+    }
+
+    fn update(&mut self, _msg: Self::Message) {}
+
+    fn handle_input(&mut self, msg: Self::Input, id: HandlerId) {
+        if let Some(res) = self.state.handle_request(msg) {
+            self.link.respond(id, res);
+        }
+    }
+}
+
+/// Hands out spans of pixels to a pool of workers and collects the results
+pub struct WorkerPool {
+    workers: Vec<Box<dyn Bridge<FractalWorker>>>,
+    job: u32,
+    width: u32,
+    /// pixels of the canvas, counted row by row
+    num_pixels: u32,
+    next_pixel: u32,
+    pending: usize,
+    res: Points,
+}
+
+impl WorkerPool {
+    pub fn new(size: usize, callback: &Callback<(usize, WorkerResponse)>) -> Self {
+        info!("WorkerPool::new: creating {} workers", size);
+        let workers = (0..size)
+            .map(|idx| {
+                let callback = callback.clone();
+                FractalWorker::bridge(Callback::from(move |res| callback.emit((idx, res))))
+            })
+            .collect();
+
+        Self {
+            workers,
+            job: 0,
+            width: 0,
+            num_pixels: 0,
+            next_pixel: 0,
+            pending: 0,
+            res: Points::default(),
+        }
+    }
+
+    /// The number of workers to use, one per logical core
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn worker_count() -> usize {
+        let count = window()
+            .expect("Window not found")
+            .navigator()
+            .hardware_concurrency() as usize;
+        count.min(MAX_WORKERS)
+    }
+
+    pub fn start(&mut self, config: &Config, width: u32, height: u32) {
+        self.job += 1;
+        self.width = width;
+        self.num_pixels = width * height;
+        self.next_pixel = 0;
+        self.pending = 0;
+
+        for idx in 0..self.workers.len() {
+            self.workers[idx].send(WorkerRequest::Init {
+                job: self.job,
+                config: Box::new(config.clone()),
+                width,
+                height,
+            });
+            self.dispatch(idx);
+        }
+    }
+
+    /// Drop all results that are still outstanding and stop handing out work
+    pub fn stop(&mut self) {
+        self.job += 1;
+        self.next_pixel = self.num_pixels;
+        self.pending = 0;
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending == 0 && self.next_pixel >= self.num_pixels
+    }
+
+    /// Process a result from worker `idx`, returns the points to draw unless the result is stale
+    pub fn handle_response(
+        &mut self,
+        idx: usize,
+        res: WorkerResponse,
+        stats: Option<&mut Stats>,
+    ) -> Option<&Points> {
+        if res.job != self.job {
+            return None;
+        }
+
+        self.pending -= 1;
+        self.dispatch(idx);
+
+        if let Some(stats) = stats {
+            stats.update_with_duration(res.iterations, res.values.len(), res.duration);
+        }
+
+        self.res
+            .set_span(res.start, self.width, &res.values, &res.angles);
+        Some(&self.res)
+    }
+
+    fn dispatch(&mut self, idx: usize) {
+        if self.next_pixel < self.num_pixels {
+            // a span has to fit into one batch of points for drawing
+            let count = (MAX_POINTS as u32).min(self.num_pixels - self.next_pixel);
+            self.workers[idx].send(WorkerRequest::Calculate {
+                start: self.next_pixel,
+                count,
+            });
+            self.next_pixel += count;
+            self.pending += 1;
+        }
+    }
+}
@@ -1,7 +1,15 @@
 mod canvas_element;
 mod control_panel;
+mod edit_attractor_cfg;
 mod edit_color_cfg;
+mod edit_flame_cfg;
+mod edit_hybrid_cfg;
+mod edit_ifs_cfg;
 mod edit_julia_cfg;
+mod edit_lambda_cfg;
+mod edit_lambda_julia_cfg;
 mod edit_mandelbrot_cfg;
+mod edit_mandelbulb_cfg;
+mod edit_quaternion_julia_cfg;
 mod modal;
 pub mod root;
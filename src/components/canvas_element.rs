@@ -1,7 +1,7 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use gloo::render::request_animation_frame;
+use gloo::render::{request_animation_frame, AnimationFrame};
 use gloo_timers::future::TimeoutFuture;
 use wasm_bindgen_futures::spawn_local;
 use web_sys::{HtmlCanvasElement, ImageData};
@@ -13,9 +13,15 @@ use crate::{
     agents::{
         canvas_msg_bus::{ControlMsgBus, ControlMsgRequest},
         command_msg_bus::{CanvasCmdMsgBus, CommandRequest},
+        fractal_worker::{WorkerPool, WorkerResponse},
     },
     components::root::Config,
-    work::{canvas::Canvas, fractal::FractalCalculator, stats::Stats},
+    work::{
+        canvas::Canvas,
+        fractal::{resolve_auto_iterations, Calculator},
+        stats::Stats,
+        tile_cache::TileCache,
+    },
 };
 
 const FPS_RESTRICTED_TIMER: bool = false;
@@ -23,15 +29,32 @@ const FPS_RESTRICTED_TIMER: bool = false;
 pub struct CanvasElement {
     event_bus: Dispatcher<ControlMsgBus>,
     mouse_drag: Option<MouseDrag>,
+    /// start of a mouse drag orbiting the camera of a 3D fractal
+    orbit_drag: Option<(u32, u32)>,
+    /// restart the fractal when the config of the orbited camera arrives
+    restart_pending: bool,
     canvas_ref: NodeRef,
     canvas: Option<Canvas>,
     _producer: Box<dyn Bridge<CanvasCmdMsgBus>>,
     // config: Config,
-    calculator: Option<FractalCalculator>,
+    calculator: Option<Calculator>,
+    worker_pool: Option<WorkerPool>,
+    tile_cache: Rc<RefCell<TileCache>>,
     stats: Option<Stats>,
     paused: bool,
     on_draw: Callback<()>,
     last_height: u32,
+    color_cycle: Option<ColorCycleState>,
+    /// copies the rows drawn since the last frame to the canvas
+    blit_frame: Option<AnimationFrame>,
+}
+
+/// The running palette cycling animation
+struct ColorCycleState {
+    offset: f32,
+    /// time stamp of the last frame in milliseconds
+    last_time: Option<f64>,
+    _frame: AnimationFrame,
 }
 
 impl Component for CanvasElement {
@@ -43,15 +66,21 @@ impl Component for CanvasElement {
         Self {
             event_bus: ControlMsgBus::dispatcher(),
             mouse_drag: None,
+            orbit_drag: None,
+            restart_pending: false,
             canvas_ref: NodeRef::default(),
             canvas: None,
             _producer: CanvasCmdMsgBus::bridge(ctx.link().callback(Msg::Command)),
             // config: ctx.props().config.clone(),
             calculator: None,
+            worker_pool: None,
+            tile_cache: Rc::new(RefCell::new(TileCache::default())),
             stats: None,
             paused: true,
             on_draw: ctx.link().callback(|_| Msg::OnDraw),
             last_height: 0,
+            color_cycle: None,
+            blit_frame: None,
         }
     }
 
@@ -90,6 +119,22 @@ impl Component for CanvasElement {
                 res
             }
             Msg::MouseUp(event) => {
+                if let Some(start) = self.orbit_drag.take() {
+                    if let Some(canvas) = self.canvas.as_ref() {
+                        let end = canvas
+                            .viewport_to_canvas_coords(event.client_x(), event.client_y())
+                            .expect("Failed to retrieve canvas coordinates");
+                        let delta = (
+                            i64::from(end.0) - i64::from(start.0),
+                            i64::from(end.1) - i64::from(start.1),
+                        );
+                        if delta != (0, 0) {
+                            self.restart_pending = true;
+                            ctx.props().cb_camera_orbit.emit(delta);
+                        }
+                    }
+                    return false;
+                }
                 let mut res = false;
                 if self.mouse_drag.is_some() {
                     if let Some(canvas) = self.canvas.as_ref() {
@@ -131,6 +176,14 @@ impl Component for CanvasElement {
                             image_data: None,
                         });
                     }
+                } else if ctx.props().config.active_config.is_3d() {
+                    if let Some(canvas) = self.canvas.as_ref() {
+                        self.orbit_drag = Some(
+                            canvas
+                                .viewport_to_canvas_coords(event.client_x(), event.client_y())
+                                .expect("Failed to retrieve canvas coordinates"),
+                        );
+                    }
                 }
                 false
             }
@@ -225,50 +278,97 @@ impl Component for CanvasElement {
                     "CanvasElement::update: Msg Received: Command: {:?}",
                     request
                 );
+                if !matches!(request, CommandRequest::StartCycling) {
+                    self.stop_cycling();
+                }
                 match request {
                     CommandRequest::Start => {
                         info!("CanvasElement::update: starting");
                         self.event_bus.send(ControlMsgRequest::FractalStarted);
 
+                        let config = resolve_auto_iterations(
+                            &ctx.props().config,
+                            ctx.props().canvas_width,
+                            ctx.props().canvas_height,
+                        );
+
                         if let Some(canvas) = self.canvas.as_mut() {
+                            canvas.set_config(&config);
                             canvas
                                 .clear_canvas(ctx.props().canvas_width, ctx.props().canvas_height);
                         }
 
                         if ctx.props().config.view_stats {
-                            self.stats = Some(Stats::new(
-                                ctx.props().canvas_width as usize
-                                    * ctx.props().canvas_height as usize,
-                            ));
+                            self.stats = Some(Stats::new(config.get_total_points(
+                                ctx.props().canvas_width,
+                                ctx.props().canvas_height,
+                            )));
                         }
 
-                        let mut calculator = FractalCalculator::new(
-                            &ctx.props().config,
-                            ctx.props().canvas_width,
-                            ctx.props().canvas_height,
-                        );
-
-                        let points = calculator.calculate(self.stats.as_mut());
-                        if let Some(stats) = self.stats.as_ref() {
-                            self.event_bus
-                                .send(ControlMsgRequest::FractalProgress(stats.format_stats()));
-                        }
-                        if let Some(canvas) = self.canvas.as_ref() {
-                            canvas.draw_results(points);
+                        let worker_count = WorkerPool::worker_count();
+                        if ctx.props().config.use_workers
+                            && worker_count > 1
+                            && Calculator::supports_workers(&config)
+                        {
+                            let on_worker_result = ctx.link().callback(Msg::WorkerResult);
+                            self.worker_pool
+                                .get_or_insert_with(|| {
+                                    WorkerPool::new(worker_count, &on_worker_result)
+                                })
+                                .start(
+                                    &config,
+                                    ctx.props().canvas_width,
+                                    ctx.props().canvas_height,
+                                );
+                            self.calculator = None;
+                        } else {
+                            self.calculator = Some(Calculator::new(
+                                &config,
+                                ctx.props().canvas_width,
+                                ctx.props().canvas_height,
+                                self.tile_cache.clone(),
+                            ));
+                            self.calculate_and_draw();
+                            self.send_draw_ev();
+                            self.request_blit(ctx);
                         }
-
-                        self.calculator = Some(calculator);
                         self.paused = false;
-                        self.send_draw_ev();
                         false
                     }
                     CommandRequest::Stop => {
+                        if let Some(worker_pool) = self.worker_pool.as_mut() {
+                            worker_pool.stop();
+                        }
                         self.paused = true;
                         self.event_bus.send(ControlMsgRequest::FractalPaused);
                         false
                     }
+                    CommandRequest::StartCycling => {
+                        info!("CanvasElement::update: CommandRequest::StartCycling");
+                        if self.paused
+                            && self
+                                .canvas
+                                .as_ref()
+                                .map_or(false, |canvas| canvas.has_retained())
+                        {
+                            self.color_cycle = Some(ColorCycleState {
+                                offset: 0.0,
+                                last_time: None,
+                                _frame: Self::request_cycle_frame(ctx),
+                            });
+                            self.event_bus.send(ControlMsgRequest::CyclingStarted);
+                        } else {
+                            error!("palette cycling needs a completed escape time fractal");
+                            self.event_bus.send(ControlMsgRequest::CyclingStopped);
+                        }
+                        false
+                    }
+                    CommandRequest::StopCycling => false,
                     CommandRequest::Clear => {
                         info!("CanvasElement::update: CommandRequest::Clear");
+                        if let Some(worker_pool) = self.worker_pool.as_mut() {
+                            worker_pool.stop();
+                        }
                         self.paused = true;
                         self.event_bus.send(ControlMsgRequest::FractalPaused);
                         if let Some(canvas) = self.canvas.as_mut() {
@@ -282,30 +382,83 @@ impl Component for CanvasElement {
             Msg::OnDraw => {
                 // info!("CanvasElement::update: OnDraw");
                 if !self.paused {
-                    if let Some(calculator) = self.calculator.as_mut() {
-                        let points = calculator.calculate(self.stats.as_mut());
-                        if let Some(stats) = self.stats.as_ref() {
-                            self.event_bus
-                                .send(ControlMsgRequest::FractalProgress(stats.format_stats()));
-                        }
-                        if let Some(canvas) = self.canvas.as_ref() {
-                            canvas.draw_results(points);
-                            // TODO: send stats
-                            if calculator.is_done() {
-                                // TODO: send notifications
-                                self.paused = true;
-                                self.event_bus.send(ControlMsgRequest::FractalPaused);
-                            } else {
-                                self.send_draw_ev();
+                    if self.calculator.is_some() {
+                        if self.calculate_and_draw() {
+                            // TODO: send notifications
+                            if let Some(canvas) = self.canvas.as_mut() {
+                                canvas.finish();
                             }
+                            self.paused = true;
+                            self.event_bus.send(ControlMsgRequest::FractalPaused);
+                        } else {
+                            self.send_draw_ev();
                         }
+                        self.request_blit(ctx);
                     }
                     true
                 } else {
                     false
                 }
             }
+            Msg::CycleFrame(time) => {
+                if let Some(cycle) = self.color_cycle.as_mut() {
+                    if let Some(last_time) = cycle.last_time {
+                        #[allow(clippy::cast_possible_truncation)]
+                        let seconds = ((time - last_time) / 1000.0) as f32;
+                        cycle.offset = ctx
+                            .props()
+                            .config
+                            .color_cycle
+                            .advance(cycle.offset, seconds);
+                    }
+                    cycle.last_time = Some(time);
+                    if let Some(canvas) = self.canvas.as_mut() {
+                        canvas.repaint(cycle.offset);
+                        canvas.blit();
+                    }
+                    cycle._frame = Self::request_cycle_frame(ctx);
+                }
+                false
+            }
+            Msg::WorkerResult((idx, res)) => {
+                if let Some(worker_pool) = self.worker_pool.as_mut() {
+                    if let Some(points) = worker_pool.handle_response(idx, res, self.stats.as_mut())
+                    {
+                        if let Some(canvas) = self.canvas.as_mut() {
+                            canvas.draw_results(points);
+                        }
+                        if let Some(stats) = self.stats.as_ref() {
+                            self.event_bus
+                                .send(ControlMsgRequest::FractalProgress(stats.format_stats()));
+                        }
+                    }
+                    if !self.paused && worker_pool.is_done() {
+                        if let Some(canvas) = self.canvas.as_mut() {
+                            canvas.finish();
+                        }
+                        self.paused = true;
+                        self.event_bus.send(ControlMsgRequest::FractalPaused);
+                    }
+                }
+                self.request_blit(ctx);
+                false
+            }
+            Msg::BlitFrame => {
+                self.blit_frame = None;
+                if let Some(canvas) = self.canvas.as_mut() {
+                    canvas.blit();
+                }
+                false
+            }
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        if self.restart_pending {
+            self.restart_pending = false;
+            ctx.link().send_message(Msg::Command(CommandRequest::Start));
         }
+        true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
@@ -341,8 +494,7 @@ impl Component for CanvasElement {
         if first_render {
             if let Some(canvas_el) = self.canvas_ref.cast::<HtmlCanvasElement>() {
                 info!("CanvasElement::rendered: Clear");
-                let mut canvas =
-                    Canvas::new(canvas_el, &ctx.props().config, ctx.props().canvas_width);
+                let mut canvas = Canvas::new(canvas_el, &ctx.props().config);
                 canvas.clear_canvas(ctx.props().canvas_width, ctx.props().canvas_height);
                 self.canvas = Some(canvas);
             }
@@ -359,6 +511,69 @@ impl Component for CanvasElement {
 }
 
 impl CanvasElement {
+    /// Calculate the next batch of the current calculator and draw it, returns true when the
+    /// fractal is complete
+    fn calculate_and_draw(&mut self) -> bool {
+        let calculator = match self.calculator.as_mut() {
+            Some(calculator) => calculator,
+            None => return true,
+        };
+
+        match calculator {
+            Calculator::EscapeTime(calculator) => {
+                let points = calculator.calculate(self.stats.as_mut());
+                if let Some(canvas) = self.canvas.as_mut() {
+                    canvas.draw_results(points);
+                }
+            }
+            Calculator::InverseIteration(calculator) => {
+                let points = calculator.calculate(self.stats.as_mut());
+                if let Some(canvas) = self.canvas.as_mut() {
+                    canvas.draw_points(points);
+                }
+            }
+            Calculator::Orbit(calculator) => {
+                let density = calculator.calculate(self.stats.as_mut());
+                if let Some(canvas) = self.canvas.as_mut() {
+                    canvas.draw_density(density);
+                }
+            }
+            Calculator::RayMarch(calculator) => {
+                let image = calculator.calculate(self.stats.as_mut());
+                if let Some(canvas) = self.canvas.as_mut() {
+                    canvas.draw_shaded(image);
+                }
+            }
+        }
+
+        if let Some(stats) = self.stats.as_ref() {
+            self.event_bus
+                .send(ControlMsgRequest::FractalProgress(stats.format_stats()));
+        }
+        calculator.is_done()
+    }
+
+    fn request_cycle_frame(ctx: &Context<Self>) -> AnimationFrame {
+        let callback = ctx.link().callback(Msg::CycleFrame);
+        request_animation_frame(move |time| callback.emit(time))
+    }
+
+    /// Copy the drawn rows to the canvas on the next animation frame, all batches drawn until
+    /// then are copied at once
+    fn request_blit(&mut self, ctx: &Context<Self>) {
+        if self.blit_frame.is_none() {
+            let callback = ctx.link().callback(|_| Msg::BlitFrame);
+            self.blit_frame = Some(request_animation_frame(move |_time| callback.emit(())));
+        }
+    }
+
+    /// Stop the palette cycling animation, dropping the requested frame cancels it
+    fn stop_cycling(&mut self) {
+        if self.color_cycle.take().is_some() {
+            self.event_bus.send(ControlMsgRequest::CyclingStopped);
+        }
+    }
+
     fn send_draw_ev(&self) {
         let callback = self.on_draw.clone();
         if FPS_RESTRICTED_TIMER {
@@ -388,6 +603,9 @@ pub enum Msg {
     TouchMove(TouchEvent),
     Command(CommandRequest),
     OnDraw,
+    CycleFrame(f64),
+    BlitFrame,
+    WorkerResult((usize, WorkerResponse)),
 }
 
 struct MouseDrag {
@@ -402,4 +620,6 @@ pub struct CanvasProps {
     pub edit_mode: bool,
     pub canvas_width: u32,
     pub canvas_height: u32,
+    /// mouse drag on a 3D fractal in canvas pixels
+    pub cb_camera_orbit: Callback<(i64, i64)>,
 }
@@ -11,8 +11,13 @@ use crate::{
         command_msg_bus::{CanvasCmdMsgBus, CommandRequest},
     },
     work::{
-        fractal::{FractalType, JuliaSetCfg, MandelbrotCfg},
-        util::set_value_on_txt_area_ref,
+        colors::{ColorCycle, Direction},
+        fractal::{
+            AttractorCfg, ColoringMode, FlameCfg, FractalType, HybridCfg, IfsCfg, JuliaSetCfg,
+            LambdaCfg, LambdaJuliaCfg, MandelbrotCfg, MandelbulbCfg, QuaternionJuliaCfg,
+            RenderMode,
+        },
+        util::{get_f64_from_ref, set_value_on_txt_area_ref},
     },
 };
 use gloo::timers::future::TimeoutFuture;
@@ -29,7 +34,14 @@ pub struct ControlPanel {
     clipboard_worker: Option<Box<dyn Bridge<ClipboardWorker>>>,
     paused: bool,
     type_sel_ref: NodeRef,
+    render_mode_sel_ref: NodeRef,
+    coloring_sel_ref: NodeRef,
+    cycle_speed_ref: NodeRef,
+    cycle_dir_sel_ref: NodeRef,
+    cycling: bool,
     view_stats_cb_ref: NodeRef,
+    use_workers_cb_ref: NodeRef,
+    stretch_palette_cb_ref: NodeRef,
     view_stats_txt_ref: NodeRef,
     _producer: Box<dyn Bridge<ControlMsgBus>>,
     no_copy: bool,
@@ -66,7 +78,14 @@ impl Component for ControlPanel {
             event_bus: None,
             paused: true,
             type_sel_ref: NodeRef::default(),
+            render_mode_sel_ref: NodeRef::default(),
+            coloring_sel_ref: NodeRef::default(),
+            cycle_speed_ref: NodeRef::default(),
+            cycle_dir_sel_ref: NodeRef::default(),
+            cycling: false,
             view_stats_cb_ref: NodeRef::default(),
+            use_workers_cb_ref: NodeRef::default(),
+            stretch_palette_cb_ref: NodeRef::default(),
             view_stats_txt_ref: NodeRef::default(),
             _producer: ControlMsgBus::bridge(ctx.link().callback(Msg::CanvasMsg)),
             no_copy,
@@ -164,7 +183,10 @@ impl Component for ControlPanel {
             }
             Msg::EditColors => {
                 info!("ControlPanel::EditColors");
-                false
+                if !ctx.props().edit_mode {
+                    ctx.props().on_edit_colors.emit(());
+                }
+                true
             }
             Msg::TypeChanged => {
                 info!("ControlPanel::TypeChanged");
@@ -177,6 +199,14 @@ impl Component for ControlPanel {
                 {
                     "type_mandelbrot" => Some(FractalType::Mandelbrot),
                     "type_julia_set" => Some(FractalType::JuliaSet),
+                    "type_lambda" => Some(FractalType::Lambda),
+                    "type_lambda_julia" => Some(FractalType::LambdaJulia),
+                    "type_hybrid" => Some(FractalType::Hybrid),
+                    "type_attractor" => Some(FractalType::Attractor),
+                    "type_ifs" => Some(FractalType::Ifs),
+                    "type_flame" => Some(FractalType::Flame),
+                    "type_mandelbulb" => Some(FractalType::Mandelbulb),
+                    "type_quaternion_julia" => Some(FractalType::QuaternionJulia),
                     val => {
                         error!("invalid fractal type '{}'", val);
                         None
@@ -192,6 +222,95 @@ impl Component for ControlPanel {
                 }
                 true
             }
+            Msg::RenderModeChanged => {
+                info!("ControlPanel::RenderModeChanged");
+                let render_mode = match self
+                    .render_mode_sel_ref
+                    .cast::<HtmlSelectElement>()
+                    .expect("Render mode select not found")
+                    .value()
+                    .as_str()
+                {
+                    "mode_scanline" => Some(RenderMode::Scanline),
+                    "mode_progressive" => Some(RenderMode::Progressive),
+                    "mode_tiled" => Some(RenderMode::Tiled),
+                    val => {
+                        error!("invalid render mode '{}'", val);
+                        None
+                    }
+                };
+
+                if let Some(render_mode) = render_mode {
+                    ctx.props().on_render_mode_changed.emit(render_mode)
+                }
+                true
+            }
+            Msg::ColoringChanged => {
+                info!("ControlPanel::ColoringChanged");
+                let coloring = match self
+                    .coloring_sel_ref
+                    .cast::<HtmlSelectElement>()
+                    .expect("Coloring select not found")
+                    .value()
+                    .as_str()
+                {
+                    "coloring_iterations" => Some(ColoringMode::Iterations),
+                    "coloring_binary" => Some(ColoringMode::BinaryDecomposition),
+                    "coloring_field_lines" => Some(ColoringMode::FieldLines),
+                    "coloring_histogram" => Some(ColoringMode::Histogram),
+                    val => {
+                        error!("invalid coloring '{}'", val);
+                        None
+                    }
+                };
+
+                if let Some(coloring) = coloring {
+                    ctx.props().on_coloring_changed.emit(coloring)
+                }
+                true
+            }
+            Msg::Cycle => {
+                info!("ControlPanel::Cycle");
+                let request = if self.cycling {
+                    CommandRequest::StopCycling
+                } else {
+                    CommandRequest::StartCycling
+                };
+                self.event_bus
+                    .as_mut()
+                    .expect("Eventbus not initialized")
+                    .send(request);
+                false
+            }
+            Msg::ColorCycleChanged => {
+                info!("ControlPanel::ColorCycleChanged");
+                let color_cycle = ctx.props().color_cycle;
+                let speed = get_f64_from_ref(&self.cycle_speed_ref, "cycle_speed").map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        color_cycle.speed
+                    },
+                    |v| v as f32,
+                );
+                let dir = match self
+                    .cycle_dir_sel_ref
+                    .cast::<HtmlSelectElement>()
+                    .expect("Cycle direction select not found")
+                    .value()
+                    .as_str()
+                {
+                    "cycle_forward" => Direction::Positive,
+                    "cycle_backward" => Direction::Negative,
+                    val => {
+                        error!("invalid cycle direction '{}'", val);
+                        color_cycle.dir
+                    }
+                };
+                ctx.props()
+                    .on_color_cycle_changed
+                    .emit(ColorCycle { speed, dir });
+                true
+            }
             Msg::ViewStatsChanged => {
                 info!("ControlPanel::ViewStatsChanged");
                 let checked = self
@@ -202,6 +321,26 @@ impl Component for ControlPanel {
                 ctx.props().on_view_stats_changed.emit(checked);
                 true
             }
+            Msg::StretchPaletteChanged => {
+                info!("ControlPanel::StretchPaletteChanged");
+                let checked = self
+                    .stretch_palette_cb_ref
+                    .cast::<HtmlInputElement>()
+                    .expect("Stretch palette checkbox not found")
+                    .checked();
+                ctx.props().on_stretch_palette_changed.emit(checked);
+                true
+            }
+            Msg::UseWorkersChanged => {
+                info!("ControlPanel::UseWorkersChanged");
+                let checked = self
+                    .use_workers_cb_ref
+                    .cast::<HtmlInputElement>()
+                    .expect("Use workers checkbox not found")
+                    .checked();
+                ctx.props().on_use_workers_changed.emit(checked);
+                true
+            }
             Msg::CanvasMsg(canvas_msg) => {
                 // TODO: implement
                 match canvas_msg {
@@ -213,6 +352,14 @@ impl Component for ControlPanel {
                         self.paused = true;
                         true
                     }
+                    ControlMsgRequest::CyclingStarted => {
+                        self.cycling = true;
+                        true
+                    }
+                    ControlMsgRequest::CyclingStopped => {
+                        self.cycling = false;
+                        true
+                    }
                     ControlMsgRequest::FractalProgress(msg) => {
                         set_value_on_txt_area_ref(
                             &self.view_stats_txt_ref,
@@ -237,6 +384,32 @@ impl Component for ControlPanel {
         let sel_type = match ctx.props().config {
             PanelConfig::ConfigMandelbrot(_) => "type_mandelbrot",
             PanelConfig::ConfigJuliaSet(_) => "type_julia_set",
+            PanelConfig::ConfigLambda(_) => "type_lambda",
+            PanelConfig::ConfigLambdaJulia(_) => "type_lambda_julia",
+            PanelConfig::ConfigHybrid(_) => "type_hybrid",
+            PanelConfig::ConfigAttractor(_) => "type_attractor",
+            PanelConfig::ConfigIfs(_) => "type_ifs",
+            PanelConfig::ConfigFlame(_) => "type_flame",
+            PanelConfig::ConfigMandelbulb(_) => "type_mandelbulb",
+            PanelConfig::ConfigQuaternionJulia(_) => "type_quaternion_julia",
+        };
+
+        let sel_mode = match ctx.props().render_mode {
+            RenderMode::Scanline => "mode_scanline",
+            RenderMode::Progressive => "mode_progressive",
+            RenderMode::Tiled => "mode_tiled",
+        };
+
+        let sel_coloring = match ctx.props().coloring {
+            ColoringMode::Iterations => "coloring_iterations",
+            ColoringMode::BinaryDecomposition => "coloring_binary",
+            ColoringMode::FieldLines => "coloring_field_lines",
+            ColoringMode::Histogram => "coloring_histogram",
+        };
+
+        let sel_cycle_dir = match ctx.props().color_cycle.dir {
+            Direction::Positive => "cycle_forward",
+            Direction::Negative => "cycle_backward",
         };
 
         info!("ControlPanel::view initial type {}", sel_type);
@@ -247,7 +420,13 @@ impl Component for ControlPanel {
         let on_copy = ctx.link().callback(|_| Msg::Copy);
         let on_edit_colors = ctx.link().callback(|_| Msg::EditColors);
         let on_type_changed = ctx.link().callback(|_| Msg::TypeChanged);
+        let on_render_mode_changed = ctx.link().callback(|_| Msg::RenderModeChanged);
+        let on_coloring_changed = ctx.link().callback(|_| Msg::ColoringChanged);
+        let on_cycle = ctx.link().callback(|_| Msg::Cycle);
+        let on_color_cycle_changed = ctx.link().callback(|_| Msg::ColorCycleChanged);
         let on_view_stats_changed = ctx.link().callback(|_| Msg::ViewStatsChanged);
+        let on_use_workers_changed = ctx.link().callback(|_| Msg::UseWorkersChanged);
+        let on_stretch_palette_changed = ctx.link().callback(|_| Msg::StretchPaletteChanged);
 
         html![
             <div class="button_cntr">
@@ -291,7 +470,78 @@ impl Component for ControlPanel {
                     >
                     <option value="type_mandelbrot" selected={sel_type=="type_mandelbrot"}>{"Mandelbrot Set"}</option>
                     <option value="type_julia_set" selected={sel_type=="type_julia_set"}>{"Julia Set"}</option>
+                    <option value="type_lambda" selected={sel_type=="type_lambda"}>{"Lambda Set"}</option>
+                    <option value="type_lambda_julia" selected={sel_type=="type_lambda_julia"}>{"Lambda Julia Set"}</option>
+                    <option value="type_hybrid" selected={sel_type=="type_hybrid"}>{"Hybrid Fractal"}</option>
+                    <option value="type_attractor" selected={sel_type=="type_attractor"}>{"Strange Attractor"}</option>
+                    <option value="type_ifs" selected={sel_type=="type_ifs"}>{"Iterated Function System"}</option>
+                    <option value="type_flame" selected={sel_type=="type_flame"}>{"Fractal Flame"}</option>
+                    <option value="type_mandelbulb" selected={sel_type=="type_mandelbulb"}>{"Mandelbulb"}</option>
+                    <option value="type_quaternion_julia" selected={sel_type=="type_quaternion_julia"}>{"Quaternion Julia Set"}</option>
+                </select>
+                <label class="type_select_label" for="render_mode_select">
+                    {"Render Mode"}
+                </label>
+                <select class="type_select" id="render_mode_select" name="render_mode_select" value={sel_mode}
+                    disabled={!self.paused || ctx.props().edit_mode } onchange={on_render_mode_changed}
+                    ref={self.render_mode_sel_ref.clone()}
+                    >
+                    <option value="mode_scanline" selected={sel_mode=="mode_scanline"}>{"Scanline"}</option>
+                    <option value="mode_progressive" selected={sel_mode=="mode_progressive"}>{"Progressive"}</option>
+                    <option value="mode_tiled" selected={sel_mode=="mode_tiled"}>{"Tiled (Cached)"}</option>
                 </select>
+                <label class="type_select_label" for="coloring_select">
+                    {"Coloring"}
+                </label>
+                <select class="type_select" id="coloring_select" name="coloring_select" value={sel_coloring}
+                    disabled={!self.paused || ctx.props().edit_mode } onchange={on_coloring_changed}
+                    ref={self.coloring_sel_ref.clone()}
+                    >
+                    <option value="coloring_iterations" selected={sel_coloring=="coloring_iterations"}>{"Iterations"}</option>
+                    <option value="coloring_binary" selected={sel_coloring=="coloring_binary"}>{"Binary Decomposition"}</option>
+                    <option value="coloring_field_lines" selected={sel_coloring=="coloring_field_lines"}>{"Field Lines"}</option>
+                    <option value="coloring_histogram" selected={sel_coloring=="coloring_histogram"}>{"Histogram"}</option>
+                </select>
+                <button class="menu_button" id="cycle" onclick={on_cycle}
+                        disabled={ !self.paused || ctx.props().edit_mode }>
+                    { if self.cycling { "Stop Cycling" } else { "Cycle Colors" } }
+                </button>
+                <label class="type_select_label" for="cycle_speed">
+                    {"Cycle Speed"}
+                </label>
+                <input class="type_select" id="cycle_speed" name="cycle_speed" type="range"
+                    min="0.01" max="1" step="0.01" value={ctx.props().color_cycle.speed.to_string()}
+                    onchange={on_color_cycle_changed.clone()} ref={self.cycle_speed_ref.clone()}
+                />
+                <label class="type_select_label" for="cycle_dir_select">
+                    {"Cycle Direction"}
+                </label>
+                <select class="type_select" id="cycle_dir_select" name="cycle_dir_select" value={sel_cycle_dir}
+                    onchange={on_color_cycle_changed} ref={self.cycle_dir_sel_ref.clone()}
+                    >
+                    <option value="cycle_forward" selected={sel_cycle_dir=="cycle_forward"}>{"Forward"}</option>
+                    <option value="cycle_backward" selected={sel_cycle_dir=="cycle_backward"}>{"Backward"}</option>
+                </select>
+                <div class="cb_stats_cntr">
+                    <label class="type_select_label" for="stretch_palette_cb">
+                        {"Stretch Palette"}
+                    </label>
+                    <input class="stats_cb" id="stretch_palette_cb" name="stretch_palette_cb" type="checkbox"
+                        disabled={!self.paused} checked={ctx.props().stretch_palette}
+                        onchange={on_stretch_palette_changed}
+                        ref={self.stretch_palette_cb_ref.clone()}
+                    />
+                </div>
+                <div class="cb_stats_cntr">
+                    <label class="type_select_label" for="workers_cb">
+                        {"Use Workers"}
+                    </label>
+                    <input class="stats_cb" id="workers_cb" name="workers_cb" type="checkbox"
+                        disabled={!self.paused} checked={ctx.props().use_workers}
+                        onchange={on_use_workers_changed}
+                        ref={self.use_workers_cb_ref.clone()}
+                    />
+                </div>
                 <div class="cb_stats_cntr">
                     <label class="type_select_label" for="stats_cb">
                         {"View Stats"}
@@ -329,7 +579,13 @@ pub enum Msg {
     CopyStart,
     EditColors,
     TypeChanged,
+    RenderModeChanged,
+    ColoringChanged,
+    Cycle,
+    ColorCycleChanged,
     ViewStatsChanged,
+    UseWorkersChanged,
+    StretchPaletteChanged,
     CanvasMsg(ControlMsgRequest),
     ClipboardRes(WorkerStatus),
 }
@@ -338,10 +594,21 @@ pub enum Msg {
 pub struct ControlPanelProps {
     pub config: PanelConfig,
     pub view_stats: bool,
+    pub render_mode: RenderMode,
+    pub coloring: ColoringMode,
+    pub use_workers: bool,
+    pub stretch_palette: bool,
     pub edit_mode: bool,
     pub on_type_changed: Callback<FractalType>,
+    pub on_render_mode_changed: Callback<RenderMode>,
+    pub on_coloring_changed: Callback<ColoringMode>,
+    pub color_cycle: ColorCycle,
+    pub on_color_cycle_changed: Callback<ColorCycle>,
     pub on_edit: Callback<()>,
+    pub on_edit_colors: Callback<()>,
     pub on_view_stats_changed: Callback<bool>,
+    pub on_use_workers_changed: Callback<bool>,
+    pub on_stretch_palette_changed: Callback<bool>,
     pub on_ctc_active: Callback<bool>,
     pub on_ctc_done: Callback<WorkerStatus>,
 }
@@ -349,5 +616,13 @@ pub struct ControlPanelProps {
 #[derive(PartialEq, Clone)]
 pub enum PanelConfig {
     ConfigJuliaSet(JuliaSetCfg),
+    ConfigLambda(LambdaCfg),
+    ConfigLambdaJulia(LambdaJuliaCfg),
+    ConfigHybrid(HybridCfg),
     ConfigMandelbrot(MandelbrotCfg),
+    ConfigAttractor(AttractorCfg),
+    ConfigIfs(IfsCfg),
+    ConfigFlame(FlameCfg),
+    ConfigMandelbulb(MandelbulbCfg),
+    ConfigQuaternionJulia(QuaternionJuliaCfg),
 }
@@ -0,0 +1,368 @@
+use web_sys::Element;
+use yew::prelude::*;
+
+use crate::agents::canvas_msg_bus::{ControlMsgBus, ControlMsgRequest};
+use crate::work::{
+    complex::Complex,
+    fractal::{AttractorCfg, AttractorType, ATTRACTOR_DEFAULT_POINTS},
+    util::{get_f64_from_ref, get_select_value_from_ref, get_u32_from_ref, set_value_on_input_ref},
+};
+use yew_agent::{Bridge, Bridged};
+
+#[allow(clippy::enum_variant_names)]
+pub enum Msg {
+    ResetParams,
+    ZoomOut,
+    ResetArea,
+    SaveConfig,
+    Cancel,
+    CanvasMsg(ControlMsgRequest),
+}
+
+pub struct EditAttractorCfg {
+    container_ref: NodeRef,
+    type_ref: NodeRef,
+    a_ref: NodeRef,
+    b_ref: NodeRef,
+    c_ref: NodeRef,
+    d_ref: NodeRef,
+    points_ref: NodeRef,
+    x_min_real_ref: NodeRef,
+    x_min_imag_ref: NodeRef,
+    x_max_real_ref: NodeRef,
+    x_max_imag_ref: NodeRef,
+    _producer: Box<dyn Bridge<ControlMsgBus>>,
+}
+
+impl EditAttractorCfg {
+    fn get_attractor_type(&self, ctx: &Context<Self>) -> AttractorType {
+        match get_select_value_from_ref(&self.type_ref, "attractor_type")
+            .map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    String::new()
+                },
+                |v| v,
+            )
+            .as_str()
+        {
+            "attractor_clifford" => AttractorType::Clifford,
+            "attractor_de_jong" => AttractorType::DeJong,
+            "attractor_hopalong" => AttractorType::Hopalong,
+            val => {
+                error!("invalid attractor type '{}'", val);
+                ctx.props().config.attractor
+            }
+        }
+    }
+
+    fn get_f64(node_ref: &NodeRef, name: &str, default: f64) -> f64 {
+        get_f64_from_ref(node_ref, name).map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+                default
+            },
+            |v| v,
+        )
+    }
+
+    fn set_values(&self, values: &[(&NodeRef, &str, f64)]) {
+        for (node_ref, name, value) in values {
+            set_value_on_input_ref(node_ref, name, value.to_string().as_str()).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+    }
+
+    fn set_area(&self, x_min: &Complex, x_max: &Complex) {
+        self.set_values(&[
+            (&self.x_max_real_ref, "x_max_real", x_max.real()),
+            (&self.x_max_imag_ref, "x_max_imag", x_max.imag()),
+            (&self.x_min_real_ref, "x_min_real", x_min.real()),
+            (&self.x_min_imag_ref, "x_min_imag", x_min.imag()),
+        ]);
+    }
+}
+
+impl Component for EditAttractorCfg {
+    type Message = Msg;
+    type Properties = EditAttractorCfgProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        EditAttractorCfg {
+            container_ref: NodeRef::default(),
+            type_ref: NodeRef::default(),
+            a_ref: NodeRef::default(),
+            b_ref: NodeRef::default(),
+            c_ref: NodeRef::default(),
+            d_ref: NodeRef::default(),
+            points_ref: NodeRef::default(),
+            x_max_real_ref: NodeRef::default(),
+            x_max_imag_ref: NodeRef::default(),
+            x_min_real_ref: NodeRef::default(),
+            x_min_imag_ref: NodeRef::default(),
+            _producer: ControlMsgBus::bridge(ctx.link().callback(Msg::CanvasMsg)),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let config = &ctx.props().config;
+        match msg {
+            Msg::Cancel => {
+                info!("EditAttractorCfg: got msg Cancel");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+                ctx.props().cb_canceled.emit(());
+                false
+            }
+            Msg::SaveConfig => {
+                info!("EditAttractorCfg: got msg SaveConfig");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+
+                let max_points = get_u32_from_ref(&self.points_ref, "points").map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        config.max_points
+                    },
+                    |v| v,
+                );
+
+                ctx.props().cb_saved.emit(AttractorCfg {
+                    attractor: self.get_attractor_type(ctx),
+                    a: Self::get_f64(&self.a_ref, "a", config.a),
+                    b: Self::get_f64(&self.b_ref, "b", config.b),
+                    c: Self::get_f64(&self.c_ref, "c", config.c),
+                    d: Self::get_f64(&self.d_ref, "d", config.d),
+                    x_max: Complex::new(
+                        Self::get_f64(&self.x_max_real_ref, "x_max_real", config.x_max.real()),
+                        Self::get_f64(&self.x_max_imag_ref, "x_max_imag", config.x_max.imag()),
+                    ),
+                    x_min: Complex::new(
+                        Self::get_f64(&self.x_min_real_ref, "x_min_real", config.x_min.real()),
+                        Self::get_f64(&self.x_min_imag_ref, "x_min_imag", config.x_min.imag()),
+                    ),
+                    max_points,
+                    color_cfg_name: config.color_cfg_name.clone(),
+                });
+                false
+            }
+            Msg::ResetArea => {
+                info!("EditAttractorCfg: got msg ResetArea");
+                let (_, x_min, x_max) = self.get_attractor_type(ctx).defaults();
+                self.set_area(&x_min, &x_max);
+                false
+            }
+            Msg::ZoomOut => {
+                info!("EditAttractorCfg: got msg ZoomOut");
+                let center = (config.x_max + config.x_min) * 0.5;
+                self.set_area(
+                    &(config.x_min - (center - config.x_min)),
+                    &(config.x_max + (config.x_max - center)),
+                );
+                false
+            }
+            Msg::ResetParams => {
+                info!("EditAttractorCfg: got msg ResetParams");
+                let ([a, b, c, d], x_min, x_max) = self.get_attractor_type(ctx).defaults();
+                self.set_values(&[
+                    (&self.a_ref, "a", a),
+                    (&self.b_ref, "b", b),
+                    (&self.c_ref, "c", c),
+                    (&self.d_ref, "d", d),
+                    (
+                        &self.points_ref,
+                        "points",
+                        f64::from(ATTRACTOR_DEFAULT_POINTS),
+                    ),
+                ]);
+                self.set_area(&x_min, &x_max);
+                false
+            }
+            Msg::CanvasMsg(canvas_msg) => {
+                info!("EditAttractorCfg: got msg CanvasMsg");
+                match canvas_msg {
+                    ControlMsgRequest::CanvasSelectMsg(coords) if ctx.props().edit_mode => {
+                        let x_scale = (config.x_max.real() - config.x_min.real())
+                            / f64::from(ctx.props().canvas_width);
+                        let y_scale = (config.x_max.imag() - config.x_min.imag())
+                            / f64::from(ctx.props().canvas_height);
+
+                        // the y axis of attractors points up, the top row is x_max.imag
+                        self.set_area(
+                            &Complex::new(
+                                config.x_min.real() + x_scale * f64::from(coords.0),
+                                config.x_max.imag() - y_scale * f64::from(coords.3),
+                            ),
+                            &Complex::new(
+                                config.x_min.real() + x_scale * f64::from(coords.2),
+                                config.x_max.imag() - y_scale * f64::from(coords.1),
+                            ),
+                        );
+                        false
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let reset_area = ctx.link().callback(|_| Msg::ResetArea);
+        let reset_params = ctx.link().callback(|_| Msg::ResetParams);
+        let zoom_out = ctx.link().callback(|_| Msg::ZoomOut);
+        let save_config = ctx.link().callback(|_| Msg::SaveConfig);
+        let cancel = ctx.link().callback(|_| Msg::Cancel);
+        let cntr_class = if ctx.props().edit_mode {
+            "edit_cntr_visible"
+        } else {
+            "edit_cntr_hidden"
+        };
+
+        let config = &ctx.props().config;
+        let sel_type = match config.attractor {
+            AttractorType::Clifford => "attractor_clifford",
+            AttractorType::DeJong => "attractor_de_jong",
+            AttractorType::Hopalong => "attractor_hopalong",
+        };
+
+        html![
+            <div class={cntr_class} id="attractor_edit_cntr" ref={self.container_ref.clone()}>
+                <div class="input_cntr">
+                    <p class="hint_text">
+                        {"Hint: You can select a rectangle in the draw area to import the coordiates into the editor. \
+                          Reset to Default loads the parameters of the selected type."}
+                    </p>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="attractor_type">
+                            {"Type"}
+                        </label>
+                        <select class="input" id="attractor_type" name="attractor_type"
+                            ref={self.type_ref.clone()}>
+                            <option value="attractor_clifford"
+                                selected={sel_type=="attractor_clifford"}>{"Clifford"}</option>
+                            <option value="attractor_de_jong"
+                                selected={sel_type=="attractor_de_jong"}>{"de Jong"}</option>
+                            <option value="attractor_hopalong"
+                                selected={sel_type=="attractor_hopalong"}>{"Hopalong"}</option>
+                        </select>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="attractor_points">
+                            {"Points"}
+                        </label>
+                        <input class="input" id="attractor_points" name="attractor_points"
+                            type="number" min="1000" max="4000000000" ref={self.points_ref.clone()}
+                            value={config.max_points.to_string()}/>
+                    </div>
+                    <button class="editor_button" id="attractor_reset_params" onclick={reset_params}>
+                        {"Reset to Default"}
+                    </button>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="attractor_a">{"a"}</label>
+                        <input class="input" id="attractor_a" name="attractor_a"
+                            type="number" step="0.0000001" ref={self.a_ref.clone()}
+                            value={config.a.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="attractor_b">{"b"}</label>
+                        <input class="input" id="attractor_b" name="attractor_b"
+                            type="number" step="0.0000001" ref={self.b_ref.clone()}
+                            value={config.b.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="attractor_c">{"c"}</label>
+                        <input class="input" id="attractor_c" name="attractor_c"
+                            type="number" step="0.0000001" ref={self.c_ref.clone()}
+                            value={config.c.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="attractor_d">{"d"}</label>
+                        <input class="input" id="attractor_d" name="attractor_d"
+                            type="number" step="0.0000001" ref={self.d_ref.clone()}
+                            value={config.d.to_string()}/>
+                    </div>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <div class="area_cntr">
+                            <div class="input_inner">
+                                <label class="input_label" for="attractor_max_real">
+                                    {"X Max."}
+                                </label>
+                                <input class="input" id="attractor_max_real" name="attractor_max_real"
+                                    type="number" step="0.0000001" ref={self.x_max_real_ref.clone()}
+                                    value={config.x_max.real().to_string()}/>
+                            </div>
+                            <div class="input_inner">
+                                <label class="input_label" for="attractor_min_real">
+                                    {"X Min."}
+                                </label>
+                                <input class="input" id="attractor_min_real" name="attractor_min_real"
+                                    type="number" step="0.0000001" ref={self.x_min_real_ref.clone()}
+                                    value={config.x_min.real().to_string()}/>
+                            </div>
+                        </div>
+                        <div class="area_cntr">
+                            <div class="input_inner">
+                                <label class="input_label" for="attractor_max_imag">
+                                    {"Y Max."}
+                                </label>
+                                <input class="input" id="attractor_max_imag" name="attractor_max_imag"
+                                    type="number" step="0.0000001" ref={self.x_max_imag_ref.clone()}
+                                    value={config.x_max.imag().to_string()}/>
+                            </div>
+                            <div class="input_inner">
+                                <label class="input_label" for="attractor_min_imag">
+                                    {"Y Min."}
+                                </label>
+                                <input class="input" id="attractor_min_imag" name="attractor_min_imag"
+                                    type="number" step="0.0000001" ref={self.x_min_imag_ref.clone()}
+                                    value={config.x_min.imag().to_string()}/>
+                            </div>
+                        </div>
+                        <div class="area_cntr">
+                            <button class="editor_button" id="attractor_reset_area"
+                                    onclick={reset_area}>
+                                {"Reset to Default"}
+                            </button>
+                            <button class="editor_button" id="attractor_zoom_out"
+                                    onclick={zoom_out}>
+                                {"Zoom Out"}
+                            </button>
+                        </div>
+                    </div>
+                </div>
+                <div class="edit_button_cntr">
+                    <button class="editor_button" id="attractor_save" onclick={save_config}>
+                        {"Save"}
+                    </button>
+                    <button class="editor_button" id="attractor_cancel" onclick={cancel}>
+                        {"Cancel"}
+                    </button>
+                </div>
+            </div>
+        ]
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct EditAttractorCfgProps {
+    pub edit_mode: bool,
+    pub config: AttractorCfg,
+    pub canvas_width: u32,
+    pub canvas_height: u32,
+    pub cb_saved: Callback<AttractorCfg>,
+    pub cb_canceled: Callback<()>,
+}
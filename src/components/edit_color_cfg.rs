@@ -1,20 +1,1038 @@
-use crate::work::colors::{ColorRange, HslRange};
+use super::modal::{ModalMsg, ModalOk};
+use crate::work::colors::{
+    ColorRange, Direction, HslColor, HslRange, OkLabColor, OkLabRange, OkLchColor, OkLchRange,
+    RgbColor, RgbRange,
+};
+use crate::work::gradient::{ColorStop, Gradient, Interpolation, RepeatMode};
+use crate::work::palette_file::{parse_palette_file, PALETTE_FILE_TYPES};
+use crate::work::palette_library::{
+    ConflictMode, PaletteLibrary, PALETTE_LIBRARY_FILE_NAME, PALETTE_LIBRARY_FILE_TYPE,
+};
+use crate::work::transfer::{Transfer, TransferFunction};
+use crate::work::util::{get_string_from_ref, set_value_on_input_ref};
+use gloo_file::callbacks::FileReader;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use wasm_bindgen::JsCast;
+use web_sys::{window, Element, FileList, HtmlElement, HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 use yew::{Component, Context, Html};
 
+/// Number of colors sampled from a palette for the gradient preview
+const PREVIEW_STEPS: usize = 32;
+
+/// Labels of the colors of a range, the index is used in `Msg::ChannelChanged`
+const ENDPOINTS: [&str; 2] = ["Start", "End"];
+
+/// Label, min, max and step of the inputs for the channels of a HSL color
+const HSL_CHANNELS: [(&str, f32, f32, f32); 3] = [
+    ("Hue", 0.0, 359.0, 1.0),
+    ("Saturation", 0.0, 1.0, 0.01),
+    ("Lightness", 0.0, 1.0, 0.01),
+];
+
+/// Label, min, max and step of the inputs for the channels of a RGB color
+const RGB_CHANNELS: [(&str, f32, f32, f32); 3] = [
+    ("Red", 0.0, 255.0, 1.0),
+    ("Green", 0.0, 255.0, 1.0),
+    ("Blue", 0.0, 255.0, 1.0),
+];
+
+/// Label, min, max and step of the inputs for the channels of an OKLab color
+const OKLAB_CHANNELS: [(&str, f32, f32, f32); 3] = [
+    ("Lightness", 0.0, 1.0, 0.01),
+    ("Green-Red", -0.4, 0.4, 0.01),
+    ("Blue-Yellow", -0.4, 0.4, 0.01),
+];
+
+/// Label, min, max and step of the inputs for the channels of an OKLCh color
+const OKLCH_CHANNELS: [(&str, f32, f32, f32); 3] = [
+    ("Lightness", 0.0, 1.0, 0.01),
+    ("Chroma", 0.0, 0.4, 0.01),
+    ("Hue", 0.0, 359.0, 1.0),
+];
+
+const KIND_HSL: &str = "kind_hsl";
+const KIND_RGB: &str = "kind_rgb";
+const KIND_GRADIENT: &str = "kind_gradient";
+const KIND_OKLAB: &str = "kind_oklab";
+const KIND_OKLCH: &str = "kind_oklch";
+
+/// Number of stops of a gradient made from a HSL or RGB range
+const GRADIENT_SAMPLES: usize = 7;
+/// A gradient has a start and an end
+const MIN_STOPS: usize = 2;
+
+/// Value of the palette select option that leaves the active fractal on the default palette
+const NO_PALETTE: &str = "";
+
+const NEW_PALETTE_NAME: &str = "new palette";
+
+pub enum Msg {
+    Select(String),
+    AddPalette,
+    DuplicatePalette,
+    RenamePalette,
+    DeletePalette,
+    KindChanged(String),
+    DirChanged(String),
+    ChannelChanged(usize, usize, String),
+    StopPositionChanged(usize, String),
+    StopColorChanged(usize, String),
+    StopInterpolationChanged(usize, String),
+    AddStop,
+    RemoveStop(usize),
+    RepeatChanged(String),
+    ScaleChanged(String),
+    AssignedChanged(String),
+    TransferFunctionChanged(String),
+    DensityChanged(String),
+    PeriodChanged(String),
+    OffsetChanged(String),
+    ImportFiles(Option<FileList>),
+    FileLoaded(String, Result<String, String>),
+    ImportErrorsOk,
+    ConflictModeChanged(String),
+    ExportToggled(String),
+    ExportPalettes,
+    SaveConfig,
+    Cancel,
+}
+
 pub struct EditColorConfig {
     container_ref: NodeRef,
+    name_ref: NodeRef,
+    palettes: ColorCfg,
+    selected: String,
+    assigned: Option<String>,
+    /// The transfer function of the active fractal, `None` if it is not an escape time fractal
+    transfer: Option<Transfer>,
+    /// Readers of the palette files being imported, dropping them aborts the import
+    readers: Vec<FileReader>,
+    loaded_files: usize,
+    import_errors: Vec<String>,
+    conflict_mode: ConflictMode,
+    /// Palettes left out of the export, new palettes are exported by default
+    not_exported: BTreeSet<String>,
+}
+
+impl EditColorConfig {
+    fn dir_value(dir: Direction) -> String {
+        format!("dir_{:?}", dir).to_lowercase()
+    }
+
+    fn interpolation_value(interpolation: Interpolation) -> String {
+        format!("interpolation_{:?}", interpolation).to_lowercase()
+    }
+
+    fn conflict_value(mode: ConflictMode) -> String {
+        format!("conflict_{:?}", mode).to_lowercase()
+    }
+
+    fn repeat_value(repeat: RepeatMode) -> String {
+        format!("repeat_{:?}", repeat).to_lowercase()
+    }
+
+    fn transfer_value(function: TransferFunction) -> String {
+        format!("transfer_{:?}", function).to_lowercase()
+    }
+
+    /// The palette selected initially, the one of the active fractal if it exists
+    fn initial_selection(ctx: &Context<Self>) -> String {
+        let config = &ctx.props().config;
+        ctx.props()
+            .palette
+            .as_ref()
+            .filter(|name| config.get(name.as_str()).is_some())
+            .or_else(|| config.names().next())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn reset(&mut self, ctx: &Context<Self>) {
+        self.palettes = ctx.props().config.clone();
+        self.selected = Self::initial_selection(ctx);
+        self.assigned = ctx.props().palette.clone();
+        self.transfer = ctx.props().transfer;
+    }
+
+    fn selected_range(&self) -> ColorRange {
+        self.palettes
+            .get(self.selected.as_str())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn select(&mut self, name: String) {
+        set_value_on_input_ref(&self.name_ref, "palette_name", name.as_str()).map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+            },
+            |v| v,
+        );
+        self.selected = name;
+    }
+
+    /// Add an imported palette as the conflict mode says and select it
+    fn import(&mut self, name: String, palette: ColorRange) {
+        match self
+            .palettes
+            .import(name.clone(), palette, self.conflict_mode)
+        {
+            Some(name) => self.select(name),
+            None => info!("EditColorConfig: skipped palette '{}'", name),
+        }
+    }
+
+    fn import_file(&mut self, file_name: &str, content: &str) -> Result<(), Vec<String>> {
+        if file_name
+            .to_lowercase()
+            .ends_with(PALETTE_LIBRARY_FILE_TYPE)
+        {
+            let library = PaletteLibrary::from_json(content)
+                .map_err(|err| vec![format!("{}: {}", file_name, err)])?;
+            for (name, palette) in library.into_palettes() {
+                self.import(name, palette);
+            }
+        } else {
+            let palette = parse_palette_file(file_name, content)?;
+            self.import(palette.name, palette.range);
+        }
+        Ok(())
+    }
+
+    /// Offer the palettes checked for export as download
+    fn export(&self) -> Result<(), String> {
+        let json = self
+            .palettes
+            .library(
+                self.palettes
+                    .names()
+                    .filter(|name| !self.not_exported.contains(*name)),
+            )
+            .to_json()?;
+        let url = format!(
+            "data:application/json;charset=utf-8,{}",
+            js_sys::encode_uri_component(json.as_str())
+        );
+        let anchor = window()
+            .and_then(|window| window.document())
+            .ok_or_else(|| "html document not found".to_owned())?
+            .create_element("a")
+            .map_err(|err| format!("failed to create download link: {:?}", err))?;
+        anchor
+            .set_attribute("href", url.as_str())
+            .and_then(|_| anchor.set_attribute("download", PALETTE_LIBRARY_FILE_NAME))
+            .map_err(|err| format!("failed to set up download link: {:?}", err))?;
+        anchor
+            .dyn_into::<HtmlElement>()
+            .map_err(|_| "download link is not a HtmlElement".to_owned())?
+            .click();
+        Ok(())
+    }
+
+    /// Label, min, max and step of the channel inputs of a range of two colors
+    fn channel_cfg(range: &ColorRange) -> [(&'static str, f32, f32, f32); 3] {
+        match range {
+            ColorRange::Hsl(_) => HSL_CHANNELS,
+            ColorRange::OkLab(_) => OKLAB_CHANNELS,
+            ColorRange::OkLch(_) => OKLCH_CHANNELS,
+            ColorRange::Rgb(_) | ColorRange::Gradient(_) => RGB_CHANNELS,
+        }
+    }
+
+    /// The channels of the start (`endpoint` 0) or end color of a range of two colors
+    fn channels(range: &ColorRange, endpoint: usize) -> Option<[f32; 3]> {
+        match range {
+            ColorRange::Hsl(range) => {
+                let color = if endpoint == 0 {
+                    range.start()
+                } else {
+                    range.end()
+                };
+                Some([color.hue(), color.saturation(), color.lightness()])
+            }
+            ColorRange::Rgb(range) => {
+                let color = if endpoint == 0 {
+                    range.start()
+                } else {
+                    range.end()
+                };
+                Some([
+                    f32::from(color.red()),
+                    f32::from(color.green()),
+                    f32::from(color.blue()),
+                ])
+            }
+            ColorRange::OkLab(range) => {
+                let color = if endpoint == 0 {
+                    range.start()
+                } else {
+                    range.end()
+                };
+                Some([color.lightness(), color.a(), color.b()])
+            }
+            ColorRange::OkLch(range) => {
+                let color = if endpoint == 0 {
+                    range.start()
+                } else {
+                    range.end()
+                };
+                Some([color.lightness(), color.chroma(), color.hue()])
+            }
+            ColorRange::Gradient(_) => None,
+        }
+    }
+
+    fn range_dir(range: &ColorRange) -> Option<Direction> {
+        match range {
+            ColorRange::Hsl(range) => Some(range.dir()),
+            ColorRange::Rgb(range) => Some(range.dir()),
+            ColorRange::OkLch(range) => Some(range.dir()),
+            ColorRange::Gradient(_) | ColorRange::OkLab(_) => None,
+        }
+    }
+
+    /// A range of two colors of the same kind as `range` with the given colors and direction
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn build_range(
+        range: &ColorRange,
+        colors: [[f32; 3]; 2],
+        dir: Direction,
+    ) -> Option<ColorRange> {
+        let [start, end] = colors;
+        match range {
+            ColorRange::Hsl(_) => Some(ColorRange::Hsl(HslRange::new_with_dir(
+                HslColor::new(start[0], start[1], start[2]),
+                HslColor::new(end[0], end[1], end[2]),
+                dir,
+            ))),
+            ColorRange::Rgb(_) => {
+                let rgb = |channels: [f32; 3]| {
+                    RgbColor::new(channels[0] as u8, channels[1] as u8, channels[2] as u8)
+                };
+                Some(ColorRange::Rgb(RgbRange::new_with_dir(
+                    rgb(start),
+                    rgb(end),
+                    dir,
+                )))
+            }
+            ColorRange::OkLab(_) => Some(ColorRange::OkLab(OkLabRange::new(
+                OkLabColor::new(start[0], start[1], start[2]),
+                OkLabColor::new(end[0], end[1], end[2]),
+            ))),
+            ColorRange::OkLch(_) => Some(ColorRange::OkLch(OkLchRange::new_with_dir(
+                OkLchColor::new(start[0], start[1], start[2]),
+                OkLchColor::new(end[0], end[1], end[2]),
+                dir,
+            ))),
+            ColorRange::Gradient(_) => None,
+        }
+    }
+
+    /// A copy of a range of two colors with one channel of the start or end color set to `value`
+    fn with_channel(
+        range: &ColorRange,
+        endpoint: usize,
+        channel: usize,
+        value: f32,
+    ) -> Option<ColorRange> {
+        let mut colors = [Self::channels(range, 0)?, Self::channels(range, 1)?];
+        let (_, min, max, _) = Self::channel_cfg(range)[channel];
+        colors[endpoint][channel] = value.max(min).min(max);
+        Self::build_range(
+            range,
+            colors,
+            Self::range_dir(range).unwrap_or(Direction::Positive),
+        )
+    }
+
+    fn with_dir(range: &ColorRange, dir: Direction) -> Option<ColorRange> {
+        let colors = [Self::channels(range, 0)?, Self::channels(range, 1)?];
+        Self::build_range(range, colors, dir)
+    }
+
+    /// A gradient looking like `range`, sampled at `GRADIENT_SAMPLES` stops
+    #[allow(clippy::cast_precision_loss)]
+    fn sampled_gradient(range: &ColorRange) -> Gradient {
+        let interpolation = match range {
+            ColorRange::Hsl(_) => Interpolation::Hsl,
+            ColorRange::OkLab(_) => Interpolation::OkLab,
+            ColorRange::OkLch(_) => Interpolation::OkLch,
+            ColorRange::Rgb(_) | ColorRange::Gradient(_) => Interpolation::Rgb,
+        };
+        let stops = (0..GRADIENT_SAMPLES)
+            .map(|idx| {
+                let position = idx as f32 / (GRADIENT_SAMPLES - 1) as f32;
+                ColorStop::new(position, range.percent_of(position), interpolation)
+            })
+            .collect();
+        Gradient::new(stops, RepeatMode::default(), 1.0)
+    }
+
+    /// Convert a palette to another kind, keeping the colors at its ends
+    fn with_kind(range: &ColorRange, kind: &str) -> Option<ColorRange> {
+        let (start, end, dir) = match range {
+            ColorRange::Hsl(range) => (range.start().to_rgb(), range.end().to_rgb(), range.dir()),
+            ColorRange::Rgb(range) => (range.start().clone(), range.end().clone(), range.dir()),
+            ColorRange::Gradient(gradient) => (
+                gradient.percent_of(0.0),
+                gradient.percent_of(1.0),
+                Direction::Positive,
+            ),
+            ColorRange::OkLab(range) => (
+                range.start().to_rgb(),
+                range.end().to_rgb(),
+                Direction::Positive,
+            ),
+            ColorRange::OkLch(range) => (range.start().to_rgb(), range.end().to_rgb(), range.dir()),
+        };
+        match (range, kind) {
+            (ColorRange::Hsl(_), KIND_HSL)
+            | (ColorRange::Rgb(_), KIND_RGB)
+            | (ColorRange::Gradient(_), KIND_GRADIENT)
+            | (ColorRange::OkLab(_), KIND_OKLAB)
+            | (ColorRange::OkLch(_), KIND_OKLCH) => Some(range.clone()),
+            (_, KIND_OKLAB) => Some(ColorRange::OkLab(OkLabRange::new(
+                start.to_oklab(),
+                end.to_oklab(),
+            ))),
+            (_, KIND_OKLCH) => Some(ColorRange::OkLch(OkLchRange::new_with_dir(
+                start.to_oklch(),
+                end.to_oklch(),
+                dir,
+            ))),
+            (_, KIND_HSL) => Some(ColorRange::Hsl(HslRange::new_with_dir(
+                start.to_hsl(),
+                end.to_hsl(),
+                dir,
+            ))),
+            (_, KIND_RGB) => Some(ColorRange::Rgb(RgbRange::new_with_dir(start, end, dir))),
+            (_, KIND_GRADIENT) => Some(ColorRange::Gradient(Self::sampled_gradient(range))),
+            _ => None,
+        }
+    }
+
+    /// Apply `change` to the stops, repeat mode and scale of the selected gradient
+    fn change_gradient<F>(&mut self, change: F)
+    where
+        F: FnOnce(&mut Vec<ColorStop>, &mut RepeatMode, &mut f32),
+    {
+        if let ColorRange::Gradient(gradient) = self.selected_range() {
+            let mut stops = gradient.stops().to_vec();
+            let mut repeat = gradient.repeat();
+            let mut scale = gradient.scale();
+            change(&mut stops, &mut repeat, &mut scale);
+            self.palettes.insert(
+                self.selected.clone(),
+                ColorRange::Gradient(Gradient::new(stops, repeat, scale)),
+            );
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn gradient_style(range: &ColorRange) -> String {
+        let stops = (0..PREVIEW_STEPS)
+            .map(|idx| {
+                range
+                    .percent_of(idx as f32 / (PREVIEW_STEPS - 1) as f32)
+                    .to_string()
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("background: linear-gradient(to right, {});", stops)
+    }
+
+    fn view_stop(ctx: &Context<Self>, idx: usize, stop: &ColorStop, removable: bool) -> Html {
+        let selected = Self::interpolation_value(stop.interpolation);
+        let on_position_changed = ctx.link().callback(move |event: Event| {
+            Msg::StopPositionChanged(
+                idx,
+                event.target_unchecked_into::<HtmlInputElement>().value(),
+            )
+        });
+        let on_color_changed = ctx.link().callback(move |event: Event| {
+            Msg::StopColorChanged(
+                idx,
+                event.target_unchecked_into::<HtmlInputElement>().value(),
+            )
+        });
+        let on_interpolation_changed = ctx.link().callback(move |event: Event| {
+            Msg::StopInterpolationChanged(
+                idx,
+                event.target_unchecked_into::<HtmlSelectElement>().value(),
+            )
+        });
+        let remove = ctx.link().callback(move |_| Msg::RemoveStop(idx));
+        let position_id = format!("palette_stop_position_{}", idx);
+        let color_id = format!("palette_stop_color_{}", idx);
+        let interpolation_id = format!("palette_stop_interpolation_{}", idx);
+
+        html![
+            <div class="input_cntr">
+                <div class="input_inner">
+                    <label class="input_label" for={position_id.clone()}>
+                        {format!("Stop {}", idx + 1)}
+                    </label>
+                    <input class="input" id={position_id.clone()} name={position_id}
+                        type="number" min="0" max="1" step="0.01"
+                        value={stop.position.to_string()} onchange={on_position_changed}/>
+                </div>
+                <div class="input_inner">
+                    <label class="input_label" for={color_id.clone()}>{"Color"}</label>
+                    <input class="input" id={color_id.clone()} name={color_id} type="color"
+                        value={stop.color.to_string().to_lowercase()} onchange={on_color_changed}/>
+                </div>
+                <div class="input_inner">
+                    <label class="input_label" for={interpolation_id.clone()}>
+                        {"Blend towards next"}
+                    </label>
+                    <select class="input" id={interpolation_id.clone()} name={interpolation_id}
+                        onchange={on_interpolation_changed}>
+                        {
+                            for Interpolation::ALL.iter().map(|interpolation| {
+                                let value = Self::interpolation_value(*interpolation);
+                                html![
+                                    <option value={value.clone()} selected={value == selected}>
+                                        {interpolation.name()}
+                                    </option>
+                                ]
+                            })
+                        }
+                    </select>
+                </div>
+                <button class="editor_button" onclick={remove} disabled={!removable}>
+                    {"Remove"}
+                </button>
+            </div>
+        ]
+    }
+
+    fn view_gradient(ctx: &Context<Self>, gradient: &Gradient) -> Html {
+        let selected = Self::repeat_value(gradient.repeat());
+        let on_repeat_changed = ctx.link().callback(|event: Event| {
+            Msg::RepeatChanged(event.target_unchecked_into::<HtmlSelectElement>().value())
+        });
+        let on_scale_changed = ctx.link().callback(|event: Event| {
+            Msg::ScaleChanged(event.target_unchecked_into::<HtmlInputElement>().value())
+        });
+        let add_stop = ctx.link().callback(|_| Msg::AddStop);
+        let removable = gradient.stops().len() > MIN_STOPS;
+
+        html![
+            <>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="palette_repeat">{"Repeat Mode"}</label>
+                        <select class="input" id="palette_repeat" name="palette_repeat"
+                            onchange={on_repeat_changed}>
+                            {
+                                for RepeatMode::ALL.iter().map(|repeat| {
+                                    let value = Self::repeat_value(*repeat);
+                                    html![
+                                        <option value={value.clone()} selected={value == selected}>
+                                            {repeat.name()}
+                                        </option>
+                                    ]
+                                })
+                            }
+                        </select>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="palette_scale">{"Repetitions"}</label>
+                        <input class="input" id="palette_scale" name="palette_scale"
+                            type="number" min="0.1" max="100" step="0.1"
+                            value={gradient.scale().to_string()} onchange={on_scale_changed}/>
+                    </div>
+                    <button class="editor_button" id="palette_add_stop" onclick={add_stop}>
+                        {"Add Stop"}
+                    </button>
+                </div>
+                {
+                    for gradient.stops().iter().enumerate().map(|(idx, stop)| {
+                        Self::view_stop(ctx, idx, stop, removable)
+                    })
+                }
+            </>
+        ]
+    }
+
+    fn view_transfer(ctx: &Context<Self>, transfer: &Transfer) -> Html {
+        let selected = Self::transfer_value(transfer.function);
+        let on_function_changed = ctx.link().callback(|event: Event| {
+            Msg::TransferFunctionChanged(event.target_unchecked_into::<HtmlSelectElement>().value())
+        });
+        let on_density_changed = ctx.link().callback(|event: Event| {
+            Msg::DensityChanged(event.target_unchecked_into::<HtmlInputElement>().value())
+        });
+        let on_period_changed = ctx.link().callback(|event: Event| {
+            Msg::PeriodChanged(event.target_unchecked_into::<HtmlInputElement>().value())
+        });
+        let on_offset_changed = ctx.link().callback(|event: Event| {
+            Msg::OffsetChanged(event.target_unchecked_into::<HtmlInputElement>().value())
+        });
+
+        html![
+            <div class="input_cntr">
+                <div class="input_inner">
+                    <label class="input_label" for="transfer_function">{"Transfer"}</label>
+                    <select class="input" id="transfer_function" name="transfer_function"
+                        onchange={on_function_changed}>
+                        {
+                            for TransferFunction::ALL.iter().map(|function| {
+                                let value = Self::transfer_value(*function);
+                                html![
+                                    <option value={value.clone()} selected={value == selected}>
+                                        {function.name()}
+                                    </option>
+                                ]
+                            })
+                        }
+                    </select>
+                </div>
+                <div class="input_inner">
+                    <label class="input_label" for="transfer_density">{"Density"}</label>
+                    <input class="input" id="transfer_density" name="transfer_density"
+                        type="number" min="0.1" max="100" step="0.1"
+                        value={transfer.density.to_string()} onchange={on_density_changed}/>
+                </div>
+                {
+                    if transfer.function == TransferFunction::Cyclic {
+                        html![
+                            <>
+                                <div class="input_inner">
+                                    <label class="input_label" for="transfer_period">
+                                        {"Period"}
+                                    </label>
+                                    <input class="input" id="transfer_period"
+                                        name="transfer_period" type="number" min="1"
+                                        max="100000" step="1"
+                                        value={transfer.period.to_string()}
+                                        onchange={on_period_changed}/>
+                                </div>
+                                <div class="input_inner">
+                                    <label class="input_label" for="transfer_offset">
+                                        {"Offset"}
+                                    </label>
+                                    <input class="input" id="transfer_offset"
+                                        name="transfer_offset" type="number" min="0" max="1"
+                                        step="0.01" value={transfer.offset.to_string()}
+                                        onchange={on_offset_changed}/>
+                                </div>
+                            </>
+                        ]
+                    } else {
+                        html![]
+                    }
+                }
+            </div>
+        ]
+    }
+
+    fn view_color(ctx: &Context<Self>, range: &ColorRange, endpoint: usize) -> Html {
+        let channel_cfg = Self::channel_cfg(range);
+        let channels = Self::channels(range, endpoint).unwrap_or_default();
+        html![
+            <div class="input_cntr">
+                <p class="input_label">{format!("{} Color", ENDPOINTS[endpoint])}</p>
+                {
+                    for channel_cfg.iter().enumerate().map(|(channel, (label, min, max, step))| {
+                        let id = format!("palette_{}_{}", ENDPOINTS[endpoint], label).to_lowercase();
+                        let onchange = ctx.link().callback(move |event: Event| {
+                            Msg::ChannelChanged(
+                                endpoint,
+                                channel,
+                                event.target_unchecked_into::<HtmlInputElement>().value(),
+                            )
+                        });
+                        html![
+                            <div class="input_inner">
+                                <label class="input_label" for={id.clone()}>{*label}</label>
+                                <input class="input" id={id.clone()} name={id} type="number"
+                                    min={min.to_string()} max={max.to_string()}
+                                    step={step.to_string()}
+                                    value={channels[channel].to_string()}
+                                    {onchange}/>
+                            </div>
+                        ]
+                    })
+                }
+            </div>
+        ]
+    }
 }
 
 impl Component for EditColorConfig {
-    type Message = ();
+    type Message = Msg;
     type Properties = EditColorCfgProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
         Self {
             container_ref: NodeRef::default(),
+            name_ref: NodeRef::default(),
+            palettes: ctx.props().config.clone(),
+            selected: Self::initial_selection(ctx),
+            assigned: ctx.props().palette.clone(),
+            transfer: ctx.props().transfer,
+            readers: Vec::new(),
+            loaded_files: 0,
+            import_errors: Vec::new(),
+            conflict_mode: ConflictMode::default(),
+            not_exported: BTreeSet::new(),
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        self.reset(ctx);
+        true
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Cancel => {
+                info!("EditColorConfig: got msg Cancel");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+                self.reset(ctx);
+                ctx.props().cb_canceled.emit(());
+                true
+            }
+            Msg::ImportFiles(files) => {
+                info!("EditColorConfig: got msg ImportFiles");
+                if let Some(files) = files {
+                    for file in gloo_file::FileList::from(files).iter() {
+                        let name = file.name();
+                        let link = ctx.link().clone();
+                        self.readers.push(gloo_file::callbacks::read_as_text(
+                            file,
+                            move |result| {
+                                link.send_message(Msg::FileLoaded(
+                                    name,
+                                    result.map_err(|err| err.to_string()),
+                                ));
+                            },
+                        ));
+                    }
+                }
+                false
+            }
+            Msg::FileLoaded(file_name, result) => {
+                info!("EditColorConfig: got msg FileLoaded({})", file_name);
+                match result {
+                    Ok(content) => {
+                        if let Err(errors) = self.import_file(file_name.as_str(), content.as_str())
+                        {
+                            self.import_errors.extend(errors);
+                        }
+                    }
+                    Err(err) => self
+                        .import_errors
+                        .push(format!("{}: failed to read file: {}", file_name, err)),
+                }
+                self.loaded_files += 1;
+                if self.loaded_files >= self.readers.len() {
+                    self.readers.clear();
+                    self.loaded_files = 0;
+                }
+                true
+            }
+            Msg::ImportErrorsOk => {
+                info!("EditColorConfig: got msg ImportErrorsOk");
+                self.import_errors.clear();
+                true
+            }
+            Msg::ConflictModeChanged(value) => {
+                match ConflictMode::ALL
+                    .iter()
+                    .find(|mode| Self::conflict_value(**mode) == value)
+                {
+                    Some(mode) => self.conflict_mode = *mode,
+                    None => error!("invalid conflict mode '{}'", value),
+                }
+                false
+            }
+            Msg::ExportToggled(name) => {
+                info!("EditColorConfig: got msg ExportToggled({})", name);
+                if !self.not_exported.remove(&name) {
+                    self.not_exported.insert(name);
+                }
+                true
+            }
+            Msg::ExportPalettes => {
+                info!("EditColorConfig: got msg ExportPalettes");
+                self.export().map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                    },
+                    |v| v,
+                );
+                false
+            }
+            Msg::SaveConfig => {
+                info!("EditColorConfig: got msg SaveConfig");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+                ctx.props().cb_saved.emit((
+                    self.palettes.clone(),
+                    self.assigned.clone(),
+                    self.transfer,
+                ));
+                false
+            }
+            Msg::Select(name) => {
+                info!("EditColorConfig: got msg Select({})", name);
+                if self.palettes.get(name.as_str()).is_some() {
+                    self.select(name);
+                } else {
+                    error!("invalid palette '{}'", name);
+                }
+                true
+            }
+            Msg::AddPalette => {
+                info!("EditColorConfig: got msg AddPalette");
+                let name = self.palettes.unique_name(NEW_PALETTE_NAME);
+                self.palettes.insert(name.clone(), ColorRange::default());
+                self.select(name);
+                true
+            }
+            Msg::DuplicatePalette => {
+                info!("EditColorConfig: got msg DuplicatePalette");
+                let name = self
+                    .palettes
+                    .unique_name(format!("{} copy", self.selected).as_str());
+                self.palettes.insert(name.clone(), self.selected_range());
+                self.select(name);
+                true
+            }
+            Msg::RenamePalette => {
+                info!("EditColorConfig: got msg RenamePalette");
+                let name = get_string_from_ref(&self.name_ref, "palette_name").map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        self.selected.clone()
+                    },
+                    |v| v.trim().to_owned(),
+                );
+                if name.is_empty() {
+                    error!("the name of a palette must not be empty");
+                } else if name != self.selected {
+                    if self.palettes.get(name.as_str()).is_some() {
+                        error!("a palette named '{}' already exists", name);
+                    } else if let Some(range) = self.palettes.remove(self.selected.as_str()) {
+                        self.palettes.insert(name.clone(), range);
+                        if self.assigned.as_ref() == Some(&self.selected) {
+                            self.assigned = Some(name.clone());
+                        }
+                        if self.not_exported.remove(&self.selected) {
+                            self.not_exported.insert(name.clone());
+                        }
+                        self.selected = name;
+                    }
+                }
+                true
+            }
+            Msg::DeletePalette => {
+                info!("EditColorConfig: got msg DeletePalette");
+                // there is always one palette left to edit
+                if self.palettes.names().count() > 1 {
+                    self.palettes.remove(self.selected.as_str());
+                    self.not_exported.remove(&self.selected);
+                    if self.assigned.as_ref() == Some(&self.selected) {
+                        self.assigned = None;
+                    }
+                    let name = self.palettes.names().next().cloned().unwrap_or_default();
+                    self.select(name);
+                }
+                true
+            }
+            Msg::KindChanged(value) => {
+                match Self::with_kind(&self.selected_range(), value.as_str()) {
+                    Some(range) => self.palettes.insert(self.selected.clone(), range),
+                    None => error!("invalid palette kind '{}'", value),
+                }
+                true
+            }
+            Msg::DirChanged(value) => {
+                match Direction::ALL
+                    .iter()
+                    .find(|dir| Self::dir_value(**dir) == value)
+                {
+                    Some(dir) => {
+                        if let Some(range) = Self::with_dir(&self.selected_range(), *dir) {
+                            self.palettes.insert(self.selected.clone(), range);
+                        }
+                    }
+                    None => error!("invalid direction '{}'", value),
+                }
+                true
+            }
+            Msg::ChannelChanged(endpoint, channel, value) => {
+                match value.parse::<f32>() {
+                    Ok(value) => {
+                        if let Some(range) =
+                            Self::with_channel(&self.selected_range(), endpoint, channel, value)
+                        {
+                            self.palettes.insert(self.selected.clone(), range);
+                        }
+                    }
+                    Err(err) => error!(
+                        "invalid value for channel {} of the {} color: {}",
+                        channel, ENDPOINTS[endpoint], err
+                    ),
+                }
+                true
+            }
+            Msg::StopPositionChanged(idx, value) => {
+                match value.parse::<f32>() {
+                    Ok(position) => self.change_gradient(|stops, _, _| {
+                        if let Some(stop) = stops.get_mut(idx) {
+                            stop.position = position;
+                        }
+                    }),
+                    Err(err) => error!("invalid position of stop {}: {}", idx, err),
+                }
+                true
+            }
+            Msg::StopColorChanged(idx, value) => {
+                match RgbColor::from_hex(value.as_str()) {
+                    Some(color) => self.change_gradient(|stops, _, _| {
+                        if let Some(stop) = stops.get_mut(idx) {
+                            stop.color = color;
+                        }
+                    }),
+                    None => error!("invalid color '{}' of stop {}", value, idx),
+                }
+                true
+            }
+            Msg::StopInterpolationChanged(idx, value) => {
+                match Interpolation::ALL
+                    .iter()
+                    .find(|interpolation| Self::interpolation_value(**interpolation) == value)
+                {
+                    Some(interpolation) => self.change_gradient(|stops, _, _| {
+                        if let Some(stop) = stops.get_mut(idx) {
+                            stop.interpolation = *interpolation;
+                        }
+                    }),
+                    None => error!("invalid interpolation '{}'", value),
+                }
+                true
+            }
+            Msg::AddStop => {
+                info!("EditColorConfig: got msg AddStop");
+                if let ColorRange::Gradient(gradient) = self.selected_range() {
+                    // split the widest gap between two stops
+                    let (position, interpolation) = gradient
+                        .stops()
+                        .windows(2)
+                        .max_by(|a, b| {
+                            (a[1].position - a[0].position)
+                                .total_cmp(&(b[1].position - b[0].position))
+                        })
+                        .map_or((0.5, Interpolation::default()), |pair| {
+                            (
+                                (pair[0].position + pair[1].position) / 2.0,
+                                pair[0].interpolation,
+                            )
+                        });
+                    let color = gradient.color_at(position);
+                    self.change_gradient(|stops, _, _| {
+                        stops.push(ColorStop::new(position, color, interpolation));
+                    });
+                }
+                true
+            }
+            Msg::RemoveStop(idx) => {
+                info!("EditColorConfig: got msg RemoveStop({})", idx);
+                self.change_gradient(|stops, _, _| {
+                    if idx < stops.len() && stops.len() > MIN_STOPS {
+                        stops.remove(idx);
+                    }
+                });
+                true
+            }
+            Msg::RepeatChanged(value) => {
+                match RepeatMode::ALL
+                    .iter()
+                    .find(|repeat| Self::repeat_value(**repeat) == value)
+                {
+                    Some(repeat) => self.change_gradient(|_, mode, _| *mode = *repeat),
+                    None => error!("invalid repeat mode '{}'", value),
+                }
+                true
+            }
+            Msg::ScaleChanged(value) => {
+                match value.parse::<f32>() {
+                    Ok(scale) if scale > 0.0 => {
+                        self.change_gradient(|_, _, repetitions| *repetitions = scale);
+                    }
+                    Ok(scale) => error!("invalid repetitions {}", scale),
+                    Err(err) => error!("invalid repetitions: {}", err),
+                }
+                true
+            }
+            Msg::AssignedChanged(value) => {
+                info!("EditColorConfig: got msg AssignedChanged({})", value);
+                self.assigned = if value == NO_PALETTE {
+                    None
+                } else {
+                    Some(value)
+                };
+                true
+            }
+            Msg::TransferFunctionChanged(value) => {
+                match TransferFunction::ALL
+                    .iter()
+                    .find(|function| Self::transfer_value(**function) == value)
+                {
+                    Some(function) => {
+                        if let Some(transfer) = self.transfer.as_mut() {
+                            transfer.function = *function;
+                        }
+                    }
+                    None => error!("invalid transfer function '{}'", value),
+                }
+                true
+            }
+            Msg::DensityChanged(value) => {
+                match value.parse::<f32>() {
+                    Ok(density) if density > 0.0 => {
+                        if let Some(transfer) = self.transfer.as_mut() {
+                            transfer.density = density;
+                        }
+                    }
+                    Ok(density) => error!("invalid density {}", density),
+                    Err(err) => error!("invalid density: {}", err),
+                }
+                true
+            }
+            Msg::PeriodChanged(value) => {
+                match value.parse::<f32>() {
+                    Ok(period) if period > 0.0 => {
+                        if let Some(transfer) = self.transfer.as_mut() {
+                            transfer.period = period;
+                        }
+                    }
+                    Ok(period) => error!("invalid period {}", period),
+                    Err(err) => error!("invalid period: {}", err),
+                }
+                true
+            }
+            Msg::OffsetChanged(value) => {
+                match value.parse::<f32>() {
+                    Ok(offset) => {
+                        if let Some(transfer) = self.transfer.as_mut() {
+                            transfer.offset = offset.rem_euclid(1.0);
+                        }
+                    }
+                    Err(err) => error!("invalid offset: {}", err),
+                }
+                true
+            }
         }
     }
 
@@ -25,8 +1043,245 @@ impl Component for EditColorConfig {
             "edit_cntr_hidden"
         };
 
+        let on_select = ctx.link().callback(|event: Event| {
+            Msg::Select(event.target_unchecked_into::<HtmlSelectElement>().value())
+        });
+        let on_kind_changed = ctx.link().callback(|event: Event| {
+            Msg::KindChanged(event.target_unchecked_into::<HtmlSelectElement>().value())
+        });
+        let on_dir_changed = ctx.link().callback(|event: Event| {
+            Msg::DirChanged(event.target_unchecked_into::<HtmlSelectElement>().value())
+        });
+        let on_assigned_changed = ctx.link().callback(|event: Event| {
+            Msg::AssignedChanged(event.target_unchecked_into::<HtmlSelectElement>().value())
+        });
+        let add_palette = ctx.link().callback(|_| Msg::AddPalette);
+        let duplicate_palette = ctx.link().callback(|_| Msg::DuplicatePalette);
+        let rename_palette = ctx.link().callback(|_| Msg::RenamePalette);
+        let delete_palette = ctx.link().callback(|_| Msg::DeletePalette);
+        let on_files_picked = ctx.link().callback(|event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            let files = input.files();
+            // picking the same file again imports it again
+            input.set_value("");
+            Msg::ImportFiles(files)
+        });
+        let on_drag_over = Callback::from(|event: DragEvent| event.prevent_default());
+        let on_drop = ctx.link().callback(|event: DragEvent| {
+            event.prevent_default();
+            Msg::ImportFiles(event.data_transfer().and_then(|transfer| transfer.files()))
+        });
+        let import_errors_ok = ctx.link().callback(|_| Msg::ImportErrorsOk);
+        let on_conflict_mode_changed = ctx.link().callback(|event: Event| {
+            Msg::ConflictModeChanged(event.target_unchecked_into::<HtmlSelectElement>().value())
+        });
+        let export_palettes = ctx.link().callback(|_| Msg::ExportPalettes);
+        let import_types = format!("{},{}", PALETTE_FILE_TYPES, PALETTE_LIBRARY_FILE_TYPE);
+        let conflict_mode = Self::conflict_value(self.conflict_mode);
+        let save_config = ctx.link().callback(|_| Msg::SaveConfig);
+        let cancel = ctx.link().callback(|_| Msg::Cancel);
+
+        let range = self.selected_range();
+        let kind = match range {
+            ColorRange::Hsl(_) => KIND_HSL,
+            ColorRange::Rgb(_) => KIND_RGB,
+            ColorRange::Gradient(_) => KIND_GRADIENT,
+            ColorRange::OkLab(_) => KIND_OKLAB,
+            ColorRange::OkLch(_) => KIND_OKLCH,
+        };
+
         html![
-            <div class={cntr_class} id="color_edit_cntr" ref={self.container_ref.clone()}>
+            <div class={cntr_class} id="color_edit_cntr" ref={self.container_ref.clone()}
+                ondragover={on_drag_over} ondrop={on_drop}>
+                <ModalOk
+                    visible={!self.import_errors.is_empty()}
+                    title={"Palette Import failed".to_owned()}
+                    message={ModalMsg::StringList(self.import_errors.clone())}
+                    on_ok={import_errors_ok}
+                    background_color={Some("salmon")}
+                />
+                <div class="input_cntr">
+                    <p class="hint_text">
+                        {"Hint: A palette maps the iterations of escape time fractals and the \
+                          density of attractors, IFS and flames to colors from its start to its end color. \
+                          GIMP palettes (.gpl), GIMP gradients (.ggr), Fractint maps (.map) and \
+                          exported palettes (.json) can be imported or dropped here."}
+                    </p>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="palette_select">{"Palette"}</label>
+                        <select class="input" id="palette_select" name="palette_select"
+                            onchange={on_select}>
+                            {
+                                for self.palettes.names().map(|name| html![
+                                    <option value={name.clone()} selected={*name == self.selected}>
+                                        {name}
+                                    </option>
+                                ])
+                            }
+                        </select>
+                    </div>
+                    <button class="editor_button" id="palette_add" onclick={add_palette}>
+                        {"New"}
+                    </button>
+                    <button class="editor_button" id="palette_duplicate" onclick={duplicate_palette}>
+                        {"Duplicate"}
+                    </button>
+                    <button class="editor_button" id="palette_delete" onclick={delete_palette}
+                        disabled={self.palettes.names().count() < 2}>
+                        {"Delete"}
+                    </button>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="palette_name">{"Name"}</label>
+                        <input class="input" id="palette_name" name="palette_name" type="text"
+                            ref={self.name_ref.clone()} value={self.selected.clone()}/>
+                    </div>
+                    <button class="editor_button" id="palette_rename" onclick={rename_palette}>
+                        {"Rename"}
+                    </button>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="palette_import">{"Import"}</label>
+                        <input class="input" id="palette_import" name="palette_import" type="file"
+                            multiple=true accept={import_types} onchange={on_files_picked}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="palette_conflict">{"Existing Names"}</label>
+                        <select class="input" id="palette_conflict" name="palette_conflict"
+                            onchange={on_conflict_mode_changed}>
+                            {
+                                for ConflictMode::ALL.iter().map(|mode| {
+                                    let value = Self::conflict_value(*mode);
+                                    html![
+                                        <option value={value.clone()}
+                                            selected={value == conflict_mode}>
+                                            {mode.name()}
+                                        </option>
+                                    ]
+                                })
+                            }
+                        </select>
+                    </div>
+                </div>
+                <div class="input_cntr">
+                    <p class="input_label">{"Export"}</p>
+                    {
+                        for self.palettes.names().map(|name| {
+                            let id = format!("palette_export_{}", name);
+                            let toggled = name.clone();
+                            let onchange = ctx.link().callback(move |_| {
+                                Msg::ExportToggled(toggled.clone())
+                            });
+                            html![
+                                <div class="input_inner">
+                                    <label class="input_label" for={id.clone()}>{name}</label>
+                                    <input class="input" id={id.clone()} name={id} type="checkbox"
+                                        checked={!self.not_exported.contains(name)} {onchange}/>
+                                </div>
+                            ]
+                        })
+                    }
+                    <button class="editor_button" id="palette_export" onclick={export_palettes}
+                        disabled={self.palettes.names().all(|name| self.not_exported.contains(name))}>
+                        {"Export"}
+                    </button>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="palette_kind">{"Color Model"}</label>
+                        <select class="input" id="palette_kind" name="palette_kind"
+                            onchange={on_kind_changed}>
+                            <option value={KIND_HSL} selected={kind == KIND_HSL}>{"HSL"}</option>
+                            <option value={KIND_RGB} selected={kind == KIND_RGB}>{"RGB"}</option>
+                            <option value={KIND_OKLAB} selected={kind == KIND_OKLAB}>{"OKLab"}</option>
+                            <option value={KIND_OKLCH} selected={kind == KIND_OKLCH}>{"OKLCh"}</option>
+                            <option value={KIND_GRADIENT} selected={kind == KIND_GRADIENT}>
+                                {"Gradient"}
+                            </option>
+                        </select>
+                    </div>
+                    {
+                        if let Some(dir) = Self::range_dir(&range) {
+                            let selected = Self::dir_value(dir);
+                            html![
+                                <div class="input_inner">
+                                    <label class="input_label" for="palette_dir">{"Direction"}</label>
+                                    <select class="input" id="palette_dir" name="palette_dir"
+                                        onchange={on_dir_changed}>
+                                        {
+                                            for Direction::ALL.iter().map(|item| {
+                                                let value = Self::dir_value(*item);
+                                                html![
+                                                    <option value={value.clone()}
+                                                        selected={value == selected}>
+                                                        {item.name()}
+                                                    </option>
+                                                ]
+                                            })
+                                        }
+                                    </select>
+                                </div>
+                            ]
+                        } else {
+                            html![]
+                        }
+                    }
+                </div>
+                {
+                    if let ColorRange::Gradient(gradient) = &range {
+                        Self::view_gradient(ctx, gradient)
+                    } else {
+                        html![
+                            <>
+                                { Self::view_color(ctx, &range, 0) }
+                                { Self::view_color(ctx, &range, 1) }
+                            </>
+                        ]
+                    }
+                }
+                <div class="input_cntr">
+                    <div class="gradient_preview" style={Self::gradient_style(&range)}></div>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="palette_assigned">
+                            {"Palette of the active Fractal"}
+                        </label>
+                        <select class="input" id="palette_assigned" name="palette_assigned"
+                            onchange={on_assigned_changed}>
+                            <option value={NO_PALETTE} selected={self.assigned.is_none()}>
+                                {"Default"}
+                            </option>
+                            {
+                                for self.palettes.names().map(|name| html![
+                                    <option value={name.clone()}
+                                        selected={self.assigned.as_ref() == Some(name)}>
+                                        {name}
+                                    </option>
+                                ])
+                            }
+                        </select>
+                    </div>
+                </div>
+                {
+                    if let Some(transfer) = self.transfer.as_ref() {
+                        Self::view_transfer(ctx, transfer)
+                    } else {
+                        html![]
+                    }
+                }
+                <div class="edit_button_cntr">
+                    <button class="editor_button" id="palette_save" onclick={save_config}>
+                        {"Save"}
+                    </button>
+                    <button class="editor_button" id="palette_cancel" onclick={cancel}>
+                        {"Cancel"}
+                    </button>
+                </div>
             </div>
         ]
     }
@@ -48,6 +1303,61 @@ impl ColorCfg {
     pub fn get<'a>(&'a self, name: &str) -> Option<&'a ColorRange> {
         self.palettes.get(name)
     }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.palettes.keys()
+    }
+
+    pub fn insert(&mut self, name: String, palette: ColorRange) {
+        self.palettes.insert(name, palette);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<ColorRange> {
+        self.palettes.remove(name)
+    }
+
+    /// The palettes of `names` as library to share
+    pub fn library<'a>(&self, names: impl Iterator<Item = &'a String>) -> PaletteLibrary {
+        PaletteLibrary::new(
+            names
+                .filter_map(|name| {
+                    self.palettes
+                        .get(name)
+                        .map(|palette| (name.clone(), palette.clone()))
+                })
+                .collect(),
+        )
+    }
+
+    /// Add an imported palette, resolving a taken name by `mode`. Returns the name the palette
+    /// was stored under, `None` if it was skipped.
+    pub fn import(
+        &mut self,
+        name: String,
+        palette: ColorRange,
+        mode: ConflictMode,
+    ) -> Option<String> {
+        let name = match mode {
+            _ if !self.palettes.contains_key(&name) => name,
+            ConflictMode::Rename => self.unique_name(name.as_str()),
+            ConflictMode::Overwrite => name,
+            ConflictMode::Skip => return None,
+        };
+        self.palettes.insert(name.clone(), palette);
+        Some(name)
+    }
+
+    /// `base` or, if a palette of that name exists, `base` followed by the lowest free number
+    pub fn unique_name(&self, base: &str) -> String {
+        if self.palettes.contains_key(base) {
+            (2..)
+                .map(|idx| format!("{} {}", base, idx))
+                .find(|name| !self.palettes.contains_key(name))
+                .expect("no free palette name")
+        } else {
+            base.to_owned()
+        }
+    }
 }
 
 impl Default for ColorCfg {
@@ -65,7 +1375,61 @@ impl Default for ColorCfg {
 #[derive(Properties, PartialEq, Clone)]
 pub struct EditColorCfgProps {
     pub config: ColorCfg,
+    /// The palette of the active fractal
+    pub palette: Option<String>,
+    /// The transfer function of the active fractal, `None` if it has none
+    pub transfer: Option<Transfer>,
     pub edit_mode: bool,
-    // pub cb_saved: Callback<ColorCfg>,
-    // pub cb_canceled: Callback<()>,
+    pub cb_saved: Callback<(ColorCfg, Option<String>, Option<Transfer>)>,
+    pub cb_canceled: Callback<()>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::ColorCfg;
+    use crate::work::colors::{ColorRange, HslRange, RgbColor, RgbRange};
+    use crate::work::palette_library::ConflictMode;
+
+    #[test]
+    fn test_unique_name() {
+        let mut cfg = ColorCfg::default();
+        assert_eq!(cfg.unique_name("fire").as_str(), "fire");
+        assert_eq!(cfg.unique_name("default").as_str(), "default 2");
+        cfg.insert("default 2".to_owned(), ColorRange::default());
+        assert_eq!(cfg.unique_name("default").as_str(), "default 3");
+        cfg.remove("default");
+        assert_eq!(cfg.unique_name("default").as_str(), "default");
+        assert_eq!(cfg.names().count(), 1);
+    }
+
+    #[test]
+    fn test_import() {
+        let rgb = ColorRange::Rgb(RgbRange::new(
+            RgbColor::new(0, 0, 0),
+            RgbColor::new(255, 0, 0),
+        ));
+        let mut cfg = ColorCfg::default();
+        assert_eq!(
+            cfg.import("default".to_owned(), rgb.clone(), ConflictMode::Skip),
+            None
+        );
+        assert!(cfg.get("default") == Some(&ColorRange::Hsl(HslRange::default())));
+        assert_eq!(
+            cfg.import("default".to_owned(), rgb.clone(), ConflictMode::Rename),
+            Some("default 2".to_owned())
+        );
+        assert_eq!(
+            cfg.import("default".to_owned(), rgb.clone(), ConflictMode::Overwrite),
+            Some("default".to_owned())
+        );
+        assert!(cfg.get("default") == Some(&rgb));
+        assert_eq!(
+            cfg.import("fire".to_owned(), rgb, ConflictMode::Skip),
+            Some("fire".to_owned())
+        );
+
+        let names = ["fire".to_owned(), "missing".to_owned()];
+        let library = cfg.library(names.iter());
+        assert_eq!(library.into_palettes().count(), 1);
+    }
 }
@@ -0,0 +1,524 @@
+use web_sys::{Element, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::agents::canvas_msg_bus::{ControlMsgBus, ControlMsgRequest};
+use crate::work::{
+    complex::Complex,
+    fractal::{FlameCfg, FlameTransform, Variation, WeightedVariation},
+    util::{get_f64_from_ref, get_u32_from_ref, set_value_on_input_ref},
+};
+use yew_agent::{Bridge, Bridged};
+
+/// Labels of the numeric fields of a transform, the index is used in `Msg::TransformChanged`
+const TRANSFORM_FIELDS: [&str; 8] = ["a", "b", "c", "d", "e", "f", "p", "color"];
+
+#[allow(clippy::enum_variant_names)]
+pub enum Msg {
+    TransformChanged(usize, usize, String),
+    VariationChanged(usize, usize, String),
+    WeightChanged(usize, usize, String),
+    AddVariation(usize),
+    RemoveVariation(usize, usize),
+    AddTransform,
+    RemoveTransform(usize),
+    ResetParams,
+    ZoomOut,
+    ResetArea,
+    SaveConfig,
+    Cancel,
+    CanvasMsg(ControlMsgRequest),
+}
+
+pub struct EditFlameCfg {
+    container_ref: NodeRef,
+    gamma_ref: NodeRef,
+    points_ref: NodeRef,
+    x_min_real_ref: NodeRef,
+    x_min_imag_ref: NodeRef,
+    x_max_real_ref: NodeRef,
+    x_max_imag_ref: NodeRef,
+    transforms: Vec<FlameTransform>,
+    _producer: Box<dyn Bridge<ControlMsgBus>>,
+}
+
+impl EditFlameCfg {
+    fn variation_value(variation: Variation) -> String {
+        format!("variation_{}", variation.name().to_lowercase())
+    }
+
+    fn get_f64(node_ref: &NodeRef, name: &str, default: f64) -> f64 {
+        get_f64_from_ref(node_ref, name).map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+                default
+            },
+            |v| v,
+        )
+    }
+
+    fn set_values(&self, values: &[(&NodeRef, &str, f64)]) {
+        for (node_ref, name, value) in values {
+            set_value_on_input_ref(node_ref, name, value.to_string().as_str()).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+    }
+
+    fn set_area(&self, x_min: &Complex, x_max: &Complex) {
+        self.set_values(&[
+            (&self.x_max_real_ref, "x_max_real", x_max.real()),
+            (&self.x_max_imag_ref, "x_max_imag", x_max.imag()),
+            (&self.x_min_real_ref, "x_min_real", x_min.real()),
+            (&self.x_min_imag_ref, "x_min_imag", x_min.imag()),
+        ]);
+    }
+
+    fn field_mut(transform: &mut FlameTransform, field: usize) -> &mut f64 {
+        match field {
+            0 => &mut transform.affine.a,
+            1 => &mut transform.affine.b,
+            2 => &mut transform.affine.c,
+            3 => &mut transform.affine.d,
+            4 => &mut transform.affine.e,
+            5 => &mut transform.affine.f,
+            6 => &mut transform.affine.probability,
+            _ => &mut transform.color,
+        }
+    }
+
+    fn parse_f64(value: &str, name: &str, idx: usize) -> Option<f64> {
+        match value.parse::<f64>() {
+            Ok(value) => Some(value),
+            Err(err) => {
+                error!("invalid value for {} of transform {}: {}", name, idx, err);
+                None
+            }
+        }
+    }
+
+    fn view_variation(
+        ctx: &Context<Self>,
+        idx: usize,
+        var_idx: usize,
+        variation: &WeightedVariation,
+    ) -> Html {
+        let selected = Self::variation_value(variation.variation);
+        let on_variation_changed = ctx.link().callback(move |event: Event| {
+            Msg::VariationChanged(
+                idx,
+                var_idx,
+                event.target_unchecked_into::<HtmlSelectElement>().value(),
+            )
+        });
+        let on_weight_changed = ctx.link().callback(move |event: Event| {
+            Msg::WeightChanged(
+                idx,
+                var_idx,
+                event.target_unchecked_into::<HtmlInputElement>().value(),
+            )
+        });
+        let remove = ctx
+            .link()
+            .callback(move |_| Msg::RemoveVariation(idx, var_idx));
+        let id = format!("flame_variation_{}_{}", idx, var_idx);
+        let weight_id = format!("flame_weight_{}_{}", idx, var_idx);
+
+        html![
+            <div class="input_inner">
+                <select class="input" id={id.clone()} name={id} onchange={on_variation_changed}>
+                    {
+                        for Variation::ALL.iter().map(|variation| {
+                            let value = Self::variation_value(*variation);
+                            html![
+                                <option value={value.clone()} selected={value == selected}>
+                                    {variation.name()}
+                                </option>
+                            ]
+                        })
+                    }
+                </select>
+                <input class="input" id={weight_id.clone()} name={weight_id}
+                    type="number" step="0.01" value={variation.weight.to_string()}
+                    onchange={on_weight_changed}/>
+                <button class="editor_button" onclick={remove}>{"Remove"}</button>
+            </div>
+        ]
+    }
+
+    fn view_transform(ctx: &Context<Self>, idx: usize, transform: &FlameTransform) -> Html {
+        let values = [
+            transform.affine.a,
+            transform.affine.b,
+            transform.affine.c,
+            transform.affine.d,
+            transform.affine.e,
+            transform.affine.f,
+            transform.affine.probability,
+            transform.color,
+        ];
+        let add_variation = ctx.link().callback(move |_| Msg::AddVariation(idx));
+        let remove = ctx.link().callback(move |_| Msg::RemoveTransform(idx));
+
+        html![
+            <div class="input_cntr">
+                {
+                    for TRANSFORM_FIELDS.iter().zip(values).enumerate().map(|(field, (label, value))| {
+                        let id = format!("flame_{}_{}", label, idx);
+                        let onchange = ctx.link().callback(move |event: Event| {
+                            Msg::TransformChanged(
+                                idx,
+                                field,
+                                event.target_unchecked_into::<HtmlInputElement>().value(),
+                            )
+                        });
+                        html![
+                            <div class="input_inner">
+                                <label class="input_label" for={id.clone()}>{label}</label>
+                                <input class="input" id={id.clone()} name={id}
+                                    type="number" step="0.0000001" value={value.to_string()}
+                                    {onchange}/>
+                            </div>
+                        ]
+                    })
+                }
+                {
+                    for transform.variations.iter().enumerate().map(|(var_idx, variation)| {
+                        Self::view_variation(ctx, idx, var_idx, variation)
+                    })
+                }
+                <button class="editor_button" id={format!("flame_add_variation_{}", idx)}
+                        onclick={add_variation}>
+                    {"Add Variation"}
+                </button>
+                <button class="editor_button" id={format!("flame_remove_{}", idx)} onclick={remove}>
+                    {"Remove Transform"}
+                </button>
+            </div>
+        ]
+    }
+}
+
+impl Component for EditFlameCfg {
+    type Message = Msg;
+    type Properties = EditFlameCfgProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        EditFlameCfg {
+            container_ref: NodeRef::default(),
+            gamma_ref: NodeRef::default(),
+            points_ref: NodeRef::default(),
+            x_max_real_ref: NodeRef::default(),
+            x_max_imag_ref: NodeRef::default(),
+            x_min_real_ref: NodeRef::default(),
+            x_min_imag_ref: NodeRef::default(),
+            transforms: ctx.props().config.transforms.clone(),
+            _producer: ControlMsgBus::bridge(ctx.link().callback(Msg::CanvasMsg)),
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        self.transforms = ctx.props().config.transforms.clone();
+        true
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let config = &ctx.props().config;
+        match msg {
+            Msg::Cancel => {
+                info!("EditFlameCfg: got msg Cancel");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+                self.transforms = config.transforms.clone();
+                ctx.props().cb_canceled.emit(());
+                true
+            }
+            Msg::SaveConfig => {
+                info!("EditFlameCfg: got msg SaveConfig");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+
+                let max_points = get_u32_from_ref(&self.points_ref, "points").map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        config.max_points
+                    },
+                    |v| v,
+                );
+
+                ctx.props().cb_saved.emit(FlameCfg {
+                    transforms: self.transforms.clone(),
+                    gamma: Self::get_f64(&self.gamma_ref, "gamma", config.gamma),
+                    x_max: Complex::new(
+                        Self::get_f64(&self.x_max_real_ref, "x_max_real", config.x_max.real()),
+                        Self::get_f64(&self.x_max_imag_ref, "x_max_imag", config.x_max.imag()),
+                    ),
+                    x_min: Complex::new(
+                        Self::get_f64(&self.x_min_real_ref, "x_min_real", config.x_min.real()),
+                        Self::get_f64(&self.x_min_imag_ref, "x_min_imag", config.x_min.imag()),
+                    ),
+                    max_points,
+                    color_cfg_name: config.color_cfg_name.clone(),
+                });
+                false
+            }
+            Msg::TransformChanged(idx, field, value) => {
+                if let Some(value) = Self::parse_f64(&value, TRANSFORM_FIELDS[field], idx) {
+                    if let Some(transform) = self.transforms.get_mut(idx) {
+                        *Self::field_mut(transform, field) = value;
+                    }
+                }
+                false
+            }
+            Msg::VariationChanged(idx, var_idx, value) => {
+                match Variation::ALL
+                    .iter()
+                    .find(|variation| Self::variation_value(**variation) == value)
+                {
+                    Some(variation) => {
+                        if let Some(weighted) = self
+                            .transforms
+                            .get_mut(idx)
+                            .and_then(|transform| transform.variations.get_mut(var_idx))
+                        {
+                            weighted.variation = *variation;
+                        }
+                    }
+                    None => error!("invalid variation '{}'", value),
+                }
+                false
+            }
+            Msg::WeightChanged(idx, var_idx, value) => {
+                if let Some(weight) = Self::parse_f64(&value, "weight", idx) {
+                    if let Some(weighted) = self
+                        .transforms
+                        .get_mut(idx)
+                        .and_then(|transform| transform.variations.get_mut(var_idx))
+                    {
+                        weighted.weight = weight;
+                    }
+                }
+                false
+            }
+            Msg::AddVariation(idx) => {
+                info!("EditFlameCfg: got msg AddVariation({})", idx);
+                if let Some(transform) = self.transforms.get_mut(idx) {
+                    transform
+                        .variations
+                        .push(WeightedVariation::new(Variation::Linear, 1.0));
+                }
+                true
+            }
+            Msg::RemoveVariation(idx, var_idx) => {
+                info!(
+                    "EditFlameCfg: got msg RemoveVariation({}, {})",
+                    idx, var_idx
+                );
+                if let Some(transform) = self.transforms.get_mut(idx) {
+                    if var_idx < transform.variations.len() {
+                        transform.variations.remove(var_idx);
+                    }
+                }
+                true
+            }
+            Msg::AddTransform => {
+                info!("EditFlameCfg: got msg AddTransform");
+                self.transforms.push(FlameTransform::default());
+                true
+            }
+            Msg::RemoveTransform(idx) => {
+                info!("EditFlameCfg: got msg RemoveTransform({})", idx);
+                if idx < self.transforms.len() {
+                    self.transforms.remove(idx);
+                }
+                true
+            }
+            Msg::ResetParams => {
+                info!("EditFlameCfg: got msg ResetParams");
+                let default = FlameCfg::default();
+                self.transforms = default.transforms;
+                self.set_values(&[
+                    (&self.gamma_ref, "gamma", default.gamma),
+                    (&self.points_ref, "points", f64::from(default.max_points)),
+                ]);
+                self.set_area(&default.x_min, &default.x_max);
+                true
+            }
+            Msg::ResetArea => {
+                info!("EditFlameCfg: got msg ResetArea");
+                let default = FlameCfg::default();
+                self.set_area(&default.x_min, &default.x_max);
+                false
+            }
+            Msg::ZoomOut => {
+                info!("EditFlameCfg: got msg ZoomOut");
+                let center = (config.x_max + config.x_min) * 0.5;
+                self.set_area(
+                    &(config.x_min - (center - config.x_min)),
+                    &(config.x_max + (config.x_max - center)),
+                );
+                false
+            }
+            Msg::CanvasMsg(canvas_msg) => {
+                info!("EditFlameCfg: got msg CanvasMsg");
+                match canvas_msg {
+                    ControlMsgRequest::CanvasSelectMsg(coords) if ctx.props().edit_mode => {
+                        let x_scale = (config.x_max.real() - config.x_min.real())
+                            / f64::from(ctx.props().canvas_width);
+                        let y_scale = (config.x_max.imag() - config.x_min.imag())
+                            / f64::from(ctx.props().canvas_height);
+
+                        // the y axis of flames points up, the top row is x_max.imag
+                        self.set_area(
+                            &Complex::new(
+                                config.x_min.real() + x_scale * f64::from(coords.0),
+                                config.x_max.imag() - y_scale * f64::from(coords.3),
+                            ),
+                            &Complex::new(
+                                config.x_min.real() + x_scale * f64::from(coords.2),
+                                config.x_max.imag() - y_scale * f64::from(coords.1),
+                            ),
+                        );
+                        false
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let add_transform = ctx.link().callback(|_| Msg::AddTransform);
+        let reset_params = ctx.link().callback(|_| Msg::ResetParams);
+        let reset_area = ctx.link().callback(|_| Msg::ResetArea);
+        let zoom_out = ctx.link().callback(|_| Msg::ZoomOut);
+        let save_config = ctx.link().callback(|_| Msg::SaveConfig);
+        let cancel = ctx.link().callback(|_| Msg::Cancel);
+        let cntr_class = if ctx.props().edit_mode {
+            "edit_cntr_visible"
+        } else {
+            "edit_cntr_hidden"
+        };
+
+        let config = &ctx.props().config;
+
+        html![
+            <div class={cntr_class} id="flame_edit_cntr" ref={self.container_ref.clone()}>
+                <div class="input_cntr">
+                    <p class="hint_text">
+                        {"Hint: Every transform applies the affine map (a x + b y + e, c x + d y + f) \
+                          followed by the weighted sum of its variations and is picked with \
+                          probability p. The color is the palette position of the transform. \
+                          You can select a rectangle in the draw area to import the coordiates into \
+                          the editor."}
+                    </p>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="flame_gamma">
+                            {"Gamma"}
+                        </label>
+                        <input class="input" id="flame_gamma" name="flame_gamma"
+                            type="number" min="1" step="0.1" ref={self.gamma_ref.clone()}
+                            value={config.gamma.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="flame_points">
+                            {"Points"}
+                        </label>
+                        <input class="input" id="flame_points" name="flame_points"
+                            type="number" min="1000" max="4000000000" ref={self.points_ref.clone()}
+                            value={config.max_points.to_string()}/>
+                    </div>
+                    <button class="editor_button" id="flame_reset_params" onclick={reset_params}>
+                        {"Reset to Default"}
+                    </button>
+                </div>
+                {
+                    for self.transforms.iter().enumerate().map(|(idx, transform)| {
+                        Self::view_transform(ctx, idx, transform)
+                    })
+                }
+                <div class="input_cntr">
+                    <button class="editor_button" id="flame_add_transform" onclick={add_transform}>
+                        {"Add Transform"}
+                    </button>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <div class="area_cntr">
+                            <div class="input_inner">
+                                <label class="input_label" for="flame_max_real">
+                                    {"X Max."}
+                                </label>
+                                <input class="input" id="flame_max_real" name="flame_max_real"
+                                    type="number" step="0.0000001" ref={self.x_max_real_ref.clone()}
+                                    value={config.x_max.real().to_string()}/>
+                            </div>
+                            <div class="input_inner">
+                                <label class="input_label" for="flame_min_real">
+                                    {"X Min."}
+                                </label>
+                                <input class="input" id="flame_min_real" name="flame_min_real"
+                                    type="number" step="0.0000001" ref={self.x_min_real_ref.clone()}
+                                    value={config.x_min.real().to_string()}/>
+                            </div>
+                        </div>
+                        <div class="area_cntr">
+                            <div class="input_inner">
+                                <label class="input_label" for="flame_max_imag">
+                                    {"Y Max."}
+                                </label>
+                                <input class="input" id="flame_max_imag" name="flame_max_imag"
+                                    type="number" step="0.0000001" ref={self.x_max_imag_ref.clone()}
+                                    value={config.x_max.imag().to_string()}/>
+                            </div>
+                            <div class="input_inner">
+                                <label class="input_label" for="flame_min_imag">
+                                    {"Y Min."}
+                                </label>
+                                <input class="input" id="flame_min_imag" name="flame_min_imag"
+                                    type="number" step="0.0000001" ref={self.x_min_imag_ref.clone()}
+                                    value={config.x_min.imag().to_string()}/>
+                            </div>
+                        </div>
+                        <div class="area_cntr">
+                            <button class="editor_button" id="flame_reset_area"
+                                    onclick={reset_area}>
+                                {"Reset to Default"}
+                            </button>
+                            <button class="editor_button" id="flame_zoom_out"
+                                    onclick={zoom_out}>
+                                {"Zoom Out"}
+                            </button>
+                        </div>
+                    </div>
+                </div>
+                <div class="edit_button_cntr">
+                    <button class="editor_button" id="flame_save" onclick={save_config}>
+                        {"Save"}
+                    </button>
+                    <button class="editor_button" id="flame_cancel" onclick={cancel}>
+                        {"Cancel"}
+                    </button>
+                </div>
+            </div>
+        ]
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct EditFlameCfgProps {
+    pub edit_mode: bool,
+    pub config: FlameCfg,
+    pub canvas_width: u32,
+    pub canvas_height: u32,
+    pub cb_saved: Callback<FlameCfg>,
+    pub cb_canceled: Callback<()>,
+}
@@ -0,0 +1,511 @@
+use web_sys::{Element, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::agents::canvas_msg_bus::{ControlMsgBus, ControlMsgRequest};
+use crate::work::{
+    complex::Complex,
+    fractal::{
+        select_area, HybridCfg, HybridFormula, HybridStep, RenderMode, HYBRID_DEFAULT_C_MAX,
+        HYBRID_DEFAULT_C_MIN, HYBRID_DEFAULT_ITERATIONS, JULIA_DEFAULT_C,
+    },
+    util::{get_f64_from_ref, get_u32_from_ref, set_value_on_input_ref},
+};
+use yew_agent::{Bridge, Bridged};
+
+#[cfg(feature = "use_katex")]
+use katex::render;
+#[cfg(feature = "use_katex")]
+use web_sys::HtmlDivElement;
+
+#[cfg(feature = "use_katex")]
+const USE_KATEX: bool = true;
+
+#[cfg(not(feature = "use_katex"))]
+const USE_KATEX: bool = false;
+
+/// Labels of the parts of the fixed c of a step, the index is used in `Msg::CChanged`
+const C_FIELDS: [&str; 2] = ["c_real", "c_imag"];
+
+#[allow(clippy::enum_variant_names)]
+pub enum Msg {
+    FormulaChanged(usize, String),
+    PowerChanged(usize, String),
+    FixedCChanged(usize, bool),
+    CChanged(usize, usize, String),
+    AddStep,
+    RemoveStep(usize),
+    ResetParams,
+    ZoomOut,
+    ResetArea,
+    SaveConfig,
+    Cancel,
+    CanvasMsg(ControlMsgRequest),
+}
+
+pub struct EditHybridCfg {
+    container_ref: NodeRef,
+    iter_ref: NodeRef,
+    c_min_real_ref: NodeRef,
+    c_min_imag_ref: NodeRef,
+    c_max_real_ref: NodeRef,
+    c_max_imag_ref: NodeRef,
+    formula_ref: NodeRef,
+    steps: Vec<HybridStep>,
+    _producer: Box<dyn Bridge<ControlMsgBus>>,
+}
+
+impl EditHybridCfg {
+    fn formula_value(formula: HybridFormula) -> String {
+        format!("formula_{:?}", formula).to_lowercase()
+    }
+
+    fn get_f64(node_ref: &NodeRef, name: &str, default: f64) -> f64 {
+        get_f64_from_ref(node_ref, name).map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+                default
+            },
+            |v| v,
+        )
+    }
+
+    fn set_values(&self, values: &[(&NodeRef, &str, f64)]) {
+        for (node_ref, name, value) in values {
+            set_value_on_input_ref(node_ref, name, value.to_string().as_str()).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+    }
+
+    fn set_area(&self, c_min: (f64, f64), c_max: (f64, f64)) {
+        self.set_values(&[
+            (&self.c_max_real_ref, "c_max_real", c_max.0),
+            (&self.c_max_imag_ref, "c_max_imag", c_max.1),
+            (&self.c_min_real_ref, "c_min_real", c_min.0),
+            (&self.c_min_imag_ref, "c_min_imag", c_min.1),
+        ]);
+    }
+
+    fn view_step(ctx: &Context<Self>, idx: usize, step: &HybridStep) -> Html {
+        let selected = Self::formula_value(step.formula);
+        let on_formula_changed = ctx.link().callback(move |event: Event| {
+            Msg::FormulaChanged(
+                idx,
+                event.target_unchecked_into::<HtmlSelectElement>().value(),
+            )
+        });
+        let on_power_changed = ctx.link().callback(move |event: Event| {
+            Msg::PowerChanged(
+                idx,
+                event.target_unchecked_into::<HtmlInputElement>().value(),
+            )
+        });
+        let on_fixed_c_changed = ctx.link().callback(move |event: Event| {
+            Msg::FixedCChanged(
+                idx,
+                event.target_unchecked_into::<HtmlInputElement>().checked(),
+            )
+        });
+        let remove = ctx.link().callback(move |_| Msg::RemoveStep(idx));
+        let formula_id = format!("hybrid_formula_{}", idx);
+        let power_id = format!("hybrid_power_{}", idx);
+        let fixed_c_id = format!("hybrid_fixed_c_{}", idx);
+
+        html![
+            <div class="input_cntr">
+                <div class="input_inner">
+                    <label class="input_label" for={formula_id.clone()}>
+                        {format!("Step {}", idx + 1)}
+                    </label>
+                    <select class="input" id={formula_id.clone()} name={formula_id}
+                        onchange={on_formula_changed}>
+                        {
+                            for HybridFormula::ALL.iter().map(|formula| {
+                                let value = Self::formula_value(*formula);
+                                html![
+                                    <option value={value.clone()} selected={value == selected}>
+                                        {formula.name()}
+                                    </option>
+                                ]
+                            })
+                        }
+                    </select>
+                </div>
+                <div class="input_inner">
+                    <label class="input_label" for={power_id.clone()}>{"Power"}</label>
+                    <input class="input" id={power_id.clone()} name={power_id}
+                        type="number" min="2" max="20" value={step.power.to_string()}
+                        disabled={step.formula == HybridFormula::Lambda}
+                        onchange={on_power_changed}/>
+                </div>
+                <div class="input_inner">
+                    <label class="input_label" for={fixed_c_id.clone()}>{"Fixed C"}</label>
+                    <input class="input" id={fixed_c_id.clone()} name={fixed_c_id}
+                        type="checkbox" checked={step.c.is_some()}
+                        onchange={on_fixed_c_changed}/>
+                </div>
+                {
+                    for step.c.iter().flat_map(|c| {
+                        C_FIELDS.iter().zip([c.real(), c.imag()]).enumerate().map(|(field, (label, value))| {
+                            let id = format!("hybrid_{}_{}", label, idx);
+                            let onchange = ctx.link().callback(move |event: Event| {
+                                Msg::CChanged(
+                                    idx,
+                                    field,
+                                    event.target_unchecked_into::<HtmlInputElement>().value(),
+                                )
+                            });
+                            html![
+                                <div class="input_inner">
+                                    <label class="input_label" for={id.clone()}>{label}</label>
+                                    <input class="input" id={id.clone()} name={id}
+                                        type="number" step="0.0000001" value={value.to_string()}
+                                        {onchange}/>
+                                </div>
+                            ]
+                        })
+                    })
+                }
+                <button class="editor_button" id={format!("hybrid_remove_{}", idx)} onclick={remove}>
+                    {"Remove"}
+                </button>
+            </div>
+        ]
+    }
+}
+
+impl Component for EditHybridCfg {
+    type Message = Msg;
+    type Properties = EditHybridCfgProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        EditHybridCfg {
+            container_ref: NodeRef::default(),
+            iter_ref: NodeRef::default(),
+            c_max_real_ref: NodeRef::default(),
+            c_max_imag_ref: NodeRef::default(),
+            c_min_real_ref: NodeRef::default(),
+            c_min_imag_ref: NodeRef::default(),
+            formula_ref: NodeRef::default(),
+            steps: ctx.props().config.steps.clone(),
+            _producer: ControlMsgBus::bridge(ctx.link().callback(Msg::CanvasMsg)),
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        self.steps = ctx.props().config.steps.clone();
+        true
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let config = &ctx.props().config;
+        match msg {
+            Msg::Cancel => {
+                info!("EditHybridCfg: got msg Cancel");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+                self.steps = config.steps.clone();
+                ctx.props().cb_canceled.emit(());
+                true
+            }
+            Msg::SaveConfig => {
+                info!("EditHybridCfg: got msg SaveConfig");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+
+                let max_iterations = get_u32_from_ref(&self.iter_ref, "iterations").map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        config.max_iterations
+                    },
+                    |v| v,
+                );
+
+                ctx.props().cb_saved.emit(HybridCfg {
+                    steps: self.steps.clone(),
+                    max_iterations,
+                    c_max: Complex::new(
+                        Self::get_f64(&self.c_max_real_ref, "c_max_real", config.c_max.real()),
+                        Self::get_f64(&self.c_max_imag_ref, "c_max_imag", config.c_max.imag()),
+                    ),
+                    c_min: Complex::new(
+                        Self::get_f64(&self.c_min_real_ref, "c_min_real", config.c_min.real()),
+                        Self::get_f64(&self.c_min_imag_ref, "c_min_imag", config.c_min.imag()),
+                    ),
+                    color_cfg_name: config.color_cfg_name.clone(),
+                    transfer: config.transfer,
+                });
+                false
+            }
+            Msg::FormulaChanged(idx, value) => {
+                match HybridFormula::ALL
+                    .iter()
+                    .find(|formula| Self::formula_value(**formula) == value)
+                {
+                    Some(formula) => {
+                        if let Some(step) = self.steps.get_mut(idx) {
+                            step.formula = *formula;
+                        }
+                    }
+                    None => error!("invalid formula '{}'", value),
+                }
+                true
+            }
+            Msg::PowerChanged(idx, value) => {
+                match value.parse::<u32>() {
+                    Ok(power) if power >= 2 => {
+                        if let Some(step) = self.steps.get_mut(idx) {
+                            step.power = power;
+                        }
+                    }
+                    Ok(power) => error!("invalid power {} of step {}", power, idx),
+                    Err(err) => error!("invalid power of step {}: {}", idx, err),
+                }
+                false
+            }
+            Msg::FixedCChanged(idx, fixed) => {
+                info!("EditHybridCfg: got msg FixedCChanged({}, {})", idx, fixed);
+                if let Some(step) = self.steps.get_mut(idx) {
+                    step.c = if fixed {
+                        Some(Complex::new(JULIA_DEFAULT_C.0, JULIA_DEFAULT_C.1))
+                    } else {
+                        None
+                    };
+                }
+                true
+            }
+            Msg::CChanged(idx, field, value) => {
+                match value.parse::<f64>() {
+                    Ok(value) => {
+                        if let Some(c) = self.steps.get_mut(idx).and_then(|step| step.c.as_mut()) {
+                            *c = if field == 0 {
+                                Complex::new(value, c.imag())
+                            } else {
+                                Complex::new(c.real(), value)
+                            };
+                        }
+                    }
+                    Err(err) => error!(
+                        "invalid value for {} of step {}: {}",
+                        C_FIELDS[field], idx, err
+                    ),
+                }
+                false
+            }
+            Msg::AddStep => {
+                info!("EditHybridCfg: got msg AddStep");
+                self.steps.push(HybridStep::default());
+                true
+            }
+            Msg::RemoveStep(idx) => {
+                info!("EditHybridCfg: got msg RemoveStep({})", idx);
+                // a hybrid needs at least one step
+                if idx < self.steps.len() && self.steps.len() > 1 {
+                    self.steps.remove(idx);
+                }
+                true
+            }
+            Msg::ResetParams => {
+                info!("EditHybridCfg: got msg ResetParams");
+                self.set_values(&[(
+                    &self.iter_ref,
+                    "max_iterations",
+                    f64::from(HYBRID_DEFAULT_ITERATIONS),
+                )]);
+                self.steps = HybridCfg::default_steps();
+                true
+            }
+            Msg::ResetArea => {
+                info!("EditHybridCfg: got msg ResetArea");
+                self.set_area(HYBRID_DEFAULT_C_MIN, HYBRID_DEFAULT_C_MAX);
+                false
+            }
+            Msg::ZoomOut => {
+                info!("EditHybridCfg: got msg ZoomOut");
+                let width = config.c_max.real() - config.c_min.real();
+                let height = config.c_max.imag() - config.c_min.imag();
+                self.set_area(
+                    (
+                        config.c_min.real() - width / 2.0,
+                        config.c_min.imag() - height / 2.0,
+                    ),
+                    (
+                        config.c_max.real() + width / 2.0,
+                        config.c_max.imag() + height / 2.0,
+                    ),
+                );
+                false
+            }
+            Msg::CanvasMsg(canvas_msg) => {
+                info!("EditHybridCfg: got msg CanvasMsg");
+                match canvas_msg {
+                    ControlMsgRequest::CanvasSelectMsg(coords) if ctx.props().edit_mode => {
+                        let (min, max) = select_area(
+                            &config.c_min,
+                            &config.c_max,
+                            ctx.props().canvas_width,
+                            ctx.props().canvas_height,
+                            ctx.props().render_mode,
+                            coords,
+                        );
+                        self.set_area((min.real(), min.imag()), (max.real(), max.imag()));
+                        false
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let add_step = ctx.link().callback(|_| Msg::AddStep);
+        let reset_area = ctx.link().callback(|_| Msg::ResetArea);
+        let reset_params = ctx.link().callback(|_| Msg::ResetParams);
+        let zoom_out = ctx.link().callback(|_| Msg::ZoomOut);
+        let save_config = ctx.link().callback(|_| Msg::SaveConfig);
+        let cancel = ctx.link().callback(|_| Msg::Cancel);
+        let cntr_class = if ctx.props().edit_mode {
+            "edit_cntr_visible"
+        } else {
+            "edit_cntr_hidden"
+        };
+
+        let config = &ctx.props().config;
+
+        html![
+            <div class={cntr_class} id="hybrid_edit_cntr" ref={self.container_ref.clone()}>
+                <div class="input_cntr">
+                    <p class="hint_text">
+                        {"Hint: The steps are applied in turn and the sequence repeats until the orbit \
+                          escapes, every step counts as one iteration. A step with a fixed c is a Julia \
+                          step, the others take c from the pixel. You can select a rectangle in the draw \
+                          area to import the coordinates into the editor."}
+                    </p>
+                </div>
+                {
+                    if USE_KATEX {
+                        html![
+                            <div class="input_cntr">
+                                <p class="formula_label" >{"Iterating over:"}</p>
+                                <div class="formula_cntr" ref={self.formula_ref.clone()}></div>
+                            </div>
+                        ]
+                    } else {
+                        html![]
+                    }
+                }
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="hybrid_iterations">
+                            {"Iterations"}
+                        </label>
+                        <input class="input" id="hybrid_iterations" name="hybrid_iterations"
+                            type="number" min="10" max="10000000" ref={self.iter_ref.clone()}
+                            value={config.max_iterations.to_string()}/>
+                    </div>
+                    <button class="editor_button" id="hybrid_reset_params" onclick={reset_params}>
+                        {"Reset to Default"}
+                    </button>
+                </div>
+                {
+                    for self.steps.iter().enumerate().map(|(idx, step)| {
+                        Self::view_step(ctx, idx, step)
+                    })
+                }
+                <div class="input_cntr">
+                    <button class="editor_button" id="hybrid_add_step" onclick={add_step}>
+                        {"Add Step"}
+                    </button>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <div class="area_cntr">
+                            <div class="input_inner">
+                                <label class="input_label" for="hybrid_max_real">
+                                    {"C Max. Real"}
+                                </label>
+                                <input class="input" id="hybrid_max_real" name="hybrid_max_real"
+                                    type="number" step="0.0000001" ref={self.c_max_real_ref.clone()}
+                                    value={config.c_max.real().to_string()}/>
+                            </div>
+                            <div class="input_inner">
+                                <label class="input_label" for="hybrid_min_real">
+                                    {"C Min. Real"}
+                                </label>
+                                <input class="input" id="hybrid_min_real" name="hybrid_min_real"
+                                    type="number" step="0.0000001" ref={self.c_min_real_ref.clone()}
+                                    value={config.c_min.real().to_string()}/>
+                            </div>
+                        </div>
+                        <div class="area_cntr">
+                            <div class="input_inner">
+                                <label class="input_label" for="hybrid_max_imag">
+                                    {"C Max. Imag"}
+                                </label>
+                                <input class="input" id="hybrid_max_imag" name="hybrid_max_imag"
+                                    type="number" step="0.0000001" ref={self.c_max_imag_ref.clone()}
+                                    value={config.c_max.imag().to_string()}/>
+                            </div>
+                            <div class="input_inner">
+                                <label class="input_label" for="hybrid_min_imag">
+                                    {"C Min. Imag"}
+                                </label>
+                                <input class="input" id="hybrid_min_imag" name="hybrid_min_imag"
+                                    type="number" step="0.0000001" ref={self.c_min_imag_ref.clone()}
+                                    value={config.c_min.imag().to_string()}/>
+                            </div>
+                        </div>
+                        <div class="area_cntr">
+                            <button class="editor_button" id="hybrid_reset_area"
+                                    onclick={reset_area}>
+                                {"Reset to Default"}
+                            </button>
+                            <button class="editor_button" id="hybrid_zoom_out"
+                                    onclick={zoom_out}>
+                                {"Zoom Out"}
+                            </button>
+                        </div>
+                    </div>
+                </div>
+                <div class="edit_button_cntr">
+                    <button class="editor_button" id="hybrid_save" onclick={save_config}>
+                        {"Save"}
+                    </button>
+                    <button class="editor_button" id="hybrid_cancel" onclick={cancel}>
+                        {"Cancel"}
+                    </button>
+                </div>
+            </div>
+        ]
+    }
+
+    #[cfg(feature = "use_katex")]
+    fn rendered(&mut self, _ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            let formula = render("\\Large z_{n+1} = f_{n \\bmod k}(z_n, c), z_0 = z_{crit}(f_0)")
+                .expect("Katex failed to render formula");
+            self.formula_ref
+                .cast::<HtmlDivElement>()
+                .expect("Formula Div not found")
+                .set_inner_html(formula.as_str());
+        }
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct EditHybridCfgProps {
+    pub edit_mode: bool,
+    pub config: HybridCfg,
+    pub canvas_width: u32,
+    pub canvas_height: u32,
+    pub render_mode: RenderMode,
+    pub cb_saved: Callback<HybridCfg>,
+    pub cb_canceled: Callback<()>,
+}
@@ -0,0 +1,413 @@
+use web_sys::{Element, HtmlInputElement};
+use yew::prelude::*;
+
+use crate::agents::canvas_msg_bus::{ControlMsgBus, ControlMsgRequest};
+use crate::work::{
+    complex::Complex,
+    fractal::{AffineTransform, IfsCfg, IfsPreset},
+    util::{get_f64_from_ref, get_select_value_from_ref, get_u32_from_ref, set_value_on_input_ref},
+};
+use yew_agent::{Bridge, Bridged};
+
+/// Labels of the editable fields of a transform, the index is used in `Msg::TransformChanged`
+const TRANSFORM_FIELDS: [&str; 7] = ["a", "b", "c", "d", "e", "f", "p"];
+
+#[allow(clippy::enum_variant_names)]
+pub enum Msg {
+    TransformChanged(usize, usize, String),
+    AddTransform,
+    RemoveTransform(usize),
+    LoadPreset,
+    ZoomOut,
+    ResetArea,
+    SaveConfig,
+    Cancel,
+    CanvasMsg(ControlMsgRequest),
+}
+
+pub struct EditIfsCfg {
+    container_ref: NodeRef,
+    preset_ref: NodeRef,
+    points_ref: NodeRef,
+    x_min_real_ref: NodeRef,
+    x_min_imag_ref: NodeRef,
+    x_max_real_ref: NodeRef,
+    x_max_imag_ref: NodeRef,
+    transforms: Vec<AffineTransform>,
+    _producer: Box<dyn Bridge<ControlMsgBus>>,
+}
+
+impl EditIfsCfg {
+    fn get_preset(&self) -> IfsPreset {
+        match get_select_value_from_ref(&self.preset_ref, "ifs_preset")
+            .map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    String::new()
+                },
+                |v| v,
+            )
+            .as_str()
+        {
+            "preset_fern" => IfsPreset::BarnsleyFern,
+            "preset_sierpinski" => IfsPreset::Sierpinski,
+            "preset_levy_dragon" => IfsPreset::LevyDragon,
+            val => {
+                error!("invalid IFS preset '{}'", val);
+                IfsPreset::BarnsleyFern
+            }
+        }
+    }
+
+    fn get_f64(node_ref: &NodeRef, name: &str, default: f64) -> f64 {
+        get_f64_from_ref(node_ref, name).map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+                default
+            },
+            |v| v,
+        )
+    }
+
+    fn set_area(&self, x_min: &Complex, x_max: &Complex) {
+        for (node_ref, name, value) in [
+            (&self.x_max_real_ref, "x_max_real", x_max.real()),
+            (&self.x_max_imag_ref, "x_max_imag", x_max.imag()),
+            (&self.x_min_real_ref, "x_min_real", x_min.real()),
+            (&self.x_min_imag_ref, "x_min_imag", x_min.imag()),
+        ] {
+            set_value_on_input_ref(node_ref, name, value.to_string().as_str()).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+    }
+
+    fn field_mut(transform: &mut AffineTransform, field: usize) -> &mut f64 {
+        match field {
+            0 => &mut transform.a,
+            1 => &mut transform.b,
+            2 => &mut transform.c,
+            3 => &mut transform.d,
+            4 => &mut transform.e,
+            5 => &mut transform.f,
+            _ => &mut transform.probability,
+        }
+    }
+
+    fn view_transform(ctx: &Context<Self>, idx: usize, transform: &AffineTransform) -> Html {
+        let values = [
+            transform.a,
+            transform.b,
+            transform.c,
+            transform.d,
+            transform.e,
+            transform.f,
+            transform.probability,
+        ];
+        let remove = ctx.link().callback(move |_| Msg::RemoveTransform(idx));
+
+        html![
+            <div class="input_cntr">
+                {
+                    for TRANSFORM_FIELDS.iter().zip(values).enumerate().map(|(field, (label, value))| {
+                        let id = format!("ifs_{}_{}", label, idx);
+                        let onchange = ctx.link().callback(move |event: Event| {
+                            Msg::TransformChanged(
+                                idx,
+                                field,
+                                event.target_unchecked_into::<HtmlInputElement>().value(),
+                            )
+                        });
+                        html![
+                            <div class="input_inner">
+                                <label class="input_label" for={id.clone()}>{label}</label>
+                                <input class="input" id={id.clone()} name={id}
+                                    type="number" step="0.0000001" value={value.to_string()}
+                                    {onchange}/>
+                            </div>
+                        ]
+                    })
+                }
+                <button class="editor_button" id={format!("ifs_remove_{}", idx)} onclick={remove}>
+                    {"Remove"}
+                </button>
+            </div>
+        ]
+    }
+}
+
+impl Component for EditIfsCfg {
+    type Message = Msg;
+    type Properties = EditIfsCfgProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        EditIfsCfg {
+            container_ref: NodeRef::default(),
+            preset_ref: NodeRef::default(),
+            points_ref: NodeRef::default(),
+            x_max_real_ref: NodeRef::default(),
+            x_max_imag_ref: NodeRef::default(),
+            x_min_real_ref: NodeRef::default(),
+            x_min_imag_ref: NodeRef::default(),
+            transforms: ctx.props().config.transforms.clone(),
+            _producer: ControlMsgBus::bridge(ctx.link().callback(Msg::CanvasMsg)),
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        self.transforms = ctx.props().config.transforms.clone();
+        true
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let config = &ctx.props().config;
+        match msg {
+            Msg::Cancel => {
+                info!("EditIfsCfg: got msg Cancel");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+                self.transforms = config.transforms.clone();
+                ctx.props().cb_canceled.emit(());
+                true
+            }
+            Msg::SaveConfig => {
+                info!("EditIfsCfg: got msg SaveConfig");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+
+                let max_points = get_u32_from_ref(&self.points_ref, "points").map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        config.max_points
+                    },
+                    |v| v,
+                );
+
+                ctx.props().cb_saved.emit(IfsCfg {
+                    transforms: self.transforms.clone(),
+                    x_max: Complex::new(
+                        Self::get_f64(&self.x_max_real_ref, "x_max_real", config.x_max.real()),
+                        Self::get_f64(&self.x_max_imag_ref, "x_max_imag", config.x_max.imag()),
+                    ),
+                    x_min: Complex::new(
+                        Self::get_f64(&self.x_min_real_ref, "x_min_real", config.x_min.real()),
+                        Self::get_f64(&self.x_min_imag_ref, "x_min_imag", config.x_min.imag()),
+                    ),
+                    max_points,
+                    color_cfg_name: config.color_cfg_name.clone(),
+                });
+                false
+            }
+            Msg::TransformChanged(idx, field, value) => {
+                match value.parse::<f64>() {
+                    Ok(value) => {
+                        if let Some(transform) = self.transforms.get_mut(idx) {
+                            *Self::field_mut(transform, field) = value;
+                        }
+                    }
+                    Err(err) => error!(
+                        "invalid value for {} of transform {}: {}",
+                        TRANSFORM_FIELDS[field], idx, err
+                    ),
+                }
+                false
+            }
+            Msg::AddTransform => {
+                info!("EditIfsCfg: got msg AddTransform");
+                self.transforms.push(AffineTransform::default());
+                true
+            }
+            Msg::RemoveTransform(idx) => {
+                info!("EditIfsCfg: got msg RemoveTransform({})", idx);
+                if idx < self.transforms.len() {
+                    self.transforms.remove(idx);
+                }
+                true
+            }
+            Msg::LoadPreset => {
+                info!("EditIfsCfg: got msg LoadPreset");
+                let (transforms, x_min, x_max) = self.get_preset().transforms();
+                self.transforms = transforms;
+                self.set_area(&x_min, &x_max);
+                true
+            }
+            Msg::ResetArea => {
+                info!("EditIfsCfg: got msg ResetArea");
+                let (_, x_min, x_max) = self.get_preset().transforms();
+                self.set_area(&x_min, &x_max);
+                false
+            }
+            Msg::ZoomOut => {
+                info!("EditIfsCfg: got msg ZoomOut");
+                let center = (config.x_max + config.x_min) * 0.5;
+                self.set_area(
+                    &(config.x_min - (center - config.x_min)),
+                    &(config.x_max + (config.x_max - center)),
+                );
+                false
+            }
+            Msg::CanvasMsg(canvas_msg) => {
+                info!("EditIfsCfg: got msg CanvasMsg");
+                match canvas_msg {
+                    ControlMsgRequest::CanvasSelectMsg(coords) if ctx.props().edit_mode => {
+                        let x_scale = (config.x_max.real() - config.x_min.real())
+                            / f64::from(ctx.props().canvas_width);
+                        let y_scale = (config.x_max.imag() - config.x_min.imag())
+                            / f64::from(ctx.props().canvas_height);
+
+                        // the y axis of an IFS points up, the top row is x_max.imag
+                        self.set_area(
+                            &Complex::new(
+                                config.x_min.real() + x_scale * f64::from(coords.0),
+                                config.x_max.imag() - y_scale * f64::from(coords.3),
+                            ),
+                            &Complex::new(
+                                config.x_min.real() + x_scale * f64::from(coords.2),
+                                config.x_max.imag() - y_scale * f64::from(coords.1),
+                            ),
+                        );
+                        false
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let add_transform = ctx.link().callback(|_| Msg::AddTransform);
+        let load_preset = ctx.link().callback(|_| Msg::LoadPreset);
+        let reset_area = ctx.link().callback(|_| Msg::ResetArea);
+        let zoom_out = ctx.link().callback(|_| Msg::ZoomOut);
+        let save_config = ctx.link().callback(|_| Msg::SaveConfig);
+        let cancel = ctx.link().callback(|_| Msg::Cancel);
+        let cntr_class = if ctx.props().edit_mode {
+            "edit_cntr_visible"
+        } else {
+            "edit_cntr_hidden"
+        };
+
+        let config = &ctx.props().config;
+
+        html![
+            <div class={cntr_class} id="ifs_edit_cntr" ref={self.container_ref.clone()}>
+                <div class="input_cntr">
+                    <p class="hint_text">
+                        {"Hint: Every transform maps (x, y) to (a x + b y + e, c x + d y + f) and is \
+                          picked with probability p. You can select a rectangle in the draw area to \
+                          import the coordiates into the editor."}
+                    </p>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="ifs_preset">
+                            {"Preset"}
+                        </label>
+                        <select class="input" id="ifs_preset" name="ifs_preset"
+                            ref={self.preset_ref.clone()}>
+                            <option value="preset_fern">{"Barnsley Fern"}</option>
+                            <option value="preset_sierpinski">{"Sierpinski Triangle"}</option>
+                            <option value="preset_levy_dragon">{"Lévy Dragon"}</option>
+                        </select>
+                    </div>
+                    <button class="editor_button" id="ifs_load_preset" onclick={load_preset}>
+                        {"Load Preset"}
+                    </button>
+                    <div class="input_inner">
+                        <label class="input_label" for="ifs_points">
+                            {"Points"}
+                        </label>
+                        <input class="input" id="ifs_points" name="ifs_points"
+                            type="number" min="1000" max="4000000000" ref={self.points_ref.clone()}
+                            value={config.max_points.to_string()}/>
+                    </div>
+                </div>
+                {
+                    for self.transforms.iter().enumerate().map(|(idx, transform)| {
+                        Self::view_transform(ctx, idx, transform)
+                    })
+                }
+                <div class="input_cntr">
+                    <button class="editor_button" id="ifs_add_transform" onclick={add_transform}>
+                        {"Add Transform"}
+                    </button>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <div class="area_cntr">
+                            <div class="input_inner">
+                                <label class="input_label" for="ifs_max_real">
+                                    {"X Max."}
+                                </label>
+                                <input class="input" id="ifs_max_real" name="ifs_max_real"
+                                    type="number" step="0.0000001" ref={self.x_max_real_ref.clone()}
+                                    value={config.x_max.real().to_string()}/>
+                            </div>
+                            <div class="input_inner">
+                                <label class="input_label" for="ifs_min_real">
+                                    {"X Min."}
+                                </label>
+                                <input class="input" id="ifs_min_real" name="ifs_min_real"
+                                    type="number" step="0.0000001" ref={self.x_min_real_ref.clone()}
+                                    value={config.x_min.real().to_string()}/>
+                            </div>
+                        </div>
+                        <div class="area_cntr">
+                            <div class="input_inner">
+                                <label class="input_label" for="ifs_max_imag">
+                                    {"Y Max."}
+                                </label>
+                                <input class="input" id="ifs_max_imag" name="ifs_max_imag"
+                                    type="number" step="0.0000001" ref={self.x_max_imag_ref.clone()}
+                                    value={config.x_max.imag().to_string()}/>
+                            </div>
+                            <div class="input_inner">
+                                <label class="input_label" for="ifs_min_imag">
+                                    {"Y Min."}
+                                </label>
+                                <input class="input" id="ifs_min_imag" name="ifs_min_imag"
+                                    type="number" step="0.0000001" ref={self.x_min_imag_ref.clone()}
+                                    value={config.x_min.imag().to_string()}/>
+                            </div>
+                        </div>
+                        <div class="area_cntr">
+                            <button class="editor_button" id="ifs_reset_area"
+                                    onclick={reset_area}>
+                                {"Reset to Preset"}
+                            </button>
+                            <button class="editor_button" id="ifs_zoom_out"
+                                    onclick={zoom_out}>
+                                {"Zoom Out"}
+                            </button>
+                        </div>
+                    </div>
+                </div>
+                <div class="edit_button_cntr">
+                    <button class="editor_button" id="ifs_save" onclick={save_config}>
+                        {"Save"}
+                    </button>
+                    <button class="editor_button" id="ifs_cancel" onclick={cancel}>
+                        {"Cancel"}
+                    </button>
+                </div>
+            </div>
+        ]
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct EditIfsCfgProps {
+    pub edit_mode: bool,
+    pub config: IfsCfg,
+    pub canvas_width: u32,
+    pub canvas_height: u32,
+    pub cb_saved: Callback<IfsCfg>,
+    pub cb_canceled: Callback<()>,
+}
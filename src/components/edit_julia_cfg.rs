@@ -5,8 +5,14 @@ use yew::prelude::*;
 use crate::agents::canvas_msg_bus::{ControlMsgBus, ControlMsgRequest};
 use crate::work::{
     complex::Complex,
-    fractal::{JULIA_DEFAULT_ITERATIONS, JULIA_DEFAULT_X_MAX, JULIA_DEFAULT_X_MIN},
-    util::{get_f64_from_ref, get_u32_from_ref, set_value_on_input_ref},
+    fractal::{
+        select_area, AutoIterations, Bailout, BailoutShape, JuliaRenderer, RenderMode,
+        IIM_DEFAULT_MAX_HITS, JULIA_DEFAULT_ITERATIONS, JULIA_DEFAULT_X_MAX, JULIA_DEFAULT_X_MIN,
+    },
+    util::{
+        get_checked_from_ref, get_f64_from_ref, get_select_value_from_ref, get_u32_from_ref,
+        set_checked_on_input_ref, set_value_on_input_ref, set_value_on_select_ref,
+    },
 };
 use yew_agent::{Bridge, Bridged};
 
@@ -36,6 +42,15 @@ pub enum Msg {
 pub struct EditJuliaCfg {
     container_ref: NodeRef,
     iter_ref: NodeRef,
+    auto_iter_ref: NodeRef,
+    auto_factor_ref: NodeRef,
+    auto_min_ref: NodeRef,
+    auto_max_ref: NodeRef,
+    auto_sample_ref: NodeRef,
+    renderer_ref: NodeRef,
+    iim_max_hits_ref: NodeRef,
+    bailout_shape_ref: NodeRef,
+    bailout_radius_ref: NodeRef,
     c_real_ref: NodeRef,
     c_imag_ref: NodeRef,
     x_min_real_ref: NodeRef,
@@ -47,6 +62,168 @@ pub struct EditJuliaCfg {
 }
 // config: Option<JuliaSetCfg>
 
+impl EditJuliaCfg {
+    fn get_auto_iterations(&self, ctx: &Context<Self>) -> AutoIterations {
+        let current = &ctx.props().config.auto_iterations;
+        let auto_iterations = AutoIterations {
+            enabled: get_checked_from_ref(&self.auto_iter_ref, "auto_iterations").map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    current.enabled
+                },
+                |v| v,
+            ),
+            factor: get_f64_from_ref(&self.auto_factor_ref, "auto_iter_factor").map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    current.factor
+                },
+                |v| v,
+            ),
+            min: get_u32_from_ref(&self.auto_min_ref, "auto_iter_min").map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    current.min
+                },
+                |v| v,
+            ),
+            max: get_u32_from_ref(&self.auto_max_ref, "auto_iter_max").map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    current.max
+                },
+                |v| v,
+            ),
+            sample: get_checked_from_ref(&self.auto_sample_ref, "auto_iter_sample").map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    current.sample
+                },
+                |v| v,
+            ),
+        };
+        // an inverted range keeps the previous one
+        if let Err(err) = auto_iterations.validate() {
+            error!("{}", err.as_str());
+            AutoIterations {
+                min: current.min,
+                max: current.max,
+                ..auto_iterations
+            }
+        } else {
+            auto_iterations
+        }
+    }
+
+    fn reset_auto_iterations(&self) {
+        let defaults = AutoIterations::default();
+        for (node_ref, name, value) in [
+            (
+                &self.auto_factor_ref,
+                "auto_iter_factor",
+                defaults.factor.to_string(),
+            ),
+            (
+                &self.auto_min_ref,
+                "auto_iter_min",
+                defaults.min.to_string(),
+            ),
+            (
+                &self.auto_max_ref,
+                "auto_iter_max",
+                defaults.max.to_string(),
+            ),
+        ] {
+            set_value_on_input_ref(node_ref, name, value.as_str()).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+        for (node_ref, name, checked) in [
+            (&self.auto_iter_ref, "auto_iterations", defaults.enabled),
+            (&self.auto_sample_ref, "auto_iter_sample", defaults.sample),
+        ] {
+            set_checked_on_input_ref(node_ref, name, checked).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+    }
+
+    fn bailout_value(shape: BailoutShape) -> String {
+        format!("bailout_{:?}", shape).to_lowercase()
+    }
+
+    fn get_bailout(&self, ctx: &Context<Self>) -> Bailout {
+        let current = &ctx.props().config.bailout;
+        let shape = get_select_value_from_ref(&self.bailout_shape_ref, "bailout_shape")
+            .map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    String::new()
+                },
+                |v| v,
+            );
+        Bailout {
+            shape: BailoutShape::ALL
+                .iter()
+                .copied()
+                .find(|candidate| Self::bailout_value(*candidate) == shape)
+                .unwrap_or_else(|| {
+                    error!("invalid bailout shape '{}'", shape);
+                    current.shape
+                }),
+            radius: get_f64_from_ref(&self.bailout_radius_ref, "bailout_radius")
+                .and_then(|radius| {
+                    if radius > 0.0 {
+                        Ok(radius)
+                    } else {
+                        Err(format!(
+                            "invalid bailout radius {}, it has to be positive",
+                            radius
+                        ))
+                    }
+                })
+                .map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        current.radius
+                    },
+                    |v| v,
+                ),
+        }
+    }
+
+    fn set_bailout(&self, bailout: &Bailout) {
+        set_value_on_select_ref(
+            &self.bailout_shape_ref,
+            "bailout_shape",
+            Self::bailout_value(bailout.shape).as_str(),
+        )
+        .map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+            },
+            |v| v,
+        );
+        set_value_on_input_ref(
+            &self.bailout_radius_ref,
+            "bailout_radius",
+            bailout.radius.to_string().as_str(),
+        )
+        .map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+            },
+            |v| v,
+        );
+    }
+}
+
 impl Component for EditJuliaCfg {
     type Message = Msg;
     type Properties = EditJuliaCfgProps;
@@ -55,6 +232,15 @@ impl Component for EditJuliaCfg {
         EditJuliaCfg {
             container_ref: NodeRef::default(),
             iter_ref: NodeRef::default(),
+            auto_iter_ref: NodeRef::default(),
+            auto_factor_ref: NodeRef::default(),
+            auto_min_ref: NodeRef::default(),
+            auto_max_ref: NodeRef::default(),
+            auto_sample_ref: NodeRef::default(),
+            renderer_ref: NodeRef::default(),
+            iim_max_hits_ref: NodeRef::default(),
+            bailout_shape_ref: NodeRef::default(),
+            bailout_radius_ref: NodeRef::default(),
             c_real_ref: NodeRef::default(),
             c_imag_ref: NodeRef::default(),
             x_max_real_ref: NodeRef::default(),
@@ -143,12 +329,48 @@ impl Component for EditJuliaCfg {
                     |v| v,
                 );
 
+                let auto_iterations = self.get_auto_iterations(ctx);
+
+                let renderer = match get_select_value_from_ref(&self.renderer_ref, "renderer")
+                    .map_or_else(
+                        |err| {
+                            error!("{}", err.as_str());
+                            String::new()
+                        },
+                        |v| v,
+                    )
+                    .as_str()
+                {
+                    "renderer_escape_time" => JuliaRenderer::EscapeTime,
+                    "renderer_iim" => JuliaRenderer::InverseIteration,
+                    val => {
+                        error!("invalid renderer '{}'", val);
+                        ctx.props().config.renderer
+                    }
+                };
+
+                let iim_max_hits = get_u32_from_ref(&self.iim_max_hits_ref, "iim_max_hits")
+                    .map_or_else(
+                        |err| {
+                            error!("{}", err.as_str());
+                            ctx.props().config.iim_max_hits
+                        },
+                        |v| v,
+                    );
+
+                let bailout = self.get_bailout(ctx);
+
                 ctx.props().cb_saved.emit(JuliaSetCfg {
                     max_iterations,
                     c: Complex::new(c_real, c_imag),
                     x_max: Complex::new(x_max_real, x_max_imag),
                     x_min: Complex::new(x_min_real, x_min_imag),
-                    color_cfg_name: None,
+                    color_cfg_name: ctx.props().config.color_cfg_name.clone(),
+                    auto_iterations,
+                    renderer,
+                    iim_max_hits,
+                    bailout,
+                    transfer: ctx.props().config.transfer,
                 });
                 false
             }
@@ -273,6 +495,19 @@ impl Component for EditJuliaCfg {
                     },
                     |v| v,
                 );
+                self.reset_auto_iterations();
+                set_value_on_input_ref(
+                    &self.iim_max_hits_ref,
+                    "iim_max_hits",
+                    IIM_DEFAULT_MAX_HITS.to_string().as_str(),
+                )
+                .map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                    },
+                    |v| v,
+                );
+                self.set_bailout(&Bailout::default());
                 false
             }
             Msg::CanvasMsg(canvas_msg) => {
@@ -281,21 +516,16 @@ impl Component for EditJuliaCfg {
                     ControlMsgRequest::CanvasSelectMsg(coords) => {
                         if ctx.props().edit_mode {
                             // TODO: implement
-                            let x_scale = (ctx.props().config.x_max.real()
-                                - ctx.props().config.x_min.real())
-                                / f64::from(ctx.props().canvas_width);
-                            let y_scale = (ctx.props().config.x_max.imag()
-                                - ctx.props().config.x_min.imag())
-                                / f64::from(ctx.props().canvas_height);
-
-                            let x_min =
-                                ctx.props().config.x_min.real() + x_scale * f64::from(coords.0);
-                            let y_min =
-                                ctx.props().config.x_min.imag() + y_scale * f64::from(coords.1);
-                            let x_max =
-                                ctx.props().config.x_min.real() + x_scale * f64::from(coords.2);
-                            let y_max =
-                                ctx.props().config.x_min.imag() + y_scale * f64::from(coords.3);
+                            let (min, max) = select_area(
+                                &ctx.props().config.x_min,
+                                &ctx.props().config.x_max,
+                                ctx.props().canvas_width,
+                                ctx.props().canvas_height,
+                                ctx.props().render_mode,
+                                coords,
+                            );
+                            let (x_min, y_min) = (min.real(), min.imag());
+                            let (x_max, y_max) = (max.real(), max.imag());
 
                             set_value_on_input_ref(
                                 &self.x_max_real_ref,
@@ -358,6 +588,11 @@ impl Component for EditJuliaCfg {
         let zoom_out = ctx.link().callback(|_| Msg::ZoomOut);
         let save_config = ctx.link().callback(|_| Msg::SaveConfig);
         let cancel = ctx.link().callback(|_| Msg::Cancel);
+        let sel_renderer = match ctx.props().config.renderer {
+            JuliaRenderer::EscapeTime => "renderer_escape_time",
+            JuliaRenderer::InverseIteration => "renderer_iim",
+        };
+        let sel_bailout = Self::bailout_value(ctx.props().config.bailout.shape);
         let cntr_class = if ctx.props().edit_mode {
             "edit_cntr_visible"
         } else {
@@ -389,7 +624,7 @@ impl Component for EditJuliaCfg {
                             {"Iterations"}
                         </label>
                         <input class="input" id="julia_iterations" name="julia_iterations"
-                            type="number" min="100" max="1000" ref={self.iter_ref.clone()}
+                            type="number" min="10" max="10000000" ref={self.iter_ref.clone()}
                             value={ctx.props().config.max_iterations.to_string()}/>
                     </div>
                     <div class="input_inner">
@@ -412,6 +647,104 @@ impl Component for EditJuliaCfg {
                         {"Reset to Default"}
                     </button>
                 </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="julia_auto_iter">
+                            {"Auto Iterations"}
+                        </label>
+                        <input class="input" id="julia_auto_iter" name="julia_auto_iter"
+                            type="checkbox" ref={self.auto_iter_ref.clone()}
+                            checked={ctx.props().config.auto_iterations.enabled}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="julia_auto_factor">
+                            {"Factor"}
+                        </label>
+                        <input class="input" id="julia_auto_factor" name="julia_auto_factor"
+                            type="number" min="0.1" step="0.1" ref={self.auto_factor_ref.clone()}
+                            value={ctx.props().config.auto_iterations.factor.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="julia_auto_min">
+                            {"Min."}
+                        </label>
+                        <input class="input" id="julia_auto_min" name="julia_auto_min"
+                            type="number" min="10" ref={self.auto_min_ref.clone()}
+                            value={ctx.props().config.auto_iterations.min.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="julia_auto_max">
+                            {"Max."}
+                        </label>
+                        <input class="input" id="julia_auto_max" name="julia_auto_max"
+                            type="number" min="10" ref={self.auto_max_ref.clone()}
+                            value={ctx.props().config.auto_iterations.max.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="julia_auto_sample">
+                            {"Sample View"}
+                        </label>
+                        <input class="input" id="julia_auto_sample" name="julia_auto_sample"
+                            type="checkbox" ref={self.auto_sample_ref.clone()}
+                            checked={ctx.props().config.auto_iterations.sample}/>
+                    </div>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="julia_renderer">
+                            {"Renderer"}
+                        </label>
+                        <select class="input" id="julia_renderer" name="julia_renderer"
+                            ref={self.renderer_ref.clone()}>
+                            <option value="renderer_escape_time"
+                                selected={sel_renderer=="renderer_escape_time"}>{"Escape Time"}</option>
+                            <option value="renderer_iim"
+                                selected={sel_renderer=="renderer_iim"}>{"Inverse Iteration"}</option>
+                        </select>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="julia_iim_max_hits">
+                            {"Max. Hits / Pixel"}
+                        </label>
+                        <input class="input" id="julia_iim_max_hits" name="julia_iim_max_hits"
+                            type="number" min="1" max="1000" ref={self.iim_max_hits_ref.clone()}
+                            value={ctx.props().config.iim_max_hits.to_string()}/>
+                    </div>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="julia_bailout_shape">
+                            {"Bailout"}
+                        </label>
+                        <select class="input" id="julia_bailout_shape" name="julia_bailout_shape"
+                            ref={self.bailout_shape_ref.clone()}>
+                            {
+                                for BailoutShape::ALL.iter().map(|shape| {
+                                    let value = Self::bailout_value(*shape);
+                                    html![
+                                        <option value={value.clone()} selected={value == sel_bailout}>
+                                            {shape.name()}
+                                        </option>
+                                    ]
+                                })
+                            }
+                        </select>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="julia_bailout_radius">
+                            {"Bailout Radius"}
+                        </label>
+                        <input class="input" id="julia_bailout_radius" name="julia_bailout_radius"
+                            type="number" min="0.1" step="0.1" ref={self.bailout_radius_ref.clone()}
+                            value={ctx.props().config.bailout.radius.to_string()}/>
+                    </div>
+                    <p class="hint_text">
+                        {"The bailout decides when an orbit has escaped, its shape and radius change the \
+                          bands and decorations outside of the set. Radii below the escape radius of c \
+                          count points of the set as escaped. Binary decomposition and field lines raise \
+                          the radius to 100."}
+                    </p>
+                </div>
                 <div class="input_cntr">
                     <div class="input_inner">
                         <div class="area_cntr">
@@ -493,6 +826,7 @@ pub struct EditJuliaCfgProps {
     pub config: JuliaSetCfg,
     pub canvas_width: u32,
     pub canvas_height: u32,
+    pub render_mode: RenderMode,
     pub cb_saved: Callback<JuliaSetCfg>,
     pub cb_canceled: Callback<()>,
 }
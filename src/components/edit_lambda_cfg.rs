@@ -0,0 +1,320 @@
+use web_sys::Element;
+use yew::prelude::*;
+
+use crate::agents::canvas_msg_bus::{ControlMsgBus, ControlMsgRequest};
+use crate::work::{
+    complex::Complex,
+    fractal::{
+        select_area, LambdaCfg, RenderMode, LAMBDA_DEFAULT_ITERATIONS, LAMBDA_DEFAULT_L_MAX,
+        LAMBDA_DEFAULT_L_MIN,
+    },
+    util::{get_f64_from_ref, get_u32_from_ref, set_value_on_input_ref},
+};
+use yew_agent::{Bridge, Bridged};
+
+#[cfg(feature = "use_katex")]
+use katex::render;
+#[cfg(feature = "use_katex")]
+use web_sys::HtmlDivElement;
+
+#[cfg(feature = "use_katex")]
+const USE_KATEX: bool = true;
+
+#[cfg(not(feature = "use_katex"))]
+const USE_KATEX: bool = false;
+
+#[allow(clippy::enum_variant_names)]
+pub enum Msg {
+    ResetParams,
+    ZoomOut,
+    ResetArea,
+    SaveConfig,
+    Cancel,
+    CanvasMsg(ControlMsgRequest),
+}
+
+pub struct EditLambdaCfg {
+    container_ref: NodeRef,
+    iter_ref: NodeRef,
+    l_min_real_ref: NodeRef,
+    l_min_imag_ref: NodeRef,
+    l_max_real_ref: NodeRef,
+    l_max_imag_ref: NodeRef,
+    formula_ref: NodeRef,
+    _producer: Box<dyn Bridge<ControlMsgBus>>,
+}
+
+impl EditLambdaCfg {
+    fn get_f64(node_ref: &NodeRef, name: &str, default: f64) -> f64 {
+        get_f64_from_ref(node_ref, name).map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+                default
+            },
+            |v| v,
+        )
+    }
+
+    fn set_values(&self, values: &[(&NodeRef, &str, f64)]) {
+        for (node_ref, name, value) in values {
+            set_value_on_input_ref(node_ref, name, value.to_string().as_str()).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+    }
+
+    fn set_area(&self, l_min: (f64, f64), l_max: (f64, f64)) {
+        self.set_values(&[
+            (&self.l_max_real_ref, "l_max_real", l_max.0),
+            (&self.l_max_imag_ref, "l_max_imag", l_max.1),
+            (&self.l_min_real_ref, "l_min_real", l_min.0),
+            (&self.l_min_imag_ref, "l_min_imag", l_min.1),
+        ]);
+    }
+}
+
+impl Component for EditLambdaCfg {
+    type Message = Msg;
+    type Properties = EditLambdaCfgProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        EditLambdaCfg {
+            container_ref: NodeRef::default(),
+            iter_ref: NodeRef::default(),
+            l_max_real_ref: NodeRef::default(),
+            l_max_imag_ref: NodeRef::default(),
+            l_min_real_ref: NodeRef::default(),
+            l_min_imag_ref: NodeRef::default(),
+            formula_ref: NodeRef::default(),
+            _producer: ControlMsgBus::bridge(ctx.link().callback(Msg::CanvasMsg)),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let config = &ctx.props().config;
+        match msg {
+            Msg::Cancel => {
+                info!("EditLambdaCfg: got msg Cancel");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+                ctx.props().cb_canceled.emit(());
+                false
+            }
+            Msg::SaveConfig => {
+                info!("EditLambdaCfg: got msg SaveConfig");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+
+                let max_iterations = get_u32_from_ref(&self.iter_ref, "iterations").map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        config.max_iterations
+                    },
+                    |v| v,
+                );
+
+                ctx.props().cb_saved.emit(LambdaCfg {
+                    max_iterations,
+                    l_max: Complex::new(
+                        Self::get_f64(&self.l_max_real_ref, "l_max_real", config.l_max.real()),
+                        Self::get_f64(&self.l_max_imag_ref, "l_max_imag", config.l_max.imag()),
+                    ),
+                    l_min: Complex::new(
+                        Self::get_f64(&self.l_min_real_ref, "l_min_real", config.l_min.real()),
+                        Self::get_f64(&self.l_min_imag_ref, "l_min_imag", config.l_min.imag()),
+                    ),
+                    color_cfg_name: config.color_cfg_name.clone(),
+                    transfer: config.transfer,
+                });
+                false
+            }
+            Msg::ResetArea => {
+                info!("EditLambdaCfg: got msg ResetArea");
+                self.set_area(LAMBDA_DEFAULT_L_MIN, LAMBDA_DEFAULT_L_MAX);
+                false
+            }
+            Msg::ZoomOut => {
+                info!("EditLambdaCfg: got msg ZoomOut");
+                let width = config.l_max.real() - config.l_min.real();
+                let height = config.l_max.imag() - config.l_min.imag();
+                self.set_area(
+                    (
+                        config.l_min.real() - width / 2.0,
+                        config.l_min.imag() - height / 2.0,
+                    ),
+                    (
+                        config.l_max.real() + width / 2.0,
+                        config.l_max.imag() + height / 2.0,
+                    ),
+                );
+                false
+            }
+            Msg::ResetParams => {
+                info!("EditLambdaCfg: got msg ResetParams");
+                self.set_values(&[(
+                    &self.iter_ref,
+                    "max_iterations",
+                    f64::from(LAMBDA_DEFAULT_ITERATIONS),
+                )]);
+                false
+            }
+            Msg::CanvasMsg(canvas_msg) => {
+                info!("EditLambdaCfg: got msg CanvasMsg");
+                match canvas_msg {
+                    ControlMsgRequest::CanvasSelectMsg(coords) if ctx.props().edit_mode => {
+                        let (min, max) = select_area(
+                            &config.l_min,
+                            &config.l_max,
+                            ctx.props().canvas_width,
+                            ctx.props().canvas_height,
+                            ctx.props().render_mode,
+                            coords,
+                        );
+                        self.set_area((min.real(), min.imag()), (max.real(), max.imag()));
+                        false
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let reset_area = ctx.link().callback(|_| Msg::ResetArea);
+        let reset_params = ctx.link().callback(|_| Msg::ResetParams);
+        let zoom_out = ctx.link().callback(|_| Msg::ZoomOut);
+        let save_config = ctx.link().callback(|_| Msg::SaveConfig);
+        let cancel = ctx.link().callback(|_| Msg::Cancel);
+        let cntr_class = if ctx.props().edit_mode {
+            "edit_cntr_visible"
+        } else {
+            "edit_cntr_hidden"
+        };
+
+        let config = &ctx.props().config;
+
+        html![
+            <div class={cntr_class} id="lambda_edit_cntr" ref={self.container_ref.clone()}>
+                <div class="input_cntr">
+                    <p class="hint_text">
+                        {"Hint: You can select a rectangle in the draw area to import the coordinates into the editor. \
+                          Lambdas inside the set make connected Lambda Julia sets."}
+                    </p>
+                </div>
+                {
+                    if USE_KATEX {
+                        html![
+                            <div class="input_cntr">
+                                <p class="formula_label" >{"Iterating over:"}</p>
+                                <div class="formula_cntr" ref={self.formula_ref.clone()}></div>
+                            </div>
+                        ]
+                    } else {
+                        html![]
+                    }
+                }
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="lambda_iterations">
+                            {"Iterations"}
+                        </label>
+                        <input class="input" id="lambda_iterations" name="lambda_iterations"
+                            type="number" min="10" max="10000000" ref={self.iter_ref.clone()}
+                            value={config.max_iterations.to_string()}/>
+                    </div>
+                    <button class="editor_button" id="lambda_reset_params" onclick={reset_params}>
+                        {"Reset to Default"}
+                    </button>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <div class="area_cntr">
+                            <div class="input_inner">
+                                <label class="input_label" for="lambda_max_real">
+                                    {"Lambda Max. Real"}
+                                </label>
+                                <input class="input" id="lambda_max_real" name="lambda_max_real"
+                                    type="number" step="0.0000001" ref={self.l_max_real_ref.clone()}
+                                    value={config.l_max.real().to_string()}/>
+                            </div>
+                            <div class="input_inner">
+                                <label class="input_label" for="lambda_min_real">
+                                    {"Lambda Min. Real"}
+                                </label>
+                                <input class="input" id="lambda_min_real" name="lambda_min_real"
+                                    type="number" step="0.0000001" ref={self.l_min_real_ref.clone()}
+                                    value={config.l_min.real().to_string()}/>
+                            </div>
+                        </div>
+                        <div class="area_cntr">
+                            <div class="input_inner">
+                                <label class="input_label" for="lambda_max_imag">
+                                    {"Lambda Max. Imag"}
+                                </label>
+                                <input class="input" id="lambda_max_imag" name="lambda_max_imag"
+                                    type="number" step="0.0000001" ref={self.l_max_imag_ref.clone()}
+                                    value={config.l_max.imag().to_string()}/>
+                            </div>
+                            <div class="input_inner">
+                                <label class="input_label" for="lambda_min_imag">
+                                    {"Lambda Min. Imag"}
+                                </label>
+                                <input class="input" id="lambda_min_imag" name="lambda_min_imag"
+                                    type="number" step="0.0000001" ref={self.l_min_imag_ref.clone()}
+                                    value={config.l_min.imag().to_string()}/>
+                            </div>
+                        </div>
+                        <div class="area_cntr">
+                            <button class="editor_button" id="lambda_reset_area"
+                                    onclick={reset_area}>
+                                {"Reset to Default"}
+                            </button>
+                            <button class="editor_button" id="lambda_zoom_out"
+                                    onclick={zoom_out}>
+                                {"Zoom Out"}
+                            </button>
+                        </div>
+                    </div>
+                </div>
+                <div class="edit_button_cntr">
+                    <button class="editor_button" id="lambda_save" onclick={save_config}>
+                        {"Save"}
+                    </button>
+                    <button class="editor_button" id="lambda_cancel" onclick={cancel}>
+                        {"Cancel"}
+                    </button>
+                </div>
+            </div>
+        ]
+    }
+
+    #[cfg(feature = "use_katex")]
+    fn rendered(&mut self, _ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            let formula = render("\\Large x_{n+1} = \\lambda x_n (1 - x_n), x_0 = \\frac{1}{2}")
+                .expect("Katex failed to render formula");
+            self.formula_ref
+                .cast::<HtmlDivElement>()
+                .expect("Formula Div not found")
+                .set_inner_html(formula.as_str());
+        }
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct EditLambdaCfgProps {
+    pub edit_mode: bool,
+    pub config: LambdaCfg,
+    pub canvas_width: u32,
+    pub canvas_height: u32,
+    pub render_mode: RenderMode,
+    pub cb_saved: Callback<LambdaCfg>,
+    pub cb_canceled: Callback<()>,
+}
@@ -3,10 +3,14 @@ use crate::agents::canvas_msg_bus::{ControlMsgBus, ControlMsgRequest};
 use crate::work::{
     complex::Complex,
     fractal::{
-        MandelbrotCfg, MANDELBROT_DEFAULT_C_MAX, MANDELBROT_DEFAULT_C_MIN,
+        critical_point, select_area, AutoIterations, Bailout, BailoutShape, MandelbrotCfg,
+        RenderMode, MANDELBROT_DEFAULT_C_MAX, MANDELBROT_DEFAULT_C_MIN,
         MANDELBROT_DEFAULT_ITERATIONS,
     },
-    util::{get_f64_from_ref, get_u32_from_ref, set_value_on_input_ref},
+    util::{
+        get_checked_from_ref, get_f64_from_ref, get_select_value_from_ref, get_u32_from_ref,
+        set_checked_on_input_ref, set_value_on_input_ref, set_value_on_select_ref,
+    },
 };
 use web_sys::{Element, HtmlDivElement};
 use yew::prelude::*;
@@ -40,22 +44,208 @@ pub enum Msg {
     SaveConfig,
     Cancel,
     PowerChanged,
+    StartAtCriticalPoint,
     CanvasMsg(ControlMsgRequest),
 }
 
 pub struct EditMandelbrotCfg {
     container_ref: NodeRef,
     iter_ref: NodeRef,
+    auto_iter_ref: NodeRef,
+    auto_factor_ref: NodeRef,
+    auto_min_ref: NodeRef,
+    auto_max_ref: NodeRef,
+    auto_sample_ref: NodeRef,
     c_min_real_ref: NodeRef,
     c_min_imag_ref: NodeRef,
     c_max_real_ref: NodeRef,
     c_max_imag_ref: NodeRef,
     formula_ref: NodeRef,
     power_ref: NodeRef,
+    z0_real_ref: NodeRef,
+    z0_imag_ref: NodeRef,
+    bailout_shape_ref: NodeRef,
+    bailout_radius_ref: NodeRef,
     _producer: Box<dyn Bridge<ControlMsgBus>>,
 }
 // config: Option<JuliaSetCfg>
 
+impl EditMandelbrotCfg {
+    fn get_auto_iterations(&self, ctx: &Context<Self>) -> AutoIterations {
+        let current = &ctx.props().config.auto_iterations;
+        let auto_iterations = AutoIterations {
+            enabled: get_checked_from_ref(&self.auto_iter_ref, "auto_iterations").map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    current.enabled
+                },
+                |v| v,
+            ),
+            factor: get_f64_from_ref(&self.auto_factor_ref, "auto_iter_factor").map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    current.factor
+                },
+                |v| v,
+            ),
+            min: get_u32_from_ref(&self.auto_min_ref, "auto_iter_min").map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    current.min
+                },
+                |v| v,
+            ),
+            max: get_u32_from_ref(&self.auto_max_ref, "auto_iter_max").map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    current.max
+                },
+                |v| v,
+            ),
+            sample: get_checked_from_ref(&self.auto_sample_ref, "auto_iter_sample").map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    current.sample
+                },
+                |v| v,
+            ),
+        };
+        // an inverted range keeps the previous one
+        if let Err(err) = auto_iterations.validate() {
+            error!("{}", err.as_str());
+            AutoIterations {
+                min: current.min,
+                max: current.max,
+                ..auto_iterations
+            }
+        } else {
+            auto_iterations
+        }
+    }
+
+    fn reset_auto_iterations(&self) {
+        let defaults = AutoIterations::default();
+        for (node_ref, name, value) in [
+            (
+                &self.auto_factor_ref,
+                "auto_iter_factor",
+                defaults.factor.to_string(),
+            ),
+            (
+                &self.auto_min_ref,
+                "auto_iter_min",
+                defaults.min.to_string(),
+            ),
+            (
+                &self.auto_max_ref,
+                "auto_iter_max",
+                defaults.max.to_string(),
+            ),
+        ] {
+            set_value_on_input_ref(node_ref, name, value.as_str()).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+        for (node_ref, name, checked) in [
+            (&self.auto_iter_ref, "auto_iterations", defaults.enabled),
+            (&self.auto_sample_ref, "auto_iter_sample", defaults.sample),
+        ] {
+            set_checked_on_input_ref(node_ref, name, checked).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+    }
+
+    fn set_z0(&self, z0: &Complex) {
+        for (node_ref, name, value) in [
+            (&self.z0_real_ref, "z0_real", z0.real()),
+            (&self.z0_imag_ref, "z0_imag", z0.imag()),
+        ] {
+            set_value_on_input_ref(node_ref, name, value.to_string().as_str()).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+    }
+
+    fn bailout_value(shape: BailoutShape) -> String {
+        format!("bailout_{:?}", shape).to_lowercase()
+    }
+
+    fn get_bailout(&self, ctx: &Context<Self>) -> Bailout {
+        let current = &ctx.props().config.bailout;
+        let shape = get_select_value_from_ref(&self.bailout_shape_ref, "bailout_shape")
+            .map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                    String::new()
+                },
+                |v| v,
+            );
+        Bailout {
+            shape: BailoutShape::ALL
+                .iter()
+                .copied()
+                .find(|candidate| Self::bailout_value(*candidate) == shape)
+                .unwrap_or_else(|| {
+                    error!("invalid bailout shape '{}'", shape);
+                    current.shape
+                }),
+            radius: get_f64_from_ref(&self.bailout_radius_ref, "bailout_radius")
+                .and_then(|radius| {
+                    if radius > 0.0 {
+                        Ok(radius)
+                    } else {
+                        Err(format!(
+                            "invalid bailout radius {}, it has to be positive",
+                            radius
+                        ))
+                    }
+                })
+                .map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        current.radius
+                    },
+                    |v| v,
+                ),
+        }
+    }
+
+    fn set_bailout(&self, bailout: &Bailout) {
+        set_value_on_select_ref(
+            &self.bailout_shape_ref,
+            "bailout_shape",
+            Self::bailout_value(bailout.shape).as_str(),
+        )
+        .map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+            },
+            |v| v,
+        );
+        set_value_on_input_ref(
+            &self.bailout_radius_ref,
+            "bailout_radius",
+            bailout.radius.to_string().as_str(),
+        )
+        .map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+            },
+            |v| v,
+        );
+    }
+}
+
 impl Component for EditMandelbrotCfg {
     type Message = Msg;
     type Properties = EditMandelbrotCfgProps;
@@ -64,12 +254,21 @@ impl Component for EditMandelbrotCfg {
         EditMandelbrotCfg {
             container_ref: NodeRef::default(),
             iter_ref: NodeRef::default(),
+            auto_iter_ref: NodeRef::default(),
+            auto_factor_ref: NodeRef::default(),
+            auto_min_ref: NodeRef::default(),
+            auto_max_ref: NodeRef::default(),
+            auto_sample_ref: NodeRef::default(),
             c_max_real_ref: NodeRef::default(),
             c_max_imag_ref: NodeRef::default(),
             c_min_real_ref: NodeRef::default(),
             c_min_imag_ref: NodeRef::default(),
             formula_ref: NodeRef::default(),
             power_ref: NodeRef::default(),
+            z0_real_ref: NodeRef::default(),
+            z0_imag_ref: NodeRef::default(),
+            bailout_shape_ref: NodeRef::default(),
+            bailout_radius_ref: NodeRef::default(),
             _producer: ControlMsgBus::bridge(ctx.link().callback(Msg::CanvasMsg)),
         }
     }
@@ -143,12 +342,35 @@ impl Component for EditMandelbrotCfg {
                     |v| v,
                 );
 
+                let z0_real = get_f64_from_ref(&self.z0_real_ref, "z0_real").map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        ctx.props().config.z0.real()
+                    },
+                    |v| v,
+                );
+
+                let z0_imag = get_f64_from_ref(&self.z0_imag_ref, "z0_imag").map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        ctx.props().config.z0.imag()
+                    },
+                    |v| v,
+                );
+
+                let auto_iterations = self.get_auto_iterations(ctx);
+                let bailout = self.get_bailout(ctx);
+
                 ctx.props().cb_saved.emit(MandelbrotCfg {
                     max_iterations,
                     c_max: Complex::new(c_max_real, c_max_imag),
                     c_min: Complex::new(c_min_real, c_min_imag),
                     power,
-                    color_cfg_name: None,
+                    color_cfg_name: ctx.props().config.color_cfg_name.clone(),
+                    auto_iterations,
+                    z0: Complex::new(z0_real, z0_imag),
+                    bailout,
+                    transfer: ctx.props().config.transfer,
                 });
                 false
             }
@@ -281,6 +503,14 @@ impl Component for EditMandelbrotCfg {
                     },
                     |v| v,
                 );
+                self.reset_auto_iterations();
+                self.set_z0(&critical_point());
+                self.set_bailout(&Bailout::default());
+                false
+            }
+            Msg::StartAtCriticalPoint => {
+                info!("EditMandelbrotCfg: got msg StartAtCriticalPoint");
+                self.set_z0(&critical_point());
                 false
             }
             Msg::CanvasMsg(canvas_msg) => {
@@ -288,24 +518,17 @@ impl Component for EditMandelbrotCfg {
                     ControlMsgRequest::CanvasSelectMsg(coords) => {
                         info!("EditMandelbrotCfg: got msg CanvasSelect");
                         if ctx.props().edit_mode {
-                            let x_scale = (ctx.props().config.c_max.real()
-                                - ctx.props().config.c_min.real())
-                                / f64::from(ctx.props().canvas_width);
-                            let y_scale = (ctx.props().config.c_max.imag()
-                                - ctx.props().config.c_min.imag())
-                                / f64::from(ctx.props().canvas_height);
                             // info!("EditMandelbrotCfg: CanvasSelectMsg size: {}/{} ",ctx.props().canvas_width,
                             //    ctx.props().canvas_height);
                             // info!("EditMandelbrotCfg: CanvasSelectMsg coords: {:?} ", coords);
-                            // info!("EditMandelbrotCfg: CanvasSelectMsg scales: {}/{} ", x_scale, y_scale);
 
-                            let c_min = Complex::new(
-                                ctx.props().config.c_min.real() + x_scale * f64::from(coords.0),
-                                ctx.props().config.c_min.imag() + y_scale * f64::from(coords.1),
-                            );
-                            let c_max = Complex::new(
-                                ctx.props().config.c_min.real() + x_scale * f64::from(coords.2),
-                                ctx.props().config.c_min.imag() + y_scale * f64::from(coords.3),
+                            let (c_min, c_max) = select_area(
+                                &ctx.props().config.c_min,
+                                &ctx.props().config.c_max,
+                                ctx.props().canvas_width,
+                                ctx.props().canvas_height,
+                                ctx.props().render_mode,
+                                coords,
                             );
 
                             // info!("EditMandelbrotCfg: CanvasSelectMsg new values: c_min: {}, c_max: {} ", c_min, c_max);
@@ -397,6 +620,9 @@ impl Component for EditMandelbrotCfg {
         };
 
         let on_pow_changed = ctx.link().callback(|_| Msg::PowerChanged);
+        let start_at_critical_point = ctx.link().callback(|_| Msg::StartAtCriticalPoint);
+
+        let sel_bailout = Self::bailout_value(ctx.props().config.bailout.shape);
 
         info!("EditMandelbrotCfg::view: USE_KATEX: {}", USE_KATEX);
 
@@ -425,7 +651,7 @@ impl Component for EditMandelbrotCfg {
                             {"Iterations"}
                         </label>
                         <input class="input" id="mandelbrot_iterations" name="mandelbrot_iterations"
-                            type="number" min="100" max="1000" ref={self.iter_ref.clone()}
+                            type="number" min="10" max="10000000" ref={self.iter_ref.clone()}
                             value={ctx.props().config.max_iterations.to_string()}/>
                     </div>
                     <button class="editor_button" id="mandelbrot_reset_params" onclick={reset_params}>
@@ -442,6 +668,107 @@ impl Component for EditMandelbrotCfg {
                         />
                     </div>
                 </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbrot_z0_real">
+                            {"Start z0 Real"}
+                        </label>
+                        <input class="input" id="mandelbrot_z0_real" name="mandelbrot_z0_real"
+                            type="number" step="0.0000001" ref={self.z0_real_ref.clone()}
+                            value={ctx.props().config.z0.real().to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbrot_z0_imag">
+                            {"Start z0 Imag"}
+                        </label>
+                        <input class="input" id="mandelbrot_z0_imag" name="mandelbrot_z0_imag"
+                            type="number" step="0.0000001" ref={self.z0_imag_ref.clone()}
+                            value={ctx.props().config.z0.imag().to_string()}/>
+                    </div>
+                    <button class="editor_button" id="mandelbrot_critical_point"
+                            onclick={start_at_critical_point}>
+                        {"Critical Point"}
+                    </button>
+                    <p class="hint_text">
+                        {"The critical point 0 is the only critical point of x^p + c for every power p. \
+                          Other starting points deform the set into a perturbed Mandelbrot set."}
+                    </p>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbrot_bailout_shape">
+                            {"Bailout"}
+                        </label>
+                        <select class="input" id="mandelbrot_bailout_shape" name="mandelbrot_bailout_shape"
+                            ref={self.bailout_shape_ref.clone()}>
+                            {
+                                for BailoutShape::ALL.iter().map(|shape| {
+                                    let value = Self::bailout_value(*shape);
+                                    html![
+                                        <option value={value.clone()} selected={value == sel_bailout}>
+                                            {shape.name()}
+                                        </option>
+                                    ]
+                                })
+                            }
+                        </select>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbrot_bailout_radius">
+                            {"Bailout Radius"}
+                        </label>
+                        <input class="input" id="mandelbrot_bailout_radius" name="mandelbrot_bailout_radius"
+                            type="number" min="0.1" step="0.1" ref={self.bailout_radius_ref.clone()}
+                            value={ctx.props().config.bailout.radius.to_string()}/>
+                    </div>
+                    <p class="hint_text">
+                        {"The bailout decides when an orbit has escaped, its shape and radius change the \
+                          bands and decorations outside of the set. Radii below 2 count points of the set as \
+                          escaped. Binary decomposition and field lines raise the radius to 100."}
+                    </p>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbrot_auto_iter">
+                            {"Auto Iterations"}
+                        </label>
+                        <input class="input" id="mandelbrot_auto_iter" name="mandelbrot_auto_iter"
+                            type="checkbox" ref={self.auto_iter_ref.clone()}
+                            checked={ctx.props().config.auto_iterations.enabled}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbrot_auto_factor">
+                            {"Factor"}
+                        </label>
+                        <input class="input" id="mandelbrot_auto_factor" name="mandelbrot_auto_factor"
+                            type="number" min="0.1" step="0.1" ref={self.auto_factor_ref.clone()}
+                            value={ctx.props().config.auto_iterations.factor.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbrot_auto_min">
+                            {"Min."}
+                        </label>
+                        <input class="input" id="mandelbrot_auto_min" name="mandelbrot_auto_min"
+                            type="number" min="10" ref={self.auto_min_ref.clone()}
+                            value={ctx.props().config.auto_iterations.min.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbrot_auto_max">
+                            {"Max."}
+                        </label>
+                        <input class="input" id="mandelbrot_auto_max" name="mandelbrot_auto_max"
+                            type="number" min="10" ref={self.auto_max_ref.clone()}
+                            value={ctx.props().config.auto_iterations.max.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbrot_auto_sample">
+                            {"Sample View"}
+                        </label>
+                        <input class="input" id="mandelbrot_auto_sample" name="mandelbrot_auto_sample"
+                            type="checkbox" ref={self.auto_sample_ref.clone()}
+                            checked={ctx.props().config.auto_iterations.sample}/>
+                    </div>
+                </div>
                 <div class="input_cntr">
                     <div class="input_cntr">
                         <div class="area_cntr">
@@ -522,6 +849,7 @@ pub struct EditMandelbrotCfgProps {
     pub config: MandelbrotCfg,
     pub canvas_width: u32,
     pub canvas_height: u32,
+    pub render_mode: RenderMode,
     pub cb_saved: Callback<MandelbrotCfg>,
     pub cb_canceled: Callback<()>,
 }
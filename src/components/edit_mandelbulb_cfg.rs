@@ -0,0 +1,251 @@
+use web_sys::Element;
+use yew::prelude::*;
+
+use crate::work::{
+    fractal::{Camera, MandelbulbCfg, MANDELBULB_DEFAULT_ITERATIONS, MANDELBULB_DEFAULT_POWER},
+    util::{get_f64_from_ref, get_u32_from_ref, set_value_on_input_ref},
+    vec3::Vec3,
+};
+
+pub enum Msg {
+    ResetParams,
+    ResetCamera,
+    SaveConfig,
+    Cancel,
+}
+
+pub struct EditMandelbulbCfg {
+    container_ref: NodeRef,
+    power_ref: NodeRef,
+    iter_ref: NodeRef,
+    pos_x_ref: NodeRef,
+    pos_y_ref: NodeRef,
+    pos_z_ref: NodeRef,
+    yaw_ref: NodeRef,
+    pitch_ref: NodeRef,
+    fov_ref: NodeRef,
+}
+
+impl EditMandelbulbCfg {
+    fn get_f64(node_ref: &NodeRef, name: &str, default: f64) -> f64 {
+        get_f64_from_ref(node_ref, name).map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+                default
+            },
+            |v| v,
+        )
+    }
+
+    fn set_values(&self, values: &[(&NodeRef, &str, f64)]) {
+        for (node_ref, name, value) in values {
+            set_value_on_input_ref(node_ref, name, value.to_string().as_str()).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+    }
+
+    fn set_camera(&self, camera: &Camera) {
+        self.set_values(&[
+            (&self.pos_x_ref, "pos_x", camera.position.x()),
+            (&self.pos_y_ref, "pos_y", camera.position.y()),
+            (&self.pos_z_ref, "pos_z", camera.position.z()),
+            (&self.yaw_ref, "yaw", camera.yaw),
+            (&self.pitch_ref, "pitch", camera.pitch),
+            (&self.fov_ref, "fov", camera.fov),
+        ]);
+    }
+}
+
+impl Component for EditMandelbulbCfg {
+    type Message = Msg;
+    type Properties = EditMandelbulbCfgProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        EditMandelbulbCfg {
+            container_ref: NodeRef::default(),
+            power_ref: NodeRef::default(),
+            iter_ref: NodeRef::default(),
+            pos_x_ref: NodeRef::default(),
+            pos_y_ref: NodeRef::default(),
+            pos_z_ref: NodeRef::default(),
+            yaw_ref: NodeRef::default(),
+            pitch_ref: NodeRef::default(),
+            fov_ref: NodeRef::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let config = &ctx.props().config;
+        match msg {
+            Msg::Cancel => {
+                info!("EditMandelbulbCfg: got msg Cancel");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+                ctx.props().cb_canceled.emit(());
+                false
+            }
+            Msg::SaveConfig => {
+                info!("EditMandelbulbCfg: got msg SaveConfig");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+
+                let max_iterations = get_u32_from_ref(&self.iter_ref, "iterations").map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        config.max_iterations
+                    },
+                    |v| v,
+                );
+
+                let camera = &config.camera;
+                ctx.props().cb_saved.emit(MandelbulbCfg {
+                    power: Self::get_f64(&self.power_ref, "power", config.power),
+                    max_iterations,
+                    camera: Camera {
+                        position: Vec3::new(
+                            Self::get_f64(&self.pos_x_ref, "pos_x", camera.position.x()),
+                            Self::get_f64(&self.pos_y_ref, "pos_y", camera.position.y()),
+                            Self::get_f64(&self.pos_z_ref, "pos_z", camera.position.z()),
+                        ),
+                        yaw: Self::get_f64(&self.yaw_ref, "yaw", camera.yaw),
+                        pitch: Self::get_f64(&self.pitch_ref, "pitch", camera.pitch),
+                        fov: Self::get_f64(&self.fov_ref, "fov", camera.fov),
+                    },
+                    color_cfg_name: config.color_cfg_name.clone(),
+                });
+                false
+            }
+            Msg::ResetParams => {
+                info!("EditMandelbulbCfg: got msg ResetParams");
+                self.set_values(&[
+                    (&self.power_ref, "power", MANDELBULB_DEFAULT_POWER),
+                    (
+                        &self.iter_ref,
+                        "iterations",
+                        f64::from(MANDELBULB_DEFAULT_ITERATIONS),
+                    ),
+                ]);
+                false
+            }
+            Msg::ResetCamera => {
+                info!("EditMandelbulbCfg: got msg ResetCamera");
+                self.set_camera(&MandelbulbCfg::default_camera());
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let reset_params = ctx.link().callback(|_| Msg::ResetParams);
+        let reset_camera = ctx.link().callback(|_| Msg::ResetCamera);
+        let save_config = ctx.link().callback(|_| Msg::SaveConfig);
+        let cancel = ctx.link().callback(|_| Msg::Cancel);
+        let cntr_class = if ctx.props().edit_mode {
+            "edit_cntr_visible"
+        } else {
+            "edit_cntr_hidden"
+        };
+
+        let config = &ctx.props().config;
+        let camera = &config.camera;
+
+        html![
+            <div class={cntr_class} id="mandelbulb_edit_cntr" ref={self.container_ref.clone()}>
+                <div class="input_cntr">
+                    <p class="hint_text">
+                        {"Hint: Drag the mouse over the image to orbit the camera around the origin. \
+                          Yaw turns the camera around the y axis, pitch tilts it up and down."}
+                    </p>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbulb_power">
+                            {"Power"}
+                        </label>
+                        <input class="input" id="mandelbulb_power" name="mandelbulb_power"
+                            type="number" min="2" max="32" step="0.1" ref={self.power_ref.clone()}
+                            value={config.power.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbulb_iterations">
+                            {"Iterations"}
+                        </label>
+                        <input class="input" id="mandelbulb_iterations" name="mandelbulb_iterations"
+                            type="number" min="1" max="100" ref={self.iter_ref.clone()}
+                            value={config.max_iterations.to_string()}/>
+                    </div>
+                    <button class="editor_button" id="mandelbulb_reset_params" onclick={reset_params}>
+                        {"Reset to Default"}
+                    </button>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbulb_pos_x">{"Camera X"}</label>
+                        <input class="input" id="mandelbulb_pos_x" name="mandelbulb_pos_x"
+                            type="number" step="0.01" ref={self.pos_x_ref.clone()}
+                            value={camera.position.x().to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbulb_pos_y">{"Camera Y"}</label>
+                        <input class="input" id="mandelbulb_pos_y" name="mandelbulb_pos_y"
+                            type="number" step="0.01" ref={self.pos_y_ref.clone()}
+                            value={camera.position.y().to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbulb_pos_z">{"Camera Z"}</label>
+                        <input class="input" id="mandelbulb_pos_z" name="mandelbulb_pos_z"
+                            type="number" step="0.01" ref={self.pos_z_ref.clone()}
+                            value={camera.position.z().to_string()}/>
+                    </div>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbulb_yaw">{"Yaw"}</label>
+                        <input class="input" id="mandelbulb_yaw" name="mandelbulb_yaw"
+                            type="number" step="1" ref={self.yaw_ref.clone()}
+                            value={camera.yaw.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbulb_pitch">{"Pitch"}</label>
+                        <input class="input" id="mandelbulb_pitch" name="mandelbulb_pitch"
+                            type="number" min="-89" max="89" step="1" ref={self.pitch_ref.clone()}
+                            value={camera.pitch.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="mandelbulb_fov">{"Field of View"}</label>
+                        <input class="input" id="mandelbulb_fov" name="mandelbulb_fov"
+                            type="number" min="1" max="179" step="1" ref={self.fov_ref.clone()}
+                            value={camera.fov.to_string()}/>
+                    </div>
+                    <button class="editor_button" id="mandelbulb_reset_camera" onclick={reset_camera}>
+                        {"Reset Camera"}
+                    </button>
+                </div>
+                <div class="edit_button_cntr">
+                    <button class="editor_button" id="mandelbulb_save" onclick={save_config}>
+                        {"Save"}
+                    </button>
+                    <button class="editor_button" id="mandelbulb_cancel" onclick={cancel}>
+                        {"Cancel"}
+                    </button>
+                </div>
+            </div>
+        ]
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct EditMandelbulbCfgProps {
+    pub edit_mode: bool,
+    pub config: MandelbulbCfg,
+    pub cb_saved: Callback<MandelbulbCfg>,
+    pub cb_canceled: Callback<()>,
+}
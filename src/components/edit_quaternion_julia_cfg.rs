@@ -0,0 +1,297 @@
+use web_sys::Element;
+use yew::prelude::*;
+
+use crate::work::{
+    fractal::{Camera, QuaternionJuliaCfg, QUATERNION_JULIA_DEFAULT_ITERATIONS},
+    quaternion::Quaternion,
+    util::{get_f64_from_ref, get_u32_from_ref, set_value_on_input_ref},
+    vec3::Vec3,
+};
+
+pub enum Msg {
+    ResetParams,
+    ResetCamera,
+    SaveConfig,
+    Cancel,
+}
+
+pub struct EditQuaternionJuliaCfg {
+    container_ref: NodeRef,
+    c_r_ref: NodeRef,
+    c_i_ref: NodeRef,
+    c_j_ref: NodeRef,
+    c_k_ref: NodeRef,
+    slice_ref: NodeRef,
+    iter_ref: NodeRef,
+    pos_x_ref: NodeRef,
+    pos_y_ref: NodeRef,
+    pos_z_ref: NodeRef,
+    yaw_ref: NodeRef,
+    pitch_ref: NodeRef,
+    fov_ref: NodeRef,
+}
+
+impl EditQuaternionJuliaCfg {
+    fn get_f64(node_ref: &NodeRef, name: &str, default: f64) -> f64 {
+        get_f64_from_ref(node_ref, name).map_or_else(
+            |err| {
+                error!("{}", err.as_str());
+                default
+            },
+            |v| v,
+        )
+    }
+
+    fn set_values(&self, values: &[(&NodeRef, &str, f64)]) {
+        for (node_ref, name, value) in values {
+            set_value_on_input_ref(node_ref, name, value.to_string().as_str()).map_or_else(
+                |err| {
+                    error!("{}", err.as_str());
+                },
+                |v| v,
+            );
+        }
+    }
+
+    fn set_camera(&self, camera: &Camera) {
+        self.set_values(&[
+            (&self.pos_x_ref, "pos_x", camera.position.x()),
+            (&self.pos_y_ref, "pos_y", camera.position.y()),
+            (&self.pos_z_ref, "pos_z", camera.position.z()),
+            (&self.yaw_ref, "yaw", camera.yaw),
+            (&self.pitch_ref, "pitch", camera.pitch),
+            (&self.fov_ref, "fov", camera.fov),
+        ]);
+    }
+}
+
+impl Component for EditQuaternionJuliaCfg {
+    type Message = Msg;
+    type Properties = EditQuaternionJuliaCfgProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        EditQuaternionJuliaCfg {
+            container_ref: NodeRef::default(),
+            c_r_ref: NodeRef::default(),
+            c_i_ref: NodeRef::default(),
+            c_j_ref: NodeRef::default(),
+            c_k_ref: NodeRef::default(),
+            slice_ref: NodeRef::default(),
+            iter_ref: NodeRef::default(),
+            pos_x_ref: NodeRef::default(),
+            pos_y_ref: NodeRef::default(),
+            pos_z_ref: NodeRef::default(),
+            yaw_ref: NodeRef::default(),
+            pitch_ref: NodeRef::default(),
+            fov_ref: NodeRef::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let config = &ctx.props().config;
+        match msg {
+            Msg::Cancel => {
+                info!("EditQuaternionJuliaCfg: got msg Cancel");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+                ctx.props().cb_canceled.emit(());
+                false
+            }
+            Msg::SaveConfig => {
+                info!("EditQuaternionJuliaCfg: got msg SaveConfig");
+                self.container_ref
+                    .cast::<Element>()
+                    .expect("Container not found")
+                    .set_class_name("edit_cntr_hidden");
+
+                let max_iterations = get_u32_from_ref(&self.iter_ref, "iterations").map_or_else(
+                    |err| {
+                        error!("{}", err.as_str());
+                        config.max_iterations
+                    },
+                    |v| v,
+                );
+
+                let camera = &config.camera;
+                ctx.props().cb_saved.emit(QuaternionJuliaCfg {
+                    c: Quaternion::new(
+                        Self::get_f64(&self.c_r_ref, "c_r", config.c.r()),
+                        Self::get_f64(&self.c_i_ref, "c_i", config.c.i()),
+                        Self::get_f64(&self.c_j_ref, "c_j", config.c.j()),
+                        Self::get_f64(&self.c_k_ref, "c_k", config.c.k()),
+                    ),
+                    slice: Self::get_f64(&self.slice_ref, "slice", config.slice),
+                    max_iterations,
+                    camera: Camera {
+                        position: Vec3::new(
+                            Self::get_f64(&self.pos_x_ref, "pos_x", camera.position.x()),
+                            Self::get_f64(&self.pos_y_ref, "pos_y", camera.position.y()),
+                            Self::get_f64(&self.pos_z_ref, "pos_z", camera.position.z()),
+                        ),
+                        yaw: Self::get_f64(&self.yaw_ref, "yaw", camera.yaw),
+                        pitch: Self::get_f64(&self.pitch_ref, "pitch", camera.pitch),
+                        fov: Self::get_f64(&self.fov_ref, "fov", camera.fov),
+                    },
+                    color_cfg_name: config.color_cfg_name.clone(),
+                });
+                false
+            }
+            Msg::ResetParams => {
+                info!("EditQuaternionJuliaCfg: got msg ResetParams");
+                let c = QuaternionJuliaCfg::default_c();
+                self.set_values(&[
+                    (&self.c_r_ref, "c_r", c.r()),
+                    (&self.c_i_ref, "c_i", c.i()),
+                    (&self.c_j_ref, "c_j", c.j()),
+                    (&self.c_k_ref, "c_k", c.k()),
+                    (&self.slice_ref, "slice", 0.0),
+                    (
+                        &self.iter_ref,
+                        "iterations",
+                        f64::from(QUATERNION_JULIA_DEFAULT_ITERATIONS),
+                    ),
+                ]);
+                false
+            }
+            Msg::ResetCamera => {
+                info!("EditQuaternionJuliaCfg: got msg ResetCamera");
+                self.set_camera(&QuaternionJuliaCfg::default_camera());
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let reset_params = ctx.link().callback(|_| Msg::ResetParams);
+        let reset_camera = ctx.link().callback(|_| Msg::ResetCamera);
+        let save_config = ctx.link().callback(|_| Msg::SaveConfig);
+        let cancel = ctx.link().callback(|_| Msg::Cancel);
+        let cntr_class = if ctx.props().edit_mode {
+            "edit_cntr_visible"
+        } else {
+            "edit_cntr_hidden"
+        };
+
+        let config = &ctx.props().config;
+        let camera = &config.camera;
+
+        html![
+            <div class={cntr_class} id="quaternion_julia_edit_cntr" ref={self.container_ref.clone()}>
+                <div class="input_cntr">
+                    <p class="hint_text">
+                        {"Hint: Drag the mouse over the image to orbit the camera around the origin. \
+                          The image shows the 3D slice of the quaternion Julia set at the given k component."}
+                    </p>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_c_r">{"C Real"}</label>
+                        <input class="input" id="quaternion_julia_c_r" name="quaternion_julia_c_r"
+                            type="number" step="0.01" ref={self.c_r_ref.clone()}
+                            value={config.c.r().to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_c_i">{"C i"}</label>
+                        <input class="input" id="quaternion_julia_c_i" name="quaternion_julia_c_i"
+                            type="number" step="0.01" ref={self.c_i_ref.clone()}
+                            value={config.c.i().to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_c_j">{"C j"}</label>
+                        <input class="input" id="quaternion_julia_c_j" name="quaternion_julia_c_j"
+                            type="number" step="0.01" ref={self.c_j_ref.clone()}
+                            value={config.c.j().to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_c_k">{"C k"}</label>
+                        <input class="input" id="quaternion_julia_c_k" name="quaternion_julia_c_k"
+                            type="number" step="0.01" ref={self.c_k_ref.clone()}
+                            value={config.c.k().to_string()}/>
+                    </div>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_slice">
+                            {"Slice (k)"}
+                        </label>
+                        <input class="input" id="quaternion_julia_slice" name="quaternion_julia_slice"
+                            type="number" min="-2" max="2" step="0.01" ref={self.slice_ref.clone()}
+                            value={config.slice.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_iterations">
+                            {"Iterations"}
+                        </label>
+                        <input class="input" id="quaternion_julia_iterations" name="quaternion_julia_iterations"
+                            type="number" min="1" max="100" ref={self.iter_ref.clone()}
+                            value={config.max_iterations.to_string()}/>
+                    </div>
+                    <button class="editor_button" id="quaternion_julia_reset_params" onclick={reset_params}>
+                        {"Reset to Default"}
+                    </button>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_pos_x">{"Camera X"}</label>
+                        <input class="input" id="quaternion_julia_pos_x" name="quaternion_julia_pos_x"
+                            type="number" step="0.01" ref={self.pos_x_ref.clone()}
+                            value={camera.position.x().to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_pos_y">{"Camera Y"}</label>
+                        <input class="input" id="quaternion_julia_pos_y" name="quaternion_julia_pos_y"
+                            type="number" step="0.01" ref={self.pos_y_ref.clone()}
+                            value={camera.position.y().to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_pos_z">{"Camera Z"}</label>
+                        <input class="input" id="quaternion_julia_pos_z" name="quaternion_julia_pos_z"
+                            type="number" step="0.01" ref={self.pos_z_ref.clone()}
+                            value={camera.position.z().to_string()}/>
+                    </div>
+                </div>
+                <div class="input_cntr">
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_yaw">{"Yaw"}</label>
+                        <input class="input" id="quaternion_julia_yaw" name="quaternion_julia_yaw"
+                            type="number" step="1" ref={self.yaw_ref.clone()}
+                            value={camera.yaw.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_pitch">{"Pitch"}</label>
+                        <input class="input" id="quaternion_julia_pitch" name="quaternion_julia_pitch"
+                            type="number" min="-89" max="89" step="1" ref={self.pitch_ref.clone()}
+                            value={camera.pitch.to_string()}/>
+                    </div>
+                    <div class="input_inner">
+                        <label class="input_label" for="quaternion_julia_fov">{"Field of View"}</label>
+                        <input class="input" id="quaternion_julia_fov" name="quaternion_julia_fov"
+                            type="number" min="1" max="179" step="1" ref={self.fov_ref.clone()}
+                            value={camera.fov.to_string()}/>
+                    </div>
+                    <button class="editor_button" id="quaternion_julia_reset_camera" onclick={reset_camera}>
+                        {"Reset Camera"}
+                    </button>
+                </div>
+                <div class="edit_button_cntr">
+                    <button class="editor_button" id="quaternion_julia_save" onclick={save_config}>
+                        {"Save"}
+                    </button>
+                    <button class="editor_button" id="quaternion_julia_cancel" onclick={cancel}>
+                        {"Cancel"}
+                    </button>
+                </div>
+            </div>
+        ]
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct EditQuaternionJuliaCfgProps {
+    pub edit_mode: bool,
+    pub config: QuaternionJuliaCfg,
+    pub cb_saved: Callback<QuaternionJuliaCfg>,
+    pub cb_canceled: Callback<()>,
+}
@@ -5,20 +5,38 @@ use yew::prelude::*;
 use super::{
     canvas_element::CanvasElement,
     control_panel::ControlPanel,
-    control_panel::PanelConfig::{ConfigJuliaSet, ConfigMandelbrot},
+    control_panel::PanelConfig::{
+        ConfigAttractor, ConfigFlame, ConfigHybrid, ConfigIfs, ConfigJuliaSet, ConfigLambda,
+        ConfigLambdaJulia, ConfigMandelbrot, ConfigMandelbulb, ConfigQuaternionJulia,
+    },
+    edit_attractor_cfg::EditAttractorCfg,
     edit_color_cfg::EditColorConfig,
+    edit_flame_cfg::EditFlameCfg,
+    edit_hybrid_cfg::EditHybridCfg,
+    edit_ifs_cfg::EditIfsCfg,
     edit_julia_cfg::EditJuliaCfg,
+    edit_lambda_cfg::EditLambdaCfg,
+    edit_lambda_julia_cfg::EditLambdaJuliaCfg,
     edit_mandelbrot_cfg::EditMandelbrotCfg,
+    edit_mandelbulb_cfg::EditMandelbulbCfg,
+    edit_quaternion_julia_cfg::EditQuaternionJuliaCfg,
     modal::{ModalMsg, ModalOk, ModalPlain},
 };
 use crate::agents::clipboard_worker::WorkerStatus;
 use crate::components::edit_color_cfg::ColorCfg;
-use crate::work::fractal::{FractalType, JuliaSetCfg, MandelbrotCfg};
+use crate::work::colors::ColorCycle;
+use crate::work::fractal::{
+    AttractorCfg, ColoringMode, FlameCfg, FractalType, HybridCfg, IfsCfg, JuliaSetCfg, LambdaCfg,
+    LambdaJuliaCfg, MandelbrotCfg, MandelbulbCfg, QuaternionJuliaCfg, RenderMode,
+};
+use crate::work::transfer::Transfer;
 
 const STORAGE_KEY: &str = "yew_fractals_v2.5";
 const DEBUG_NO_STORAGE: bool = false;
 
 pub const DEFAULT_WIDTH: u32 = 1024;
+/// rotation of the camera of 3D fractals per pixel of mouse drag
+const ORBIT_DEGREES_PER_PIXEL: f64 = 0.25;
 
 // TODO: make canvas its own component and setup communication with editors
 
@@ -78,8 +96,83 @@ impl Component for Root {
                 self.config.store();
                 true
             }
+            Msg::AttractorCfgChanged(config) => {
+                self.edit_mode = false;
+                self.config.attractor_cfg = config;
+                self.canvas_height = self.config.get_canvas_height(DEFAULT_WIDTH);
+                self.config.store();
+                true
+            }
+            Msg::IfsCfgChanged(config) => {
+                self.edit_mode = false;
+                self.config.ifs_cfg = config;
+                self.canvas_height = self.config.get_canvas_height(DEFAULT_WIDTH);
+                self.config.store();
+                true
+            }
+            Msg::FlameCfgChanged(config) => {
+                self.edit_mode = false;
+                self.config.flame_cfg = config;
+                self.canvas_height = self.config.get_canvas_height(DEFAULT_WIDTH);
+                self.config.store();
+                true
+            }
+            Msg::MandelbulbCfgChanged(config) => {
+                self.edit_mode = false;
+                self.config.mandelbulb_cfg = config;
+                self.config.store();
+                true
+            }
+            Msg::QuaternionJuliaCfgChanged(config) => {
+                self.edit_mode = false;
+                self.config.quaternion_julia_cfg = config;
+                self.config.store();
+                true
+            }
+            Msg::LambdaCfgChanged(config) => {
+                self.edit_mode = false;
+                self.config.lambda_cfg = config;
+                self.canvas_height = self.config.get_canvas_height(DEFAULT_WIDTH);
+                self.config.store();
+                true
+            }
+            Msg::LambdaJuliaCfgChanged(config) => {
+                self.edit_mode = false;
+                self.config.lambda_julia_cfg = config;
+                self.canvas_height = self.config.get_canvas_height(DEFAULT_WIDTH);
+                self.config.store();
+                true
+            }
+            Msg::HybridCfgChanged(config) => {
+                self.edit_mode = false;
+                self.config.hybrid_cfg = config;
+                self.canvas_height = self.config.get_canvas_height(DEFAULT_WIDTH);
+                self.config.store();
+                true
+            }
+            Msg::ColorCfgChanged((color_cfg, palette, transfer)) => {
+                self.color_edit_mode = false;
+                self.config.color_cfg = color_cfg;
+                self.config.set_color_cfg_name(palette);
+                if let Some(transfer) = transfer {
+                    self.config.set_transfer(transfer);
+                }
+                self.config.store();
+                true
+            }
+            Msg::CameraOrbit((delta_x, delta_y)) => {
+                info!("Root::update: CameraOrbit: {}, {}", delta_x, delta_y);
+                #[allow(clippy::cast_precision_loss)]
+                self.config.orbit_camera(
+                    -(delta_x as f64) * ORBIT_DEGREES_PER_PIXEL,
+                    delta_y as f64 * ORBIT_DEGREES_PER_PIXEL,
+                );
+                self.config.store();
+                true
+            }
             Msg::EditCfgCanceled => {
                 self.edit_mode = false;
+                self.color_edit_mode = false;
                 true
             }
             Msg::TypeChanged(fractal_type) => {
@@ -88,6 +181,36 @@ impl Component for Root {
                 self.config.store();
                 true
             }
+            Msg::RenderModeChanged(render_mode) => {
+                info!("Root::update: RenderModeChanged: {:?}", render_mode);
+                self.config.render_mode = render_mode;
+                self.config.store();
+                true
+            }
+            Msg::ColoringChanged(coloring) => {
+                info!("Root::update: ColoringChanged: {:?}", coloring);
+                self.config.coloring = coloring;
+                self.config.store();
+                true
+            }
+            Msg::ColorCycleChanged(color_cycle) => {
+                info!("Root::update: ColorCycleChanged: {:?}", color_cycle);
+                self.config.color_cycle = color_cycle;
+                self.config.store();
+                true
+            }
+            Msg::StretchPaletteChanged(status) => {
+                info!("Root::update: StretchPaletteChanged: {}", status);
+                self.config.stretch_palette = status;
+                self.config.store();
+                true
+            }
+            Msg::UseWorkersChanged(status) => {
+                info!("Root::update: UseWorkersChanged: {}", status);
+                self.config.use_workers = status;
+                self.config.store();
+                true
+            }
             Msg::ViewStatsChanged(status) => {
                 info!("Root::update: ViewStatsChanged: {}", status);
                 self.config.view_stats = status;
@@ -98,6 +221,10 @@ impl Component for Root {
                 self.edit_mode = true;
                 true
             }
+            Msg::EditColors => {
+                self.color_edit_mode = true;
+                true
+            }
             Msg::CtcActive(status) => {
                 info!("Root::update: CtcActive");
                 self.show_ctc_preparing = status;
@@ -134,6 +261,29 @@ impl Component for Root {
         let ctrl_panel_cfg = match self.config.active_config {
             FractalType::JuliaSet => ConfigJuliaSet(self.config.julia_set_cfg.clone()),
             FractalType::Mandelbrot => ConfigMandelbrot(self.config.mandelbrot_cfg.clone()),
+            FractalType::Attractor => ConfigAttractor(self.config.attractor_cfg.clone()),
+            FractalType::Ifs => ConfigIfs(self.config.ifs_cfg.clone()),
+            FractalType::Flame => ConfigFlame(self.config.flame_cfg.clone()),
+            FractalType::Mandelbulb => ConfigMandelbulb(self.config.mandelbulb_cfg.clone()),
+            FractalType::QuaternionJulia => {
+                ConfigQuaternionJulia(self.config.quaternion_julia_cfg.clone())
+            }
+            FractalType::Lambda => ConfigLambda(self.config.lambda_cfg.clone()),
+            FractalType::LambdaJulia => ConfigLambdaJulia(self.config.lambda_julia_cfg.clone()),
+            FractalType::Hybrid => ConfigHybrid(self.config.hybrid_cfg.clone()),
+        };
+
+        let title = match self.config.active_config {
+            FractalType::Mandelbrot => "Mandelbrot Set",
+            FractalType::JuliaSet => "Julia Set",
+            FractalType::Attractor => "Strange Attractor",
+            FractalType::Ifs => "Iterated Function System",
+            FractalType::Flame => "Fractal Flame",
+            FractalType::Mandelbulb => "Mandelbulb",
+            FractalType::QuaternionJulia => "Quaternion Julia Set",
+            FractalType::Lambda => "Lambda Set",
+            FractalType::LambdaJulia => "Lambda Julia Set",
+            FractalType::Hybrid => "Hybrid Fractal",
         };
 
         let disclaimer_msg = vec!["\
@@ -145,17 +295,28 @@ is best viewed on a computer.".to_owned()
 
         html! {
             <div class="outer_cntr">
-                <h1>{if self.config.active_config == FractalType::Mandelbrot {"Mandelbrot Set"} else {"Julia Set"}}</h1>
+                <h1>{title}</h1>
                 <div class="inner_cntr">
                     <ControlPanel
                         config={ctrl_panel_cfg}
                         view_stats={self.config.view_stats}
+                        render_mode={self.config.render_mode}
+                        coloring={self.config.coloring}
+                        use_workers={self.config.use_workers}
+                        stretch_palette={self.config.stretch_palette}
                         on_type_changed={ctx.link().callback(Msg::TypeChanged)}
                         on_edit={ctx.link().callback(|_| Msg::EditConfig)}
+                        on_edit_colors={ctx.link().callback(|_| Msg::EditColors)}
                         on_view_stats_changed={ctx.link().callback(Msg::ViewStatsChanged)}
+                        on_render_mode_changed={ctx.link().callback(Msg::RenderModeChanged)}
+                        on_coloring_changed={ctx.link().callback(Msg::ColoringChanged)}
+                        color_cycle={self.config.color_cycle}
+                        on_color_cycle_changed={ctx.link().callback(Msg::ColorCycleChanged)}
+                        on_use_workers_changed={ctx.link().callback(Msg::UseWorkersChanged)}
+                        on_stretch_palette_changed={ctx.link().callback(Msg::StretchPaletteChanged)}
                         on_ctc_active={ctx.link().callback(Msg::CtcActive)}
                         on_ctc_done={ctx.link().callback(Msg::CtcDone)}
-                        edit_mode={self.edit_mode}
+                        edit_mode={self.edit_mode || self.color_edit_mode}
                     />
                     <div class="fractal_container">
                         <ModalPlain
@@ -178,12 +339,17 @@ is best viewed on a computer.".to_owned()
                         />
                         <EditColorConfig
                             config={self.config.color_cfg.clone()}
+                            palette={self.config.get_color_cfg_name()}
+                            transfer={self.config.get_transfer()}
                             edit_mode={self.color_edit_mode}
+                            cb_saved={ctx.link().callback(Msg::ColorCfgChanged)}
+                            cb_canceled={ctx.link().callback(|_| Msg::EditCfgCanceled)}
                         />
                         <EditJuliaCfg edit_mode={self.edit_mode && self.config.active_config == FractalType::JuliaSet}
                                         config={self.config.julia_set_cfg.clone()}
                                         canvas_width={DEFAULT_WIDTH}
                                         canvas_height={self.canvas_height}
+                                        render_mode={self.config.render_mode}
                                         cb_saved={ctx.link().callback(Msg::JuliaSetCfgChanged)}
                                         cb_canceled={ctx.link().callback(|_| Msg::EditCfgCanceled)}
                         />
@@ -191,14 +357,71 @@ is best viewed on a computer.".to_owned()
                                         config={self.config.mandelbrot_cfg.clone()}
                                         canvas_width={DEFAULT_WIDTH}
                                         canvas_height={self.canvas_height}
+                                        render_mode={self.config.render_mode}
                                         cb_saved={ctx.link().callback(Msg::MandelbrotCfgChanged)}
                                         cb_canceled={ctx.link().callback(|_| Msg::EditCfgCanceled)}
                         />
+                        <EditAttractorCfg edit_mode={self.edit_mode && self.config.active_config == FractalType::Attractor}
+                                        config={self.config.attractor_cfg.clone()}
+                                        canvas_width={DEFAULT_WIDTH}
+                                        canvas_height={self.canvas_height}
+                                        cb_saved={ctx.link().callback(Msg::AttractorCfgChanged)}
+                                        cb_canceled={ctx.link().callback(|_| Msg::EditCfgCanceled)}
+                        />
+                        <EditIfsCfg edit_mode={self.edit_mode && self.config.active_config == FractalType::Ifs}
+                                        config={self.config.ifs_cfg.clone()}
+                                        canvas_width={DEFAULT_WIDTH}
+                                        canvas_height={self.canvas_height}
+                                        cb_saved={ctx.link().callback(Msg::IfsCfgChanged)}
+                                        cb_canceled={ctx.link().callback(|_| Msg::EditCfgCanceled)}
+                        />
+                        <EditFlameCfg edit_mode={self.edit_mode && self.config.active_config == FractalType::Flame}
+                                        config={self.config.flame_cfg.clone()}
+                                        canvas_width={DEFAULT_WIDTH}
+                                        canvas_height={self.canvas_height}
+                                        cb_saved={ctx.link().callback(Msg::FlameCfgChanged)}
+                                        cb_canceled={ctx.link().callback(|_| Msg::EditCfgCanceled)}
+                        />
+                        <EditMandelbulbCfg edit_mode={self.edit_mode && self.config.active_config == FractalType::Mandelbulb}
+                                        config={self.config.mandelbulb_cfg.clone()}
+                                        cb_saved={ctx.link().callback(Msg::MandelbulbCfgChanged)}
+                                        cb_canceled={ctx.link().callback(|_| Msg::EditCfgCanceled)}
+                        />
+                        <EditQuaternionJuliaCfg edit_mode={self.edit_mode && self.config.active_config == FractalType::QuaternionJulia}
+                                        config={self.config.quaternion_julia_cfg.clone()}
+                                        cb_saved={ctx.link().callback(Msg::QuaternionJuliaCfgChanged)}
+                                        cb_canceled={ctx.link().callback(|_| Msg::EditCfgCanceled)}
+                        />
+                        <EditLambdaCfg edit_mode={self.edit_mode && self.config.active_config == FractalType::Lambda}
+                                        config={self.config.lambda_cfg.clone()}
+                                        canvas_width={DEFAULT_WIDTH}
+                                        canvas_height={self.canvas_height}
+                                        render_mode={self.config.render_mode}
+                                        cb_saved={ctx.link().callback(Msg::LambdaCfgChanged)}
+                                        cb_canceled={ctx.link().callback(|_| Msg::EditCfgCanceled)}
+                        />
+                        <EditLambdaJuliaCfg edit_mode={self.edit_mode && self.config.active_config == FractalType::LambdaJulia}
+                                        config={self.config.lambda_julia_cfg.clone()}
+                                        canvas_width={DEFAULT_WIDTH}
+                                        canvas_height={self.canvas_height}
+                                        render_mode={self.config.render_mode}
+                                        cb_saved={ctx.link().callback(Msg::LambdaJuliaCfgChanged)}
+                                        cb_canceled={ctx.link().callback(|_| Msg::EditCfgCanceled)}
+                        />
+                        <EditHybridCfg edit_mode={self.edit_mode && self.config.active_config == FractalType::Hybrid}
+                                        config={self.config.hybrid_cfg.clone()}
+                                        canvas_width={DEFAULT_WIDTH}
+                                        canvas_height={self.canvas_height}
+                                        render_mode={self.config.render_mode}
+                                        cb_saved={ctx.link().callback(Msg::HybridCfgChanged)}
+                                        cb_canceled={ctx.link().callback(|_| Msg::EditCfgCanceled)}
+                        />
                         <CanvasElement
                             config={self.config.clone()}
                             edit_mode={self.edit_mode}
                             canvas_width={DEFAULT_WIDTH}
                             canvas_height={self.canvas_height}
+                            cb_camera_orbit={ctx.link().callback(Msg::CameraOrbit)}
                         />
                     </div>
                 </div>
@@ -210,23 +433,66 @@ is best viewed on a computer.".to_owned()
 pub enum Msg {
     JuliaSetCfgChanged(JuliaSetCfg),
     MandelbrotCfgChanged(MandelbrotCfg),
+    AttractorCfgChanged(AttractorCfg),
+    IfsCfgChanged(IfsCfg),
+    FlameCfgChanged(FlameCfg),
+    MandelbulbCfgChanged(MandelbulbCfg),
+    QuaternionJuliaCfgChanged(QuaternionJuliaCfg),
+    LambdaCfgChanged(LambdaCfg),
+    LambdaJuliaCfgChanged(LambdaJuliaCfg),
+    HybridCfgChanged(HybridCfg),
+    ColorCfgChanged((ColorCfg, Option<String>, Option<Transfer>)),
+    CameraOrbit((i64, i64)),
     EditCfgCanceled,
     TypeChanged(FractalType),
     ViewStatsChanged(bool),
+    RenderModeChanged(RenderMode),
+    ColoringChanged(ColoringMode),
+    ColorCycleChanged(ColorCycle),
+    UseWorkersChanged(bool),
+    StretchPaletteChanged(bool),
     CtcActive(bool),
     CtcDone(WorkerStatus),
     CtcModalOk,
     DisclaimerOk,
     EditConfig,
+    EditColors,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Config {
     pub view_stats: bool,
+    #[serde(default)]
+    pub render_mode: RenderMode,
+    #[serde(default)]
+    pub coloring: ColoringMode,
+    #[serde(default)]
+    pub use_workers: bool,
+    #[serde(default)]
+    pub color_cycle: ColorCycle,
+    /// stretch the palette over the iterations present in the view
+    #[serde(default)]
+    pub stretch_palette: bool,
     pub color_cfg: ColorCfg,
     pub active_config: FractalType,
     pub julia_set_cfg: JuliaSetCfg,
     pub mandelbrot_cfg: MandelbrotCfg,
+    #[serde(default)]
+    pub attractor_cfg: AttractorCfg,
+    #[serde(default)]
+    pub ifs_cfg: IfsCfg,
+    #[serde(default)]
+    pub flame_cfg: FlameCfg,
+    #[serde(default)]
+    pub mandelbulb_cfg: MandelbulbCfg,
+    #[serde(default)]
+    pub quaternion_julia_cfg: QuaternionJuliaCfg,
+    #[serde(default)]
+    pub lambda_cfg: LambdaCfg,
+    #[serde(default)]
+    pub lambda_julia_cfg: LambdaJuliaCfg,
+    #[serde(default)]
+    pub hybrid_cfg: HybridCfg,
 }
 
 impl Default for Config {
@@ -265,13 +531,133 @@ impl Config {
         }
     }
 
-    fn std_cfg() -> Self {
+    /// The defaults of all fractals, ignoring the configuration stored in the browser
+    pub(crate) fn std_cfg() -> Self {
         Self {
             view_stats: false,
+            render_mode: RenderMode::default(),
+            coloring: ColoringMode::default(),
+            use_workers: false,
+            color_cycle: ColorCycle::default(),
+            stretch_palette: false,
             color_cfg: ColorCfg::default(),
             active_config: FractalType::Mandelbrot,
             julia_set_cfg: JuliaSetCfg::default(),
             mandelbrot_cfg: MandelbrotCfg::default(),
+            attractor_cfg: AttractorCfg::default(),
+            ifs_cfg: IfsCfg::default(),
+            flame_cfg: FlameCfg::default(),
+            mandelbulb_cfg: MandelbulbCfg::default(),
+            quaternion_julia_cfg: QuaternionJuliaCfg::default(),
+            lambda_cfg: LambdaCfg::default(),
+            lambda_julia_cfg: LambdaJuliaCfg::default(),
+            hybrid_cfg: HybridCfg::default(),
+        }
+    }
+
+    pub fn set_max_iterations(&mut self, max_iterations: u32) {
+        match self.active_config {
+            FractalType::Mandelbrot => self.mandelbrot_cfg.max_iterations = max_iterations,
+            FractalType::JuliaSet => self.julia_set_cfg.max_iterations = max_iterations,
+            FractalType::Mandelbulb => self.mandelbulb_cfg.max_iterations = max_iterations,
+            FractalType::QuaternionJulia => {
+                self.quaternion_julia_cfg.max_iterations = max_iterations;
+            }
+            FractalType::Lambda => self.lambda_cfg.max_iterations = max_iterations,
+            FractalType::LambdaJulia => self.lambda_julia_cfg.max_iterations = max_iterations,
+            FractalType::Hybrid => self.hybrid_cfg.max_iterations = max_iterations,
+            FractalType::Attractor | FractalType::Ifs | FractalType::Flame => (),
+        }
+    }
+
+    /// The name of the palette of the active fractal, `None` for the default palette
+    pub fn get_color_cfg_name(&self) -> Option<String> {
+        match self.active_config {
+            FractalType::Mandelbrot => self.mandelbrot_cfg.color_cfg_name.clone(),
+            FractalType::JuliaSet => self.julia_set_cfg.color_cfg_name.clone(),
+            FractalType::Attractor => self.attractor_cfg.color_cfg_name.clone(),
+            FractalType::Ifs => self.ifs_cfg.color_cfg_name.clone(),
+            FractalType::Flame => self.flame_cfg.color_cfg_name.clone(),
+            FractalType::Mandelbulb => self.mandelbulb_cfg.color_cfg_name.clone(),
+            FractalType::QuaternionJulia => self.quaternion_julia_cfg.color_cfg_name.clone(),
+            FractalType::Lambda => self.lambda_cfg.color_cfg_name.clone(),
+            FractalType::LambdaJulia => self.lambda_julia_cfg.color_cfg_name.clone(),
+            FractalType::Hybrid => self.hybrid_cfg.color_cfg_name.clone(),
+        }
+    }
+
+    pub fn set_color_cfg_name(&mut self, color_cfg_name: Option<String>) {
+        match self.active_config {
+            FractalType::Mandelbrot => self.mandelbrot_cfg.color_cfg_name = color_cfg_name,
+            FractalType::JuliaSet => self.julia_set_cfg.color_cfg_name = color_cfg_name,
+            FractalType::Attractor => self.attractor_cfg.color_cfg_name = color_cfg_name,
+            FractalType::Ifs => self.ifs_cfg.color_cfg_name = color_cfg_name,
+            FractalType::Flame => self.flame_cfg.color_cfg_name = color_cfg_name,
+            FractalType::Mandelbulb => self.mandelbulb_cfg.color_cfg_name = color_cfg_name,
+            FractalType::QuaternionJulia => {
+                self.quaternion_julia_cfg.color_cfg_name = color_cfg_name;
+            }
+            FractalType::Lambda => self.lambda_cfg.color_cfg_name = color_cfg_name,
+            FractalType::LambdaJulia => self.lambda_julia_cfg.color_cfg_name = color_cfg_name,
+            FractalType::Hybrid => self.hybrid_cfg.color_cfg_name = color_cfg_name,
+        }
+    }
+
+    /// The transfer function of the active escape time fractal, `None` for the others
+    pub fn get_transfer(&self) -> Option<Transfer> {
+        match self.active_config {
+            FractalType::Mandelbrot => Some(self.mandelbrot_cfg.transfer),
+            FractalType::JuliaSet => Some(self.julia_set_cfg.transfer),
+            FractalType::Lambda => Some(self.lambda_cfg.transfer),
+            FractalType::LambdaJulia => Some(self.lambda_julia_cfg.transfer),
+            FractalType::Hybrid => Some(self.hybrid_cfg.transfer),
+            FractalType::Attractor
+            | FractalType::Ifs
+            | FractalType::Flame
+            | FractalType::Mandelbulb
+            | FractalType::QuaternionJulia => None,
+        }
+    }
+
+    pub fn set_transfer(&mut self, transfer: Transfer) {
+        match self.active_config {
+            FractalType::Mandelbrot => self.mandelbrot_cfg.transfer = transfer,
+            FractalType::JuliaSet => self.julia_set_cfg.transfer = transfer,
+            FractalType::Lambda => self.lambda_cfg.transfer = transfer,
+            FractalType::LambdaJulia => self.lambda_julia_cfg.transfer = transfer,
+            FractalType::Hybrid => self.hybrid_cfg.transfer = transfer,
+            FractalType::Attractor
+            | FractalType::Ifs
+            | FractalType::Flame
+            | FractalType::Mandelbulb
+            | FractalType::QuaternionJulia => (),
+        }
+    }
+
+    /// Rotate the camera of the active 3D fractal around the origin
+    pub fn orbit_camera(&mut self, delta_yaw: f64, delta_pitch: f64) {
+        match self.active_config {
+            FractalType::Mandelbulb => {
+                self.mandelbulb_cfg.camera =
+                    self.mandelbulb_cfg.camera.orbit(delta_yaw, delta_pitch);
+            }
+            FractalType::QuaternionJulia => {
+                self.quaternion_julia_cfg.camera = self
+                    .quaternion_julia_cfg
+                    .camera
+                    .orbit(delta_yaw, delta_pitch);
+            }
+            _ => (),
+        }
+    }
+
+    /// The number of points the active fractal calculates, used for progress in the stats
+    pub fn get_total_points(&self, canvas_width: u32, canvas_height: u32) -> usize {
+        match self.active_config {
+            FractalType::Attractor => self.attractor_cfg.max_points as usize,
+            FractalType::Ifs => self.ifs_cfg.max_points as usize,
+            FractalType::Flame => self.flame_cfg.max_points as usize,
+            _ => canvas_width as usize * canvas_height as usize,
         }
     }
 
@@ -289,6 +675,42 @@ impl Config {
                     / (self.julia_set_cfg.x_max.real() - self.julia_set_cfg.x_min.real()))
                     as u32
             }
+            FractalType::Attractor => {
+                (f64::from(canvas_width)
+                    * (self.attractor_cfg.x_max.imag() - self.attractor_cfg.x_min.imag())
+                    / (self.attractor_cfg.x_max.real() - self.attractor_cfg.x_min.real()))
+                    as u32
+            }
+            FractalType::Ifs => {
+                (f64::from(canvas_width) * (self.ifs_cfg.x_max.imag() - self.ifs_cfg.x_min.imag())
+                    / (self.ifs_cfg.x_max.real() - self.ifs_cfg.x_min.real()))
+                    as u32
+            }
+            FractalType::Flame => {
+                (f64::from(canvas_width)
+                    * (self.flame_cfg.x_max.imag() - self.flame_cfg.x_min.imag())
+                    / (self.flame_cfg.x_max.real() - self.flame_cfg.x_min.real()))
+                    as u32
+            }
+            FractalType::Lambda => {
+                (f64::from(canvas_width)
+                    * (self.lambda_cfg.l_max.imag() - self.lambda_cfg.l_min.imag())
+                    / (self.lambda_cfg.l_max.real() - self.lambda_cfg.l_min.real()))
+                    as u32
+            }
+            FractalType::LambdaJulia => {
+                (f64::from(canvas_width)
+                    * (self.lambda_julia_cfg.x_max.imag() - self.lambda_julia_cfg.x_min.imag())
+                    / (self.lambda_julia_cfg.x_max.real() - self.lambda_julia_cfg.x_min.real()))
+                    as u32
+            }
+            FractalType::Hybrid => {
+                (f64::from(canvas_width)
+                    * (self.hybrid_cfg.c_max.imag() - self.hybrid_cfg.c_min.imag())
+                    / (self.hybrid_cfg.c_max.real() - self.hybrid_cfg.c_min.real()))
+                    as u32
+            }
+            FractalType::Mandelbulb | FractalType::QuaternionJulia => canvas_width * 3 / 4,
         }
     }
 }
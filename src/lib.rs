@@ -7,13 +7,21 @@ mod agents;
 mod components;
 mod work;
 
+use agents::fractal_worker::FractalWorkerHost;
 use components::root::Root;
+use yew_agent::Threaded;
 // mod model;
 
 #[allow(clippy::unused_unit)]
 #[wasm_bindgen(start)]
 pub fn start() {
     wasm_logger::init(wasm_logger::Config::default());
-    info!("starting up");
-    yew::start_app::<Root>();
+    if web_sys::window().is_none() {
+        // loaded as worker bundle - there is no window in a web worker
+        info!("starting up fractal worker");
+        FractalWorkerHost::register();
+    } else {
+        info!("starting up");
+        yew::start_app::<Root>();
+    }
 }
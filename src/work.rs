@@ -1,6 +1,16 @@
 pub mod canvas;
 pub mod colors;
 pub mod complex;
+pub mod density;
 pub mod fractal;
+pub mod framebuffer;
+pub mod gradient;
+pub mod histogram;
+pub mod palette_file;
+pub mod palette_library;
+pub mod quaternion;
 pub mod stats;
+pub mod tile_cache;
+pub mod transfer;
 pub mod util;
+pub mod vec3;
@@ -1,20 +1,86 @@
 use super::fractal::Points;
 use crate::components::root::Config;
-use crate::work::colors::{ColorRange, BACKGROUND_COLOR};
-use crate::work::fractal::FractalType;
+use crate::work::colors::{ColorRange, RgbColor, BACKGROUND_COLOR, POINT_COLOR};
+use crate::work::density::DensityBuffer;
+use crate::work::fractal::{ColoringMode, FractalType, ShadedImage};
+use crate::work::framebuffer::FrameBuffer;
+use crate::work::histogram::{EscapeRange, Histogram};
+use crate::work::transfer::Transfer;
 use js_sys::Object;
-use wasm_bindgen::{JsCast, JsValue};
+use std::f32::consts::TAU;
+use wasm_bindgen::{Clamped, JsCast, JsValue};
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageData};
 
+// brightness of the iteration colors is quantized to SHADE_LEVELS steps
+const SHADE_LEVELS: u32 = 8;
+// number of field lines per iteration band
+const FIELD_LINES: f32 = 8.0;
+// half width of a field line relative to the distance between lines
+const FIELD_LINE_WIDTH: f32 = 0.25;
+// number of precalculated palette colors for drawing density buffers
+const DENSITY_COLORS: usize = 256;
+// number of precalculated palette colors for cycling the palette
+const CYCLE_COLORS: usize = 1024;
+// palette lookup tables have at most LUT_LEN + 1 entries, above LUT_LEN iterations neighbouring
+// counts share an entry
+const LUT_LEN: u32 = 1 << 16;
+// iterations of pixels that have not been drawn yet, drawn in the background color
+const NOT_DRAWN: u32 = u32::MAX;
+
 pub struct Canvas {
     canvas: HtmlCanvasElement,
+    /// the image drawn, changed rows are copied to the canvas by `blit`
+    frame: FrameBuffer,
+    /// palette color of every entry up to `lut_len`, see `lut_index`
+    lut: Vec<[u8; 4]>,
+    /// one row of CYCLE_COLORS palette colors per shade level for repainting
+    cycle_palette: Vec<[u8; 4]>,
+    /// index into a row of `cycle_palette` of every entry up to `lut_len`
+    cycle_indices: Vec<usize>,
     iterations: u32,
-    width: u32,
+    lut_len: u32,
     color_range: ColorRange,
+    coloring: ColoringMode,
+    /// maps the iterations of a pixel to its palette position
+    transfer: Transfer,
+    /// iterations and shade of every pixel drawn by `draw_results`, kept to repaint the image
+    /// with a shifted palette
+    retained: Vec<(u32, u32)>,
+    has_retained: bool,
+    /// distribution of the retained iterations for ColoringMode::Histogram
+    histogram: Option<Histogram>,
+    /// stretch the palette over the escape range once the image is complete
+    stretch_palette: bool,
+    escape_range: Option<EscapeRange>,
+    stretched: bool,
 }
 
 impl Canvas {
-    pub fn new(canvas: HtmlCanvasElement, config: &Config, width: u32) -> Self {
+    pub fn new(canvas: HtmlCanvasElement, config: &Config) -> Self {
+        let mut res = Self {
+            canvas,
+            frame: FrameBuffer::new(0, 0, background_rgba()),
+            lut: Vec::new(),
+            cycle_palette: Vec::new(),
+            cycle_indices: Vec::new(),
+            iterations: 0,
+            lut_len: 0,
+            color_range: ColorRange::default(),
+            coloring: ColoringMode::default(),
+            transfer: Transfer::default(),
+            retained: Vec::new(),
+            has_retained: false,
+            histogram: None,
+            stretch_palette: false,
+            escape_range: None,
+            stretched: false,
+        };
+        res.set_config(config);
+        res
+    }
+
+    /// Pick up iterations and palette of the active fractal
+    pub fn set_config(&mut self, config: &Config) {
         let (iterations, color_cfg_name) = match config.active_config {
             FractalType::JuliaSet => (
                 config.julia_set_cfg.max_iterations,
@@ -24,9 +90,37 @@ impl Canvas {
                 config.mandelbrot_cfg.max_iterations,
                 config.mandelbrot_cfg.color_cfg_name.as_ref(),
             ),
+            FractalType::Attractor => (0, config.attractor_cfg.color_cfg_name.as_ref()),
+            FractalType::Ifs => (0, config.ifs_cfg.color_cfg_name.as_ref()),
+            FractalType::Flame => (0, config.flame_cfg.color_cfg_name.as_ref()),
+            FractalType::Mandelbulb => (
+                config.mandelbulb_cfg.max_iterations,
+                config.mandelbulb_cfg.color_cfg_name.as_ref(),
+            ),
+            FractalType::QuaternionJulia => (
+                config.quaternion_julia_cfg.max_iterations,
+                config.quaternion_julia_cfg.color_cfg_name.as_ref(),
+            ),
+            FractalType::Lambda => (
+                config.lambda_cfg.max_iterations,
+                config.lambda_cfg.color_cfg_name.as_ref(),
+            ),
+            FractalType::LambdaJulia => (
+                config.lambda_julia_cfg.max_iterations,
+                config.lambda_julia_cfg.color_cfg_name.as_ref(),
+            ),
+            FractalType::Hybrid => (
+                config.hybrid_cfg.max_iterations,
+                config.hybrid_cfg.color_cfg_name.as_ref(),
+            ),
         };
 
-        let color_range = if let Some(color_cfg_name) = color_cfg_name {
+        self.iterations = iterations;
+        self.lut_len = iterations.min(LUT_LEN);
+        self.coloring = config.coloring;
+        self.transfer = config.get_transfer().unwrap_or_default();
+        self.stretch_palette = config.stretch_palette;
+        self.color_range = if let Some(color_cfg_name) = color_cfg_name {
             if let Some(color_range) = config.color_cfg.get(color_cfg_name.as_str()) {
                 color_range.clone()
             } else {
@@ -35,13 +129,9 @@ impl Canvas {
         } else {
             ColorRange::default()
         };
-
-        Self {
-            canvas,
-            iterations,
-            width,
-            color_range,
-        }
+        self.lut = self.palette_lut();
+        self.cycle_palette = self.shaded_palette();
+        self.cycle_indices = self.palette_indices();
     }
 
     pub fn clear_canvas(&mut self, width: u32, height: u32) {
@@ -52,45 +142,219 @@ impl Canvas {
         if width != self.canvas.width() {
             self.canvas.set_width(width);
         }
-        self.width = width;
 
-        let ctx = self.get_2d_context();
-        // ctx.begin_path();
-        ctx.set_fill_style(&JsValue::from_str(BACKGROUND_COLOR));
-        ctx.fill_rect(0.into(), 0.into(), width.into(), height.into());
+        self.frame.clear(width, height, background_rgba());
+        self.blit();
+
+        self.retained.clear();
+        self.retained
+            .resize((width * height) as usize, (NOT_DRAWN, SHADE_LEVELS));
+        self.has_retained = false;
+        // only finish changes the indices set up by set_config
+        let recolored = self.histogram.is_some() || self.stretched;
+        self.histogram = None;
+        self.escape_range = None;
+        self.stretched = false;
+        if recolored {
+            self.cycle_indices = self.palette_indices();
+        }
     }
 
-    pub fn draw_results(&self, points: &Points) {
-        let mut x = points.x_start;
-        let mut y = points.y_start;
+    /// Whether an escape time image has been drawn since the canvas was cleared
+    pub fn has_retained(&self) -> bool {
+        self.has_retained
+    }
 
-        let ctx = self.get_2d_context();
-        ctx.set_fill_style(&JsValue::from_str("FFFFFF"));
+    pub fn draw_results(&mut self, points: &Points) {
+        let background = background_rgba();
+        for (((x, y), value), angle) in points.coords[0..points.num_points]
+            .iter()
+            .zip(points.values[0..points.num_points].iter())
+            .zip(points.angles[0..points.num_points].iter())
+        {
+            // points beyond the maximum iterations did not escape
+            let color = match self.lut_index(*value).and_then(|idx| self.lut.get(idx)) {
+                Some(color) => shaded(*color, self.shade(*angle)),
+                None => background,
+            };
+            self.frame.fill_block(*x, *y, points.block_size, &color);
+        }
+        self.retain(points);
+    }
 
-        let mut last_value = self.iterations + 2;
-        points.values[0..points.num_points]
+    /// Keep the iterations and shades of `points`, a block covers all of its pixels
+    fn retain(&mut self, points: &Points) {
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        if self.retained.len() != (width * height) as usize {
+            return;
+        }
+        for (((x, y), value), angle) in points.coords[0..points.num_points]
             .iter()
-            .for_each(|value| {
-                if *value != last_value {
-                    last_value = *value;
-                    let color = if *value > self.iterations {
-                        BACKGROUND_COLOR.to_string()
-                    } else {
-                        self.iterations_as_hue_to_rgb(*value)
-                    };
-                    // log!(format!("draw_result: color: {} pos: {},{}", color, x, y));
-                    ctx.set_fill_style(&JsValue::from_str(color.as_str()));
+            .zip(points.values[0..points.num_points].iter())
+            .zip(points.angles[0..points.num_points].iter())
+        {
+            let shade = self.shade(*angle);
+            for block_y in *y..(*y + points.block_size).min(height) {
+                let row = (block_y * width) as usize;
+                for block_x in *x..(*x + points.block_size).min(width) {
+                    self.retained[row + block_x as usize] = (*value, shade);
                 }
-                ctx.fill_rect(x.into(), y.into(), 1.0, 1.0);
-
-                x += 1;
-                if x >= self.width {
-                    x = 0;
-                    y += 1;
+            }
+            if *value <= self.iterations {
+                match self.escape_range.as_mut() {
+                    Some(range) => range.include(*value),
+                    None => self.escape_range = Some(EscapeRange::new(*value)),
                 }
+            }
+        }
+        self.has_retained = true;
+    }
+
+    /// Called once the fractal is complete, recolors the retained image by the histogram of its
+    /// iterations or stretches the palette over their range if asked for
+    pub fn finish(&mut self) {
+        if !self.has_retained {
+            return;
+        }
+        if self.coloring == ColoringMode::Histogram {
+            self.histogram = Some(Histogram::new(
+                self.retained.iter().map(|(value, _)| *value),
+                self.iterations,
+            ));
+            self.cycle_indices = self.palette_indices();
+            self.repaint(0.0);
+        } else if self.stretch_palette && self.escape_range.is_some() {
+            self.stretched = true;
+            self.cycle_indices = self.palette_indices();
+            self.repaint(0.0);
+        }
+    }
+
+    /// Repaint the retained escape time image with the palette shifted by `offset` (0.0..1.0)
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn repaint(&mut self, offset: f32) {
+        let shift = (offset * CYCLE_COLORS as f32) as usize;
+        let background = background_rgba();
+
+        let (palette, color_indices, retained) =
+            (&self.cycle_palette, &self.cycle_indices, &self.retained);
+        let (iterations, lut_len) = (self.iterations, self.lut_len);
+        self.frame
+            .fill_with(self.canvas.width(), self.canvas.height(), |idx| {
+                // pixels not drawn yet or not escaped have no palette index
+                let (value, shade) = retained.get(idx).copied().unwrap_or((NOT_DRAWN, 0));
+                lut_index(value, iterations, lut_len)
+                    .and_then(|lut_idx| color_indices.get(lut_idx))
+                    .map_or(background, |color_idx| {
+                        palette[shade as usize * CYCLE_COLORS + (color_idx + shift) % CYCLE_COLORS]
+                    })
             });
     }
 
+    /// Plot single pixels in POINT_COLOR, used for fractals that are not colored by iterations
+    pub fn draw_points(&mut self, points: &[(u32, u32)]) {
+        let color = RgbColor::from_hex(POINT_COLOR).map_or([0xFF; 4], |color| color.to_rgba());
+        for (x, y) in points {
+            self.frame.fill_block(*x, *y, 1, &color);
+        }
+    }
+
+    /// Draw the complete density buffer, the palette is scaled by the log density so pixels
+    /// that were never hit stay black. Colored buffers take the palette color from the average
+    /// palette index of the hits instead.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn draw_density(&mut self, density: &DensityBuffer) {
+        let palette = (0..DENSITY_COLORS)
+            .map(|idx| {
+                let percent = idx as f32 / (DENSITY_COLORS - 1) as f32;
+                self.palette_color(percent).scaled(percent).to_rgba()
+            })
+            .collect::<Vec<[u8; 4]>>();
+        let palette_full = (0..DENSITY_COLORS)
+            .map(|idx| {
+                self.palette_color(idx as f32 / (DENSITY_COLORS - 1) as f32)
+                    .to_rgba()
+            })
+            .collect::<Vec<[u8; 4]>>();
+
+        if density.is_colored() {
+            // flames: the hue comes from the palette index, the brightness from the density
+            self.frame
+                .fill_with(density.width(), density.height(), |idx| {
+                    let color_idx =
+                        (density.color(idx) * (DENSITY_COLORS - 1) as f32).round() as usize;
+                    let [red, green, blue, alpha] = palette_full[color_idx.min(DENSITY_COLORS - 1)];
+                    let brightness = density.brightness(idx);
+                    [
+                        (f32::from(red) * brightness) as u8,
+                        (f32::from(green) * brightness) as u8,
+                        (f32::from(blue) * brightness) as u8,
+                        alpha,
+                    ]
+                });
+        } else {
+            self.frame
+                .fill_with(density.width(), density.height(), |idx| {
+                    let color_idx =
+                        (density.brightness(idx) * (DENSITY_COLORS - 1) as f32).round() as usize;
+                    palette[color_idx.min(DENSITY_COLORS - 1)]
+                });
+        }
+    }
+
+    /// Draw the complete image of a 3D fractal, the palette color of every pixel is scaled by
+    /// its brightness, pixels without a hit get the background color
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn draw_shaded(&mut self, image: &ShadedImage) {
+        let palette = (0..DENSITY_COLORS)
+            .map(|idx| self.palette_color(idx as f32 / (DENSITY_COLORS - 1) as f32))
+            .collect::<Vec<RgbColor>>();
+        let background = background_rgba();
+
+        self.frame.fill_with(image.width(), image.height(), |idx| {
+            match image.pixel(idx) {
+                Some((brightness, color)) => {
+                    let color_idx = (color * (DENSITY_COLORS - 1) as f32).round() as usize;
+                    palette[color_idx.min(DENSITY_COLORS - 1)]
+                        .scaled(brightness)
+                        .to_rgba()
+                }
+                None => background,
+            }
+        });
+    }
+
+    /// Copy the rows of the frame buffer changed since the last blit to the canvas. The draw
+    /// functions only write to the frame buffer, the canvas is updated once per animation frame.
+    pub fn blit(&mut self) {
+        if let Some((start, end)) = self.frame.take_dirty_rows() {
+            if start >= end || self.frame.width() == 0 {
+                return;
+            }
+            let image_data = ImageData::new_with_u8_clamped_array_and_sh(
+                Clamped(self.frame.rows(start, end)),
+                self.frame.width(),
+                end - start,
+            )
+            .expect("failed to create image data");
+            self.get_2d_context()
+                .put_image_data(&image_data, 0.0, f64::from(start))
+                .expect("cannot draw image data");
+        }
+    }
+
     pub fn draw_frame(&self, x_start: u32, y_start: u32, x_end: u32, y_end: u32) -> ImageData {
         // log!(format!("draw_frame: ({},{}),({},{})", x_start,y_start, x_end, y_end));
 
@@ -169,16 +433,274 @@ impl Canvas {
             .expect("Failed to cast to CanvasRenderingContext2d")
     }
 
-    #[allow(clippy::cast_precision_loss)]
-    fn iterations_as_hue_to_rgb(&self, iterations: u32) -> String {
-        let color_rgb = match &self.color_range {
-            ColorRange::Hsl(range) => range
-                .percent_of((iterations as f32 / self.iterations as f32).min(1.0))
-                .to_rgb(),
-            ColorRange::Rgb(range) => {
-                range.percent_of((iterations as f32 / self.iterations as f32).min(1.0))
+    /// The brightness level 0..=SHADE_LEVELS of an escaped pixel derived from the angle of its
+    /// final orbit value
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn shade(&self, angle: f32) -> u32 {
+        match self.coloring {
+            ColoringMode::Iterations | ColoringMode::Histogram => SHADE_LEVELS,
+            ColoringMode::BinaryDecomposition => {
+                if angle >= 0.0 {
+                    SHADE_LEVELS
+                } else {
+                    SHADE_LEVELS / 2
+                }
+            }
+            ColoringMode::FieldLines => {
+                // distance to the nearest field line, 0.0 on the line and 0.5 halfway between
+                let pos = (angle / TAU * FIELD_LINES).rem_euclid(1.0);
+                let dist = pos.min(1.0 - pos);
+                let brightness = 0.3 + 0.7 * (dist / FIELD_LINE_WIDTH).min(1.0);
+                (brightness * SHADE_LEVELS as f32).round() as u32
             }
+        }
+    }
+
+    /// The palette position of a retained pixel with `value` iterations
+    #[allow(clippy::cast_precision_loss)]
+    #[inline]
+    fn retained_percent(&self, value: u32) -> f32 {
+        let percent = if let Some(histogram) = self.histogram.as_ref() {
+            histogram.percent(value)
+        } else if let Some(range) = self.escape_range.as_ref().filter(|_| self.stretched) {
+            range.percent(value)
+        } else {
+            (value as f32 / self.iterations as f32).min(1.0)
         };
-        color_rgb.to_string()
+        self.transfer.apply(percent, value as f32)
+    }
+
+    /// The entry of `value` iterations in `lut` and `cycle_indices`, None if it did not escape
+    #[inline]
+    fn lut_index(&self, value: u32) -> Option<usize> {
+        lut_index(value, self.iterations, self.lut_len)
+    }
+
+    /// The palette color of every entry up to `lut_len`, at full brightness
+    #[allow(clippy::cast_precision_loss)]
+    fn palette_lut(&self) -> Vec<[u8; 4]> {
+        (0..=self.lut_len)
+            .map(|idx| {
+                let value = lut_value(idx, self.iterations, self.lut_len);
+                let percent = (value as f32 / self.iterations as f32).min(1.0);
+                self.palette_color(self.transfer.apply(percent, value as f32))
+                    .to_rgba()
+            })
+            .collect()
+    }
+
+    /// One row of CYCLE_COLORS palette colors per shade level
+    #[allow(clippy::cast_precision_loss)]
+    fn shaded_palette(&self) -> Vec<[u8; 4]> {
+        (0..=SHADE_LEVELS)
+            .flat_map(|shade| {
+                (0..CYCLE_COLORS).map(move |idx| (shade, idx as f32 / CYCLE_COLORS as f32))
+            })
+            .map(|(shade, percent)| {
+                self.palette_color(percent)
+                    .scaled(shade as f32 / SHADE_LEVELS as f32)
+                    .to_rgba()
+            })
+            .collect()
+    }
+
+    /// The index into a row of `cycle_palette` of every entry up to `lut_len`, changes with the
+    /// histogram and the stretched palette
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn palette_indices(&self) -> Vec<usize> {
+        (0..=self.lut_len)
+            .map(|idx| {
+                let value = lut_value(idx, self.iterations, self.lut_len);
+                (self.retained_percent(value) * CYCLE_COLORS as f32) as usize % CYCLE_COLORS
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn palette_color(&self, percent: f32) -> RgbColor {
+        self.color_range.percent_of(percent)
+    }
+}
+
+/// The entry of `value` in a palette table of `lut_len + 1` entries spread over 0..=`iterations`,
+/// None for values above `iterations`
+#[inline]
+#[allow(clippy::cast_possible_truncation)]
+fn lut_index(value: u32, iterations: u32, lut_len: u32) -> Option<usize> {
+    (value <= iterations)
+        .then(|| (u64::from(value) * u64::from(lut_len) / u64::from(iterations.max(1))) as usize)
+}
+
+/// The lowest iteration count of entry `idx` of a palette table, the inverse of `lut_index`
+#[allow(clippy::cast_possible_truncation)]
+fn lut_value(idx: u32, iterations: u32, lut_len: u32) -> u32 {
+    let lut_len = u64::from(lut_len.max(1));
+    ((u64::from(idx) * u64::from(iterations) + lut_len - 1) / lut_len) as u32
+}
+
+fn background_rgba() -> [u8; 4] {
+    RgbColor::from_hex(BACKGROUND_COLOR).map_or([0, 0, 0, 0xFF], |color| color.to_rgba())
+}
+
+/// `color` darkened to `shade` of `SHADE_LEVELS`
+#[inline]
+#[allow(clippy::cast_possible_truncation)]
+fn shaded(color: [u8; 4], shade: u32) -> [u8; 4] {
+    if shade >= SHADE_LEVELS {
+        color
+    } else {
+        let scale =
+            |channel: u8| ((u32::from(channel) * shade + SHADE_LEVELS / 2) / SHADE_LEVELS) as u8;
+        [scale(color[0]), scale(color[1]), scale(color[2]), color[3]]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{lut_index, lut_value, LUT_LEN};
+
+    #[test]
+    fn test_lut_index() {
+        // up to LUT_LEN iterations every count has its own entry
+        for value in 0..=400 {
+            assert_eq!(lut_index(value, 400, 400), Some(value as usize));
+            assert_eq!(lut_value(value, 400, 400), value);
+        }
+        assert_eq!(lut_index(401, 400, 400), None);
+
+        // beyond the table is shared, every entry is the one of its lowest count
+        let iterations = 10_000_000;
+        assert_eq!(lut_index(0, iterations, LUT_LEN), Some(0));
+        assert_eq!(
+            lut_index(iterations, iterations, LUT_LEN),
+            Some(LUT_LEN as usize)
+        );
+        assert_eq!(lut_index(iterations + 1, iterations, LUT_LEN), None);
+        for idx in [0, 1, 2, 1000, LUT_LEN - 1, LUT_LEN] {
+            let value = lut_value(idx, iterations, LUT_LEN);
+            assert_eq!(lut_index(value, iterations, LUT_LEN), Some(idx as usize));
+            if value > 0 {
+                assert_eq!(
+                    lut_index(value - 1, iterations, LUT_LEN),
+                    Some(idx as usize - 1)
+                );
+            }
+        }
+    }
+}
+
+/// Compares drawing batches of points into the frame buffer with the former `fill_rect` per
+/// point, run with `wasm-pack test --headless --firefox`
+#[cfg(all(test, target_arch = "wasm32"))]
+mod bench {
+    use super::{Canvas, BACKGROUND_COLOR};
+    use crate::components::root::Config;
+    use crate::work::fractal::{Points, MAX_POINTS};
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_test::{console_log, wasm_bindgen_test, wasm_bindgen_test_configure};
+    use web_sys::{window, CanvasRenderingContext2d, HtmlCanvasElement};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const WIDTH: u32 = 1000;
+    const HEIGHT: u32 = 1000;
+    const ITERATIONS: u32 = 400;
+    const BATCHES: u32 = 200;
+    // batches are calculated on zero timeouts, the browser clamps them to 4ms
+    const BATCHES_PER_FRAME: u32 = 4;
+
+    fn points(batch: u32) -> Points {
+        let rows = MAX_POINTS as u32 / WIDTH;
+        let values = (0..rows * WIDTH)
+            .map(|idx| (idx * 7 + batch) % (ITERATIONS + 1))
+            .collect::<Vec<u32>>();
+        let mut points = Points::default();
+        points.set_span((batch * rows) % HEIGHT * WIDTH, WIDTH, &values, &[]);
+        points
+    }
+
+    fn millis() -> f64 {
+        window()
+            .expect("window should be available")
+            .performance()
+            .expect("performance should be available")
+            .now()
+    }
+
+    /// Reading a pixel back waits for all pending drawing of the canvas
+    fn flush(ctx: &CanvasRenderingContext2d) {
+        ctx.get_image_data(0.0, 0.0, 1.0, 1.0)
+            .expect("failed to retrieve image data");
+    }
+
+    #[wasm_bindgen_test]
+    fn bench_draw_results() {
+        let element = window()
+            .expect("window should be available")
+            .document()
+            .expect("document should be available")
+            .create_element("canvas")
+            .expect("canvas should be created")
+            .dyn_into::<HtmlCanvasElement>()
+            .expect("canvas element");
+        let mut config = Config::std_cfg();
+        config.mandelbrot_cfg.max_iterations = ITERATIONS;
+        let mut canvas = Canvas::new(element, &config);
+        canvas.clear_canvas(WIDTH, HEIGHT);
+        let batches = (0..BATCHES).map(points).collect::<Vec<Points>>();
+
+        // the former drawing: one fill_rect per point, the style changes with the color
+        let ctx = canvas.get_2d_context();
+        let start = millis();
+        for points in &batches {
+            let mut last_value = ITERATIONS + 2;
+            for ((x, y), value) in points.coords[0..points.num_points]
+                .iter()
+                .zip(points.values[0..points.num_points].iter())
+            {
+                if *value != last_value {
+                    last_value = *value;
+                    let color = if *value > ITERATIONS {
+                        BACKGROUND_COLOR.to_string()
+                    } else {
+                        let idx = canvas.lut_index(*value).unwrap_or_default();
+                        let [red, green, blue, _] = canvas.lut[idx];
+                        format!("#{:02X}{:02X}{:02X}", red, green, blue)
+                    };
+                    ctx.set_fill_style(&JsValue::from_str(color.as_str()));
+                }
+                ctx.fill_rect((*x).into(), (*y).into(), 1.0, 1.0);
+            }
+        }
+        flush(&ctx);
+        let fill_rect = millis() - start;
+
+        let start = millis();
+        for (idx, points) in batches.iter().enumerate() {
+            canvas.draw_results(points);
+            if (idx as u32 + 1) % BATCHES_PER_FRAME == 0 {
+                canvas.blit();
+            }
+        }
+        canvas.blit();
+        flush(&ctx);
+        let frame_buffer = millis() - start;
+
+        console_log!(
+            "{} batches of {} points: fill_rect {:.1} ms, frame buffer {:.1} ms, speed-up {:.1}",
+            BATCHES,
+            MAX_POINTS,
+            fill_rect,
+            frame_buffer,
+            fill_rect / frame_buffer.max(f64::EPSILON)
+        );
     }
 }
@@ -1,6 +1,8 @@
+use super::gradient::Gradient;
 use serde::{Deserialize, Serialize};
 
 pub const BACKGROUND_COLOR: &str = "#000000";
+pub const POINT_COLOR: &str = "#FFFFFF";
 
 pub const DEFAULT_HUE: f32 = 0.0;
 pub const DEFAULT_SATURATION: f32 = 1.0;
@@ -8,12 +10,57 @@ pub const DEFAULT_LIGHTNESS: f32 = 0.5;
 
 pub const HUE_RANGE: f32 = 300.0;
 
+/// Lightness and chroma of the default OKLCh range, most hues stay inside of sRGB at these
+pub const OKLCH_DEFAULT_LIGHTNESS: f32 = 0.7;
+pub const OKLCH_DEFAULT_CHROMA: f32 = 0.12;
+
 #[derive(PartialEq, Clone, Copy, Serialize, Deserialize, Debug)]
 pub enum Direction {
     Positive,
     Negative,
 }
 
+impl Direction {
+    pub const ALL: [Direction; 2] = [Direction::Positive, Direction::Negative];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::Positive => "Ascending",
+            Direction::Negative => "Descending",
+        }
+    }
+}
+
+/// Palette lengths per second the colors move by when cycling
+pub const CYCLE_DEFAULT_SPEED: f32 = 0.1;
+
+/// Speed and direction of the palette cycling animation
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct ColorCycle {
+    pub speed: f32,
+    pub dir: Direction,
+}
+
+impl ColorCycle {
+    /// The palette offset after `seconds` starting at `offset`, in 0.0..1.0
+    pub fn advance(&self, offset: f32, seconds: f32) -> f32 {
+        let delta = self.speed * seconds;
+        match self.dir {
+            Direction::Positive => (offset + delta).rem_euclid(1.0),
+            Direction::Negative => (offset - delta).rem_euclid(1.0),
+        }
+    }
+}
+
+impl Default for ColorCycle {
+    fn default() -> Self {
+        Self {
+            speed: CYCLE_DEFAULT_SPEED,
+            dir: Direction::Positive,
+        }
+    }
+}
+
 #[inline]
 fn range_percent(start: f32, end: f32, dir: Direction, max: f32, percent: f32) -> f32 {
     debug_assert!(percent <= 1.0 && percent >= 0.0);
@@ -57,11 +104,22 @@ impl HslRange {
         }
     }
 
-    #[allow(dead_code)]
     pub fn new_with_dir(start: HslColor, end: HslColor, dir: Direction) -> Self {
         Self { dir, start, end }
     }
 
+    pub fn dir(&self) -> Direction {
+        self.dir
+    }
+
+    pub fn start(&self) -> &HslColor {
+        &self.start
+    }
+
+    pub fn end(&self) -> &HslColor {
+        &self.end
+    }
+
     #[inline]
     pub fn percent_of(&self, percent: f32) -> HslColor {
         HslColor {
@@ -115,11 +173,22 @@ impl RgbRange {
         }
     }
 
-    #[allow(dead_code)]
     pub fn new_with_dir(start: RgbColor, end: RgbColor, dir: Direction) -> Self {
         Self { start, end, dir }
     }
 
+    pub fn dir(&self) -> Direction {
+        self.dir
+    }
+
+    pub fn start(&self) -> &RgbColor {
+        &self.start
+    }
+
+    pub fn end(&self) -> &RgbColor {
+        &self.end
+    }
+
     #[inline]
     pub fn percent_of(&self, percent: f32) -> RgbColor {
         RgbColor {
@@ -151,10 +220,111 @@ impl RgbRange {
     }
 }
 
+/// A range interpolated in the perceptually uniform OKLab space, along the straight line
+/// between start and end
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct OkLabRange {
+    start: OkLabColor,
+    end: OkLabColor,
+}
+
+impl OkLabRange {
+    pub fn new(start: OkLabColor, end: OkLabColor) -> Self {
+        Self { start, end }
+    }
+
+    pub fn start(&self) -> &OkLabColor {
+        &self.start
+    }
+
+    pub fn end(&self) -> &OkLabColor {
+        &self.end
+    }
+
+    #[inline]
+    pub fn percent_of(&self, percent: f32) -> OkLabColor {
+        let lerp = |start: f32, end: f32| start + (end - start) * percent;
+        OkLabColor {
+            lightness: lerp(self.start.lightness, self.end.lightness),
+            a: lerp(self.start.a, self.end.a),
+            b: lerp(self.start.b, self.end.b),
+        }
+    }
+}
+
+/// A range interpolated in OKLCh, the hue runs around the color circle in direction `dir`
+/// with even perceived lightness and chroma
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct OkLchRange {
+    dir: Direction,
+    start: OkLchColor,
+    end: OkLchColor,
+}
+
+impl OkLchRange {
+    pub fn new_with_dir(start: OkLchColor, end: OkLchColor, dir: Direction) -> Self {
+        Self { dir, start, end }
+    }
+
+    pub fn dir(&self) -> Direction {
+        self.dir
+    }
+
+    pub fn start(&self) -> &OkLchColor {
+        &self.start
+    }
+
+    pub fn end(&self) -> &OkLchColor {
+        &self.end
+    }
+
+    #[inline]
+    pub fn percent_of(&self, percent: f32) -> OkLchColor {
+        let lerp = |start: f32, end: f32| start + (end - start) * percent;
+        OkLchColor {
+            lightness: lerp(self.start.lightness, self.end.lightness),
+            chroma: lerp(self.start.chroma, self.end.chroma),
+            hue: range_percent(self.start.hue, self.end.hue, self.dir, 360.0, percent),
+        }
+    }
+}
+
+impl Default for OkLchRange {
+    /// The hues of the default HSL range at even lightness and chroma
+    fn default() -> Self {
+        Self {
+            dir: Direction::Positive,
+            start: OkLchColor::new(OKLCH_DEFAULT_LIGHTNESS, OKLCH_DEFAULT_CHROMA, DEFAULT_HUE),
+            end: OkLchColor::new(
+                OKLCH_DEFAULT_LIGHTNESS,
+                OKLCH_DEFAULT_CHROMA,
+                DEFAULT_HUE + HUE_RANGE,
+            ),
+        }
+    }
+}
+
 #[derive(PartialEq, Clone, Serialize, Deserialize)]
 pub enum ColorRange {
     Hsl(HslRange),
     Rgb(RgbRange),
+    Gradient(Gradient),
+    OkLab(OkLabRange),
+    OkLch(OkLchRange),
+}
+
+impl ColorRange {
+    /// The color at `percent` (0.0..=1.0) of the way from start to end
+    #[inline]
+    pub fn percent_of(&self, percent: f32) -> RgbColor {
+        match self {
+            ColorRange::Hsl(range) => range.percent_of(percent).to_rgb(),
+            ColorRange::Rgb(range) => range.percent_of(percent),
+            ColorRange::Gradient(gradient) => gradient.percent_of(percent),
+            ColorRange::OkLab(range) => range.percent_of(percent).to_rgb(),
+            ColorRange::OkLch(range) => range.percent_of(percent).to_rgb(),
+        }
+    }
 }
 
 impl Default for ColorRange {
@@ -179,6 +349,18 @@ impl HslColor {
         }
     }
 
+    pub fn hue(&self) -> f32 {
+        self.hue
+    }
+
+    pub fn saturation(&self) -> f32 {
+        self.saturation
+    }
+
+    pub fn lightness(&self) -> f32 {
+        self.lightness
+    }
+
     #[allow(
         clippy::many_single_char_names,
         clippy::cast_possible_truncation,
@@ -220,6 +402,95 @@ impl HslColor {
         RgbColor::new((r & 0xFF) as u8, (g & 0xFF) as u8, (b & 0xFF) as u8)
     }
 }
+
+/// A color in the perceptually uniform OKLab space, see <https://bottosson.github.io/posts/oklab/>
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct OkLabColor {
+    lightness: f32,
+    a: f32,
+    b: f32,
+}
+
+impl OkLabColor {
+    pub fn new(lightness: f32, a: f32, b: f32) -> Self {
+        Self { lightness, a, b }
+    }
+
+    pub fn lightness(&self) -> f32 {
+        self.lightness
+    }
+
+    /// The green (negative) to red (positive) axis
+    pub fn a(&self) -> f32 {
+        self.a
+    }
+
+    /// The blue (negative) to yellow (positive) axis
+    pub fn b(&self) -> f32 {
+        self.b
+    }
+
+    /// The nearest sRGB color, colors outside of sRGB are clipped
+    #[allow(clippy::many_single_char_names)]
+    pub fn to_rgb(&self) -> RgbColor {
+        let l = (self.lightness + 0.396_337_78 * self.a + 0.215_803_76 * self.b).powi(3);
+        let m = (self.lightness - 0.105_561_346 * self.a - 0.063_854_17 * self.b).powi(3);
+        let s = (self.lightness - 0.089_484_18 * self.a - 1.291_485_5 * self.b).powi(3);
+        RgbColor::new(
+            linear_to_srgb(4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s),
+            linear_to_srgb(-1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s),
+            linear_to_srgb(-0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s),
+        )
+    }
+
+    pub fn to_oklch(&self) -> OkLchColor {
+        OkLchColor {
+            lightness: self.lightness,
+            chroma: self.a.hypot(self.b),
+            hue: self.b.atan2(self.a).to_degrees().rem_euclid(360.0),
+        }
+    }
+}
+
+/// OKLab in polar coordinates, the hue in degrees
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct OkLchColor {
+    lightness: f32,
+    chroma: f32,
+    hue: f32,
+}
+
+impl OkLchColor {
+    pub fn new(lightness: f32, chroma: f32, hue: f32) -> Self {
+        Self {
+            lightness,
+            chroma,
+            hue: hue.rem_euclid(360.0),
+        }
+    }
+
+    pub fn lightness(&self) -> f32 {
+        self.lightness
+    }
+
+    pub fn chroma(&self) -> f32 {
+        self.chroma
+    }
+
+    pub fn hue(&self) -> f32 {
+        self.hue
+    }
+
+    pub fn to_oklab(&self) -> OkLabColor {
+        let (sin, cos) = self.hue.to_radians().sin_cos();
+        OkLabColor::new(self.lightness, self.chroma * cos, self.chroma * sin)
+    }
+
+    pub fn to_rgb(&self) -> RgbColor {
+        self.to_oklab().to_rgb()
+    }
+}
+
 #[derive(PartialEq, Clone, Serialize, Deserialize)]
 pub struct RgbColor {
     red: u8,
@@ -232,15 +503,119 @@ impl RgbColor {
         Self { red, green, blue }
     }
 
+    pub fn red(&self) -> u8 {
+        self.red
+    }
+
+    pub fn green(&self) -> u8 {
+        self.green
+    }
+
+    pub fn blue(&self) -> u8 {
+        self.blue
+    }
+
     pub fn to_string(&self) -> String {
         format!("#{:0>2X}{:0>2X}{:0>2X}", self.red, self.green, self.blue)
     }
+
+    pub fn to_rgba(&self) -> [u8; 4] {
+        [self.red, self.green, self.blue, 0xFF]
+    }
+
+    /// Parse a color in the `#RRGGBB` format of `to_string` and HTML color inputs
+    pub fn from_hex(hex: &str) -> Option<RgbColor> {
+        let hex = hex.strip_prefix('#')?;
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |idx: usize| u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok();
+        Some(RgbColor::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    #[allow(clippy::many_single_char_names)]
+    pub fn to_hsl(&self) -> HslColor {
+        // see: https://www.rapidtables.com/convert/color/rgb-to-hsl.html
+        let (r, g, b) = (
+            f32::from(self.red) / 255.0,
+            f32::from(self.green) / 255.0,
+            f32::from(self.blue) / 255.0,
+        );
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let lightness = (max + min) / 2.0;
+        if delta == 0.0 {
+            return HslColor::new(0.0, 0.0, lightness);
+        }
+        let saturation = (delta / (1.0 - (2.0 * lightness - 1.0).abs())).min(1.0);
+        let hue = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        HslColor::new(hue, saturation, lightness)
+    }
+
+    #[allow(clippy::many_single_char_names)]
+    pub fn to_oklab(&self) -> OkLabColor {
+        // see: https://bottosson.github.io/posts/oklab/
+        let (r, g, b) = (
+            srgb_to_linear(self.red),
+            srgb_to_linear(self.green),
+            srgb_to_linear(self.blue),
+        );
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+        OkLabColor::new(
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        )
+    }
+
+    pub fn to_oklch(&self) -> OkLchColor {
+        self.to_oklab().to_oklch()
+    }
+
+    /// Darken the color by multiplying all channels with a factor in 0.0..=1.0
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn scaled(&self, factor: f32) -> RgbColor {
+        let scale = |channel: u8| (f32::from(channel) * factor.clamp(0.0, 1.0)).round() as u8;
+        RgbColor::new(scale(self.red), scale(self.green), scale(self.blue))
+    }
+}
+
+fn srgb_to_linear(channel: u8) -> f32 {
+    let value = f32::from(channel) / 255.0;
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn linear_to_srgb(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let value = if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+    (value * 255.0).round() as u8
 }
 
 #[cfg(test)]
 mod test {
-    use super::{HslColor, DEFAULT_LIGHTNESS, DEFAULT_SATURATION};
-    use crate::work::colors::{Direction, HslRange, DEFAULT_HUE, HUE_RANGE};
+    use super::{HslColor, RgbColor, DEFAULT_LIGHTNESS, DEFAULT_SATURATION};
+    use crate::work::colors::{
+        ColorCycle, Direction, HslRange, OkLabColor, OkLabRange, OkLchColor, OkLchRange,
+        DEFAULT_HUE, HUE_RANGE,
+    };
 
     #[test]
     fn test_hsl_to_rgb() {
@@ -262,6 +637,60 @@ mod test {
         assert_eq!(color.to_rgb().to_string().as_str(), "#FF0055");
     }
 
+    #[test]
+    fn test_rgb_scaled() {
+        let color = RgbColor::new(255, 128, 0);
+        assert_eq!(color.scaled(1.0).to_string().as_str(), "#FF8000");
+        assert_eq!(color.scaled(0.5).to_string().as_str(), "#804000");
+        assert_eq!(color.scaled(0.0).to_string().as_str(), "#000000");
+    }
+
+    #[test]
+    fn test_rgb_conversions() {
+        let colors = [
+            RgbColor::new(255, 0, 0),
+            RgbColor::new(0, 128, 255),
+            RgbColor::new(12, 200, 99),
+            RgbColor::new(77, 77, 77),
+            RgbColor::new(255, 255, 255),
+        ];
+        // to_rgb rounds down, a channel may come back one less
+        let close = |a: &RgbColor, b: &RgbColor| {
+            let diff = |a: u8, b: u8| (i16::from(a) - i16::from(b)).abs() <= 1;
+            diff(a.red(), b.red()) && diff(a.green(), b.green()) && diff(a.blue(), b.blue())
+        };
+        for color in colors.iter() {
+            assert!(close(&color.to_hsl().to_rgb(), color));
+            assert_eq!(color.to_oklab().to_rgb().to_string(), color.to_string());
+            assert_eq!(color.to_oklch().to_rgb().to_string(), color.to_string());
+            assert_eq!(
+                RgbColor::from_hex(color.to_string().as_str())
+                    .expect("valid hex color")
+                    .to_string(),
+                color.to_string()
+            );
+        }
+        assert!(RgbColor::from_hex("#12345").is_none());
+        assert!(RgbColor::from_hex("123456").is_none());
+        assert!(RgbColor::from_hex("#12345G").is_none());
+    }
+
+    #[test]
+    fn test_color_cycle() {
+        let cycle = ColorCycle {
+            speed: 0.25,
+            dir: Direction::Positive,
+        };
+        assert_eq!(cycle.advance(0.0, 1.0), 0.25);
+        assert_eq!(cycle.advance(0.5, 3.0), 0.25);
+        let cycle = ColorCycle {
+            speed: 0.25,
+            dir: Direction::Negative,
+        };
+        assert_eq!(cycle.advance(0.0, 1.0), 0.75);
+        assert_eq!(cycle.advance(0.5, 1.0), 0.25);
+    }
+
     #[test]
     fn test_hsl_range() {
         let range = HslRange::default();
@@ -280,4 +709,77 @@ mod test {
         assert_eq!(color.saturation, DEFAULT_SATURATION);
         assert_eq!(color.lightness, DEFAULT_LIGHTNESS);
     }
+
+    #[test]
+    fn test_oklab_reference() {
+        // reference values of the CSS Color Module Level 4
+        let close = |value: f32, expected: f32| (value - expected).abs() < 1e-3;
+        let references = [
+            ("#FFFFFF", 1.0, 0.0, 0.0),
+            ("#000000", 0.0, 0.0, 0.0),
+            ("#FF0000", 0.627_955, 0.224_863, 0.125_846),
+            ("#00FF00", 0.866_440, -0.233_888, 0.179_498),
+            ("#0000FF", 0.452_014, -0.032_457, -0.311_528),
+        ];
+        for (hex, lightness, a, b) in references.iter() {
+            let lab = RgbColor::from_hex(hex).expect("valid hex color").to_oklab();
+            assert!(close(lab.lightness(), *lightness), "{}: {:?}", hex, lab);
+            assert!(close(lab.a(), *a), "{}: {:?}", hex, lab);
+            assert!(close(lab.b(), *b), "{}: {:?}", hex, lab);
+            assert_eq!(
+                OkLabColor::new(*lightness, *a, *b)
+                    .to_rgb()
+                    .to_string()
+                    .as_str(),
+                *hex
+            );
+        }
+
+        let red = RgbColor::new(255, 0, 0).to_oklch();
+        assert!(close(red.lightness(), 0.627_955));
+        assert!(close(red.chroma(), 0.257_683));
+        assert!((red.hue() - 29.234).abs() < 0.01);
+        assert_eq!(
+            OkLchColor::new(0.627_955, 0.257_683, 29.234)
+                .to_rgb()
+                .to_string()
+                .as_str(),
+            "#FF0000"
+        );
+    }
+
+    #[test]
+    fn test_oklab_ranges() {
+        let range = OkLabRange::new(
+            RgbColor::new(0, 0, 0).to_oklab(),
+            RgbColor::new(255, 255, 255).to_oklab(),
+        );
+        let gray = range.percent_of(0.5);
+        assert!((gray.lightness() - 0.5).abs() < 1e-3);
+        assert_eq!(
+            range.percent_of(1.0).to_rgb().to_string().as_str(),
+            "#FFFFFF"
+        );
+
+        // even lightness along the whole range, unlike the bright bands of HSL
+        let range = OkLchRange::default();
+        for step in 0..=10 {
+            let color = range.percent_of(step as f32 / 10.0);
+            assert_eq!(color.lightness(), range.start().lightness());
+        }
+        assert_eq!(range.percent_of(0.5).hue(), DEFAULT_HUE + HUE_RANGE * 0.5);
+
+        let range = OkLchRange::new_with_dir(
+            OkLchColor::new(0.7, 0.1, 350.0),
+            OkLchColor::new(0.7, 0.1, 10.0),
+            Direction::Positive,
+        );
+        assert!(range.percent_of(0.5).hue().abs() < 1e-3);
+        let range = OkLchRange::new_with_dir(
+            OkLchColor::new(0.7, 0.1, 350.0),
+            OkLchColor::new(0.7, 0.1, 10.0),
+            Direction::Negative,
+        );
+        assert!((range.percent_of(0.5).hue() - 180.0).abs() < 1e-3);
+    }
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display, Formatter};
-use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 #[derive(Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Complex {
@@ -40,6 +40,12 @@ impl Complex {
         f64::sqrt(self.square_length())
     }
 
+    /// The argument in the range -pi..=pi
+    #[inline]
+    pub fn arg(&self) -> f64 {
+        self.imag.atan2(self.real)
+    }
+
     #[inline]
     pub fn mul_by(&self, other: &Complex) -> Complex {
         Self {
@@ -48,6 +54,15 @@ impl Complex {
         }
     }
 
+    /// The principal square root, the other root is its negation
+    pub fn sqrt(&self) -> Complex {
+        let norm = self.norm();
+        Complex {
+            real: f64::sqrt((norm + self.real) / 2.0),
+            imag: f64::sqrt(((norm - self.real) / 2.0).max(0.0)).copysign(self.imag),
+        }
+    }
+
     pub fn powi(&self, power: u32) -> Complex {
         // a ^ (b + c) = a ^ b * a ^ c
         match power {
@@ -137,6 +152,17 @@ impl SubAssign for Complex {
     }
 }
 
+impl Neg for Complex {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            real: -self.real,
+            imag: -self.imag,
+        }
+    }
+}
+
 impl Mul for Complex {
     type Output = Self;
     fn mul(self, other: Self) -> Self::Output {
@@ -204,4 +230,20 @@ pub mod test {
         let res = c.powi(5);
         assert_eq!(res, c.mul_by(&c).mul_by(&c).mul_by(&c).mul_by(&c));
     }
+
+    #[test]
+    fn test_sqrt() {
+        assert_eq!(Complex::new(4.0, 0.0).sqrt(), Complex::new(2.0, 0.0));
+        assert_eq!(Complex::new(-4.0, 0.0).sqrt(), Complex::new(0.0, 2.0));
+        assert_eq!(Complex::new(3.0, 4.0).sqrt(), Complex::new(2.0, 1.0));
+        assert_eq!(Complex::new(3.0, -4.0).sqrt(), Complex::new(2.0, -1.0));
+        assert_eq!(Complex::new(0.0, 0.0).sqrt(), Complex::new(0.0, 0.0));
+
+        let c = Complex::new(-0.8, 0.156);
+        let root = c.sqrt();
+        let square = root * root;
+        assert!((square - c).norm() < 1e-12);
+        let square = -root * -root;
+        assert!((square - c).norm() < 1e-12);
+    }
 }
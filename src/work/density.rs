@@ -0,0 +1,157 @@
+/// Hit counts per pixel for fractals that plot the orbit of a single point, such as strange
+/// attractors, instead of coloring every pixel by its own iteration
+pub struct DensityBuffer {
+    width: u32,
+    height: u32,
+    hits: Vec<u32>,
+    max_hits: u32,
+    /// sum of the palette indices of the colored hits per pixel
+    colors: Vec<f32>,
+    colored: bool,
+    gamma: f64,
+}
+
+impl DensityBuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            hits: vec![0; (width * height) as usize],
+            max_hits: 0,
+            colors: vec![0.0; (width * height) as usize],
+            colored: false,
+            gamma: 1.0,
+        }
+    }
+
+    /// Set the gamma applied by `brightness`, values below 1.0 are ignored
+    pub fn with_gamma(mut self, gamma: f64) -> Self {
+        self.gamma = gamma.max(1.0);
+        self
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// True if any hit was added with a palette index
+    pub fn is_colored(&self) -> bool {
+        self.colored
+    }
+
+    /// Count a hit of the pixel at (x, y), returns false if (x, y) is not on the canvas
+    #[inline]
+    pub fn add(&mut self, x: f64, y: f64) -> bool {
+        self.hit(x, y).is_some()
+    }
+
+    /// Count a hit of the pixel at (x, y) with the palette index `color` in 0.0..=1.0,
+    /// returns false if (x, y) is not on the canvas
+    #[inline]
+    pub fn add_colored(&mut self, x: f64, y: f64, color: f32) -> bool {
+        if let Some(idx) = self.hit(x, y) {
+            self.colors[idx] += color;
+            self.colored = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    #[inline]
+    fn hit(&mut self, x: f64, y: f64) -> Option<usize> {
+        if x >= 0.0 && x < f64::from(self.width) && y >= 0.0 && y < f64::from(self.height) {
+            let idx = (y as u32 * self.width + x as u32) as usize;
+            let hits = self.hits[idx].saturating_add(1);
+            self.hits[idx] = hits;
+            self.max_hits = self.max_hits.max(hits);
+            Some(idx)
+        } else {
+            None
+        }
+    }
+
+    /// Log scale tone mapping: the density of pixel `idx` in 0.0..=1.0 relative to the most
+    /// frequently hit pixel
+    #[allow(clippy::cast_possible_truncation)]
+    #[inline]
+    pub fn log_density(&self, idx: usize) -> f32 {
+        if self.max_hits == 0 {
+            0.0
+        } else {
+            (f64::from(self.hits[idx]).ln_1p() / f64::from(self.max_hits).ln_1p()) as f32
+        }
+    }
+
+    /// The log density of pixel `idx` with gamma correction applied
+    #[allow(clippy::cast_possible_truncation)]
+    #[inline]
+    pub fn brightness(&self, idx: usize) -> f32 {
+        f64::from(self.log_density(idx))
+            .powf(self.gamma.recip())
+            .min(1.0) as f32
+    }
+
+    /// The average palette index of the hits of pixel `idx`
+    #[allow(clippy::cast_precision_loss)]
+    #[inline]
+    pub fn color(&self, idx: usize) -> f32 {
+        if self.hits[idx] == 0 {
+            0.0
+        } else {
+            self.colors[idx] / self.hits[idx] as f32
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DensityBuffer;
+
+    #[test]
+    fn test_add() {
+        let mut buffer = DensityBuffer::new(4, 3);
+        assert!(buffer.add(0.5, 0.5));
+        assert!(buffer.add(3.9, 2.9));
+        assert!(buffer.add(3.0, 2.0));
+        assert!(!buffer.add(4.0, 0.0));
+        assert!(!buffer.add(0.0, -0.1));
+        assert_eq!(buffer.hits[0], 1);
+        assert_eq!(buffer.hits[11], 2);
+        assert_eq!(buffer.max_hits, 2);
+    }
+
+    #[test]
+    fn test_log_density() {
+        let mut buffer = DensityBuffer::new(2, 1);
+        assert_eq!(buffer.log_density(0), 0.0);
+        for _ in 0..99 {
+            buffer.add(0.0, 0.0);
+        }
+        buffer.add(1.0, 0.0);
+        assert_eq!(buffer.log_density(0), 1.0);
+        let density = buffer.log_density(1);
+        assert!((density - 2f32.ln() / 100f32.ln()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_gamma_and_color() {
+        let mut buffer = DensityBuffer::new(2, 1).with_gamma(2.0);
+        assert!(!buffer.is_colored());
+        for _ in 0..99 {
+            buffer.add_colored(0.0, 0.0, 1.0);
+        }
+        buffer.add_colored(1.0, 0.0, 0.2);
+        buffer.add_colored(1.0, 0.0, 0.6);
+        assert!(buffer.is_colored());
+        assert!((buffer.color(0) - 1.0).abs() < 1e-6);
+        assert!((buffer.color(1) - 0.4).abs() < 1e-6);
+        assert!((buffer.brightness(1) - buffer.log_density(1).sqrt()).abs() < 1e-6);
+        assert!(buffer.brightness(1) > buffer.log_density(1));
+    }
+}
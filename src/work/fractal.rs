@@ -1,19 +1,65 @@
 use super::stats::Stats;
+use super::tile_cache::{grid_size, zoom_level, TileCache, TileKey, TILE_SIZE};
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+mod attractor;
+pub use attractor::{AttractorCfg, AttractorMap, AttractorType, ATTRACTOR_DEFAULT_POINTS};
+mod auto_iterations;
+pub use auto_iterations::{resolve_auto_iterations, AutoIterations};
+mod bailout;
+pub use bailout::{Bailout, BailoutShape};
+mod flame;
+pub use flame::{FlameCfg, FlameMap, FlameTransform, Variation, WeightedVariation};
+mod hybrid;
+pub use hybrid::{
+    Hybrid, HybridCfg, HybridFormula, HybridStep, HYBRID_DEFAULT_C_MAX, HYBRID_DEFAULT_C_MIN,
+    HYBRID_DEFAULT_ITERATIONS,
+};
+mod ifs;
+pub use ifs::{AffineTransform, IfsCfg, IfsMap, IfsPreset};
+mod julia_iim;
+pub use julia_iim::{IimCalculator, IIM_DEFAULT_MAX_HITS};
 mod julia_set;
 pub use julia_set::{
-    JuliaSet, JuliaSetCfg, JULIA_DEFAULT_ITERATIONS, JULIA_DEFAULT_X_MAX, JULIA_DEFAULT_X_MIN,
+    JuliaRenderer, JuliaSet, JuliaSetCfg, JULIA_DEFAULT_C, JULIA_DEFAULT_ITERATIONS,
+    JULIA_DEFAULT_X_MAX, JULIA_DEFAULT_X_MIN,
+};
+mod lambda;
+pub use lambda::{
+    Lambda, LambdaCfg, LambdaJulia, LambdaJuliaCfg, LAMBDA_DEFAULT_ITERATIONS,
+    LAMBDA_DEFAULT_L_MAX, LAMBDA_DEFAULT_L_MIN, LAMBDA_JULIA_DEFAULT_ITERATIONS,
+    LAMBDA_JULIA_DEFAULT_LAMBDA, LAMBDA_JULIA_DEFAULT_X_MAX, LAMBDA_JULIA_DEFAULT_X_MIN,
 };
 mod mandelbrot;
+mod mandelbulb;
+pub use mandelbulb::{
+    Mandelbulb, MandelbulbCfg, MANDELBULB_DEFAULT_ITERATIONS, MANDELBULB_DEFAULT_POWER,
+};
+mod orbit;
+pub use orbit::{OrbitCalculator, OrbitMap, XorShiftRng};
+mod quaternion_julia;
+pub use quaternion_julia::{
+    QuaternionJulia, QuaternionJuliaCfg, QUATERNION_JULIA_DEFAULT_ITERATIONS,
+};
+mod ray_march;
 use crate::components::root::Config;
 use crate::work::complex::Complex;
 pub use mandelbrot::{
-    Mandelbrot, MandelbrotCfg, MANDELBROT_DEFAULT_C_MAX, MANDELBROT_DEFAULT_C_MIN,
+    critical_point, Mandelbrot, MandelbrotCfg, MANDELBROT_DEFAULT_C_MAX, MANDELBROT_DEFAULT_C_MIN,
     MANDELBROT_DEFAULT_ITERATIONS,
 };
+pub use ray_march::{Camera, DistanceEstimator, RayMarchCalculator, ShadedImage};
 
-const MAX_POINTS: usize = 5000;
+pub const MAX_POINTS: usize = 5000;
 pub const MAX_DURATION: f64 = 200.0;
+/// block size of the first pass in progressive mode, halved with every pass down to 1
+pub const PROGRESSIVE_BLOCK_SIZE: u32 = 16;
+/// escape radius used when the coloring needs the angle of the final orbit value, a large
+/// radius makes the angle a good approximation of the external angle
+pub const ANGLE_ESCAPE_RADIUS: f64 = 100.0;
 
 pub struct FractalCalculator {
     fractal: Box<dyn Fractal>,
@@ -22,21 +68,43 @@ pub struct FractalCalculator {
     y_curr: u32,
     width: u32,
     height: u32,
+    block_size: u32,
+    start_block_size: u32,
     scale: Complex,
     offset: Complex,
+    tiling: Option<Tiling>,
+    tile_cache: Rc<RefCell<TileCache>>,
+    with_angles: bool,
     done: bool,
 }
 
 impl FractalCalculator {
     pub fn new(config: &Config, canvas_width: u32, canvas_height: u32) -> FractalCalculator {
-        let fractal: Box<dyn Fractal> = match config.active_config {
-            FractalType::Mandelbrot => Box::new(Mandelbrot::new(&config)),
-            FractalType::JuliaSet => Box::new(JuliaSet::new(&config)),
-        };
+        let fractal = create_fractal(config);
 
         let scale = fractal.get_scale(config, canvas_width, canvas_height);
         let offset = fractal.get_offset(config);
 
+        let start_block_size = match config.render_mode {
+            RenderMode::Scanline | RenderMode::Tiled => 1,
+            RenderMode::Progressive => PROGRESSIVE_BLOCK_SIZE,
+        };
+
+        let (scale, offset, tiling) = if config.render_mode == RenderMode::Tiled {
+            // snap the view to the grid of the tiling
+            let (scale, offset) = snap_to_tiles(&scale, &offset);
+            let tiling = Tiling::new(
+                fractal.as_ref(),
+                &scale,
+                &offset,
+                canvas_width,
+                canvas_height,
+            );
+            (scale, offset, Some(tiling))
+        } else {
+            (scale, offset, None)
+        };
+
         FractalCalculator {
             fractal,
             res: Points::default(),
@@ -44,13 +112,28 @@ impl FractalCalculator {
             y_curr: 0,
             width: canvas_width,
             height: canvas_height,
+            block_size: start_block_size,
+            start_block_size,
             scale,
             offset,
+            tiling,
+            tile_cache: Rc::new(RefCell::new(TileCache::new(0))),
+            with_angles: config.coloring.needs_angles(),
             done: false,
         }
     }
 
+    /// Use a tile cache that outlives the calculator, only relevant in tiled render mode
+    pub fn with_tile_cache(mut self, tile_cache: Rc<RefCell<TileCache>>) -> Self {
+        self.tile_cache = tile_cache;
+        self
+    }
+
     pub fn calculate(&mut self, stats: Option<&mut Stats>) -> &Points {
+        if self.tiling.is_some() {
+            return self.calculate_tiles(stats);
+        }
+
         let performance = web_sys::window()
             .expect("Window not found")
             .performance()
@@ -58,55 +141,78 @@ impl FractalCalculator {
 
         let start = performance.now();
 
-        self.res.x_start = self.x_curr;
-        self.res.y_start = self.y_curr;
+        // all points of a batch share the block size of the current pass
+        let block_size = self.block_size;
+        self.res.block_size = block_size;
         self.res.num_points = 0;
 
-        let mut x = self.x_curr;
-        let mut y = self.y_curr;
-
-        let mut points_done: Option<usize> = None;
         let mut last_check = 0usize;
         let mut iterations = 0usize;
 
         for count in 0..self.res.values.len() {
-            let calc = Complex::new(
-                f64::from(x).mul_add(self.scale.real(), self.offset.real()),
-                f64::from(y).mul_add(self.scale.imag(), self.offset.imag()),
-            );
-            let curr = self.fractal.iterate(&calc);
+            let (curr, last) = self
+                .fractal
+                .iterate_final(&self.point_at(self.x_curr, self.y_curr));
             self.res.values[count] = curr;
-
-            if x < self.width - 1 {
-                x += 1;
+            self.res.angles[count] = if self.with_angles {
+                final_angle(&last)
             } else {
-                x = 0;
-                y += 1;
-                if y >= self.height {
-                    self.done = true;
-                    points_done = Some(count + 1);
-                    break;
-                }
+                0.0
+            };
+            self.res.coords[count] = (self.x_curr, self.y_curr);
+            self.res.num_points = count + 1;
+            iterations += curr as usize;
+
+            if !self.next_pixel() {
+                self.done = true;
+                break;
+            }
+
+            if self.block_size != block_size {
+                break;
             }
 
-            iterations += curr as usize;
             if iterations - last_check > 100 {
                 last_check = iterations;
                 if performance.now() - start >= MAX_DURATION {
-                    points_done = Some(count + 1);
                     break;
                 }
             }
         }
 
-        if let Some(points) = points_done {
-            self.res.num_points = points;
-        } else {
-            self.res.num_points = self.res.values.len();
+        if let Some(stats) = stats {
+            stats.update(iterations, self.res.num_points, start);
+        }
+
+        &self.res
+    }
+
+    fn calculate_tiles(&mut self, stats: Option<&mut Stats>) -> &Points {
+        let performance = web_sys::window()
+            .expect("Window not found")
+            .performance()
+            .expect("performance should be available");
+
+        let start = performance.now();
+
+        self.res.block_size = 1;
+        self.res.num_points = 0;
+
+        let mut iterations = 0usize;
+        let tile_points = (TILE_SIZE * TILE_SIZE) as usize;
+        while !self.done && self.res.num_points + tile_points <= MAX_POINTS {
+            iterations += self.process_tile();
+            if performance.now() - start >= MAX_DURATION {
+                break;
+            }
         }
 
-        self.x_curr = x;
-        self.y_curr = y;
+        if self.done {
+            info!(
+                "FractalCalculator: tile cache holds {} tiles",
+                self.tile_cache.borrow().tile_count()
+            );
+        }
 
         if let Some(stats) = stats {
             stats.update(iterations, self.res.num_points, start);
@@ -115,31 +221,364 @@ impl FractalCalculator {
         &self.res
     }
 
+    /// Take the current tile from the cache or calculate it, append its pixels inside the canvas
+    /// to the results and move on to the next tile. Returns the iterations spent.
+    fn process_tile(&mut self) -> usize {
+        let tiling = self.tiling.as_mut().expect("tiling not initialized");
+        let key = tiling.tile_key();
+        let mut tile_cache = self.tile_cache.borrow_mut();
+        let iterations = if let Some((values, angles)) = tile_cache.get(&key) {
+            tiling.copy_to_points(&key, values, angles, self.width, self.height, &mut self.res);
+            0
+        } else {
+            let (values, angles, iterations) =
+                tiling.calculate_tile(&key, self.fractal.as_ref(), self.with_angles);
+            tiling.copy_to_points(
+                &key,
+                &values,
+                &angles,
+                self.width,
+                self.height,
+                &mut self.res,
+            );
+            tile_cache.insert(key, values, angles);
+            iterations
+        };
+
+        if !tiling.next_tile() {
+            self.done = true;
+        }
+        iterations
+    }
+
+    /// Calculate `count` pixels from pixel `start` on in one go without time slicing, used by
+    /// the web workers. Pixels are counted row by row, a span can start and end inside a row.
+    /// Returns the values, the angles (empty unless the coloring needs them) and the iterations.
+    pub fn calculate_span(&self, start: u32, count: u32) -> (Vec<u32>, Vec<f32>, usize) {
+        let end = (start + count).min(self.width * self.height);
+        let mut values = Vec::with_capacity(end.saturating_sub(start) as usize);
+        let mut angles = Vec::new();
+        let mut iterations = 0usize;
+        for idx in start..end {
+            let (value, last) = self
+                .fractal
+                .iterate_final(&self.point_at(idx % self.width, idx / self.width));
+            iterations += value as usize;
+            values.push(value);
+            if self.with_angles {
+                angles.push(final_angle(&last));
+            }
+        }
+        (values, angles, iterations)
+    }
+
     pub fn is_done(&self) -> bool {
         self.done
     }
+
+    #[inline]
+    fn point_at(&self, x: u32, y: u32) -> Complex {
+        Complex::new(
+            f64::from(x).mul_add(self.scale.real(), self.offset.real()),
+            f64::from(y).mul_add(self.scale.imag(), self.offset.imag()),
+        )
+    }
+
+    /// Advance to the next pixel that has not been calculated yet, moving on to the next
+    /// (finer) pass when the current one is complete. Returns false when all pixels are done.
+    fn next_pixel(&mut self) -> bool {
+        loop {
+            self.x_curr += self.block_size;
+            if self.x_curr >= self.width {
+                self.x_curr = 0;
+                self.y_curr += self.block_size;
+                if self.y_curr >= self.height {
+                    if self.block_size == 1 {
+                        return false;
+                    }
+                    self.block_size /= 2;
+                    self.y_curr = 0;
+                }
+            }
+
+            if !self.is_calculated(self.x_curr, self.y_curr) {
+                return true;
+            }
+        }
+    }
+
+    /// Pixels on the grid of a previous (coarser) pass have already been calculated.
+    #[inline]
+    fn is_calculated(&self, x: u32, y: u32) -> bool {
+        let prev_block_size = self.block_size * 2;
+        prev_block_size <= self.start_block_size
+            && x % prev_block_size == 0
+            && y % prev_block_size == 0
+    }
 }
 
-pub trait Fractal {
+/// The calculator for the active fractal and renderer
+pub enum Calculator {
+    /// boxed, the batch of points is kept inline
+    EscapeTime(Box<FractalCalculator>),
+    InverseIteration(IimCalculator),
+    Orbit(OrbitCalculator),
+    RayMarch(RayMarchCalculator),
+}
+
+impl Calculator {
+    pub fn new(
+        config: &Config,
+        canvas_width: u32,
+        canvas_height: u32,
+        tile_cache: Rc<RefCell<TileCache>>,
+    ) -> Self {
+        match config.active_config {
+            FractalType::JuliaSet
+                if config.julia_set_cfg.renderer == JuliaRenderer::InverseIteration =>
+            {
+                Calculator::InverseIteration(IimCalculator::new(
+                    config,
+                    canvas_width,
+                    canvas_height,
+                ))
+            }
+            FractalType::Attractor | FractalType::Ifs | FractalType::Flame => {
+                Calculator::Orbit(OrbitCalculator::new(config, canvas_width, canvas_height))
+            }
+            FractalType::Mandelbulb | FractalType::QuaternionJulia => {
+                Calculator::RayMarch(RayMarchCalculator::new(config, canvas_width, canvas_height))
+            }
+            _ => Calculator::EscapeTime(Box::new(
+                FractalCalculator::new(config, canvas_width, canvas_height)
+                    .with_tile_cache(tile_cache),
+            )),
+        }
+    }
+
+    /// Only escape time fractals can be calculated in bands of rows by the web workers, the
+    /// bands replace the order of the progressive and tiled render modes
+    pub fn supports_workers(config: &Config) -> bool {
+        config.render_mode == RenderMode::Scanline
+            && config.active_config.is_escape_time()
+            && !(config.active_config == FractalType::JuliaSet
+                && config.julia_set_cfg.renderer == JuliaRenderer::InverseIteration)
+    }
+
+    pub fn is_done(&self) -> bool {
+        match self {
+            Calculator::EscapeTime(calculator) => calculator.is_done(),
+            Calculator::InverseIteration(calculator) => calculator.is_done(),
+            Calculator::Orbit(calculator) => calculator.is_done(),
+            Calculator::RayMarch(calculator) => calculator.is_done(),
+        }
+    }
+}
+
+pub fn create_fractal(config: &Config) -> Box<dyn Fractal> {
+    match config.active_config {
+        FractalType::Mandelbrot => Box::new(Mandelbrot::new(config)),
+        FractalType::JuliaSet => Box::new(JuliaSet::new(config)),
+        FractalType::Lambda => Box::new(Lambda::new(config)),
+        FractalType::LambdaJulia => Box::new(LambdaJulia::new(config)),
+        FractalType::Hybrid => Box::new(Hybrid::new(config)),
+        fractal_type => panic!("{:?} is not an escape time fractal", fractal_type),
+    }
+}
+
+/// Position of the view in the fixed tiling of the complex plane used by the tile cache
+struct Tiling {
+    fractal: u64,
+    zoom: i32,
+    grid_size: f64,
+    /// grid coordinates of canvas pixel (0, 0)
+    origin: (i64, i64),
+    tile_min: (i64, i64),
+    tile_max: (i64, i64),
+    tile_curr: (i64, i64),
+}
+
+impl Tiling {
+    #[allow(clippy::cast_possible_truncation)]
+    fn new(
+        fractal: &dyn Fractal,
+        scale: &Complex,
+        offset: &Complex,
+        canvas_width: u32,
+        canvas_height: u32,
+    ) -> Self {
+        let zoom = zoom_level(scale.real());
+        let grid_size = grid_size(zoom);
+        let origin = (
+            (offset.real() / grid_size).round() as i64,
+            (offset.imag() / grid_size).round() as i64,
+        );
+
+        let tile_size = i64::from(TILE_SIZE);
+        let tile_min = (
+            origin.0.div_euclid(tile_size),
+            origin.1.div_euclid(tile_size),
+        );
+        let tile_max = (
+            (origin.0 + i64::from(canvas_width) - 1).div_euclid(tile_size),
+            (origin.1 + i64::from(canvas_height) - 1).div_euclid(tile_size),
+        );
+
+        let mut hasher = DefaultHasher::new();
+        fractal.cache_key().hash(&mut hasher);
+
+        Self {
+            fractal: hasher.finish(),
+            zoom,
+            grid_size,
+            origin,
+            tile_min,
+            tile_max,
+            tile_curr: tile_min,
+        }
+    }
+
+    #[inline]
+    fn tile_key(&self) -> TileKey {
+        TileKey {
+            fractal: self.fractal,
+            zoom: self.zoom,
+            x: self.tile_curr.0,
+            y: self.tile_curr.1,
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    #[inline]
+    fn point_at(&self, grid_x: i64, grid_y: i64) -> Complex {
+        Complex::new(
+            grid_x as f64 * self.grid_size,
+            grid_y as f64 * self.grid_size,
+        )
+    }
+
+    fn calculate_tile(
+        &self,
+        key: &TileKey,
+        fractal: &dyn Fractal,
+        with_angles: bool,
+    ) -> (Vec<u32>, Vec<f32>, usize) {
+        let tile_size = i64::from(TILE_SIZE);
+        let mut values = Vec::with_capacity((TILE_SIZE * TILE_SIZE) as usize);
+        let mut angles = Vec::new();
+        let mut iterations = 0usize;
+        for grid_y in key.y * tile_size..(key.y + 1) * tile_size {
+            for grid_x in key.x * tile_size..(key.x + 1) * tile_size {
+                let (value, last) = fractal.iterate_final(&self.point_at(grid_x, grid_y));
+                iterations += value as usize;
+                values.push(value);
+                if with_angles {
+                    angles.push(final_angle(&last));
+                }
+            }
+        }
+        (values, angles, iterations)
+    }
+
+    /// Append the pixels of a tile that lie inside the canvas to the results
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn copy_to_points(
+        &self,
+        key: &TileKey,
+        values: &[u32],
+        angles: &[f32],
+        canvas_width: u32,
+        canvas_height: u32,
+        res: &mut Points,
+    ) {
+        let tile_size = i64::from(TILE_SIZE);
+        let mut count = res.num_points;
+        for (idx, value) in values.iter().enumerate() {
+            let x = key.x * tile_size + idx as i64 % tile_size - self.origin.0;
+            let y = key.y * tile_size + idx as i64 / tile_size - self.origin.1;
+            if (0..i64::from(canvas_width)).contains(&x)
+                && (0..i64::from(canvas_height)).contains(&y)
+            {
+                res.coords[count] = (x as u32, y as u32);
+                res.values[count] = *value;
+                res.angles[count] = angles.get(idx).copied().unwrap_or(0.0);
+                count += 1;
+            }
+        }
+        res.num_points = count;
+    }
+
+    /// Move on to the next tile row by row, returns false when all tiles are done
+    fn next_tile(&mut self) -> bool {
+        self.tile_curr.0 += 1;
+        if self.tile_curr.0 > self.tile_max.0 {
+            self.tile_curr.0 = self.tile_min.0;
+            self.tile_curr.1 += 1;
+        }
+        self.tile_curr.1 <= self.tile_max.1
+    }
+}
+
+/// A single application of the formula of an escape time fractal, hybrid fractals compose the
+/// steps of several formulas
+pub trait Step {
+    /// The orbit value following `z` for the pixel `c`
+    fn step(&self, z: &Complex, c: &Complex) -> Complex;
+}
+
+pub trait Fractal: Step {
     fn get_scale(&self, config: &Config, canvas_width: u32, canvas_height: u32) -> Complex;
     fn get_offset(&self, config: &Config) -> Complex;
-    fn iterate(&self, calc: &Complex) -> u32;
+    /// Iterate until the orbit escapes, returns the iteration count and the final orbit value
+    fn iterate_final(&self, calc: &Complex) -> (u32, Complex);
+    fn iterate(&self, calc: &Complex) -> u32 {
+        self.iterate_final(calc).0
+    }
+    /// identifies the fractal and all parameters that affect the iteration values
+    fn cache_key(&self) -> String;
 }
 
+/// A batch of calculated pixels. The pixels need not be contiguous, each value is drawn as a
+/// block of `block_size` x `block_size` pixels with its top left corner at the matching coords.
+/// `angles` holds the argument of the final orbit value if the coloring needs it.
 pub struct Points {
-    pub x_start: u32,
-    pub y_start: u32,
+    pub block_size: u32,
     pub num_points: usize,
+    pub coords: [(u32, u32); MAX_POINTS],
     pub values: [u32; MAX_POINTS],
+    pub angles: [f32; MAX_POINTS],
+}
+
+impl Points {
+    /// Fill the batch with consecutive pixels of rows `width` pixels wide from pixel `start` on,
+    /// counted row by row,
+    /// `angles` is either empty or holds one angle per value
+    pub fn set_span(&mut self, start: u32, width: u32, values: &[u32], angles: &[f32]) {
+        assert!(values.len() <= MAX_POINTS);
+        self.block_size = 1;
+        self.num_points = values.len();
+        let (mut x, mut y) = (start % width, start / width);
+        for (idx, value) in values.iter().enumerate() {
+            self.coords[idx] = (x, y);
+            self.values[idx] = *value;
+            self.angles[idx] = angles.get(idx).copied().unwrap_or(0.0);
+            x += 1;
+            if x >= width {
+                x = 0;
+                y += 1;
+            }
+        }
+    }
 }
 
 impl Default for Points {
     fn default() -> Self {
         Self {
-            x_start: 0,
-            y_start: 0,
+            block_size: 1,
             num_points: 0,
+            coords: [(0, 0); MAX_POINTS],
             values: [0; MAX_POINTS],
+            angles: [0.0; MAX_POINTS],
         }
     }
 }
@@ -148,6 +587,116 @@ impl Default for Points {
 pub enum FractalType {
     Mandelbrot,
     JuliaSet,
+    Attractor,
+    Ifs,
+    Flame,
+    Mandelbulb,
+    QuaternionJulia,
+    Lambda,
+    LambdaJulia,
+    Hybrid,
+}
+
+impl FractalType {
+    /// Escape time fractals color every pixel by the iterations of its own orbit
+    pub fn is_escape_time(self) -> bool {
+        matches!(
+            self,
+            FractalType::Mandelbrot
+                | FractalType::JuliaSet
+                | FractalType::Lambda
+                | FractalType::LambdaJulia
+                | FractalType::Hybrid
+        )
+    }
+
+    /// 3D fractals are ray marched, mouse drag on the canvas orbits their camera
+    pub fn is_3d(self) -> bool {
+        matches!(self, FractalType::Mandelbulb | FractalType::QuaternionJulia)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum RenderMode {
+    /// calculate the image row by row from the top
+    #[default]
+    Scanline,
+    /// calculate every 16th pixel first and refine in passes of 8, 4, 2 and 1
+    Progressive,
+    /// calculate the image in tiles of a fixed grid and reuse cached tiles
+    Tiled,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum ColoringMode {
+    /// color by the iteration count only
+    #[default]
+    Iterations,
+    /// shade the iteration bands by the sign of the imaginary part of the final orbit value
+    BinaryDecomposition,
+    /// draw field lines along the angle of the final orbit value across the iteration bands
+    FieldLines,
+    /// spread the palette over the distribution of the iteration counts of the whole image,
+    /// the image is recolored once it is complete
+    Histogram,
+}
+
+impl ColoringMode {
+    pub fn needs_angles(self) -> bool {
+        matches!(
+            self,
+            ColoringMode::BinaryDecomposition | ColoringMode::FieldLines
+        )
+    }
+}
+
+/// The argument of the final orbit value as stored in Points
+#[allow(clippy::cast_possible_truncation)]
+#[inline]
+pub fn final_angle(last: &Complex) -> f32 {
+    last.arg() as f32
+}
+
+/// The pixel size `scale` and top left point `offset` of a view snapped to the grid of the tile
+/// cache, the view tiled render mode renders
+pub fn snap_to_tiles(scale: &Complex, offset: &Complex) -> (Complex, Complex) {
+    let grid_size = grid_size(zoom_level(scale.real()));
+    (
+        Complex::new(grid_size, grid_size),
+        Complex::new(
+            (offset.real() / grid_size).round() * grid_size,
+            (offset.imag() / grid_size).round() * grid_size,
+        ),
+    )
+}
+
+/// The points at the corners (x_min, y_min, x_max, y_max) of a canvas selection in the view from
+/// `min` to `max`, mapped through the view as rendered in `render_mode` so repeated zooms do not
+/// drift off the selected area in tiled mode
+pub fn select_area(
+    min: &Complex,
+    max: &Complex,
+    canvas_width: u32,
+    canvas_height: u32,
+    render_mode: RenderMode,
+    coords: (u32, u32, u32, u32),
+) -> (Complex, Complex) {
+    let scale = Complex::new(
+        (max.real() - min.real()) / f64::from(canvas_width),
+        (max.imag() - min.imag()) / f64::from(canvas_height),
+    );
+    let (scale, offset) = if render_mode == RenderMode::Tiled {
+        snap_to_tiles(&scale, min)
+    } else {
+        (scale, *min)
+    };
+    let point_at = |x: u32, y: u32| {
+        Complex::new(
+            offset.real() + scale.real() * f64::from(x),
+            offset.imag() + scale.imag() * f64::from(y),
+        )
+    };
+    (point_at(coords.0, coords.1), point_at(coords.2, coords.3))
 }
 
 // TODO: adapt to power in mandelbrot equation
@@ -181,3 +730,192 @@ pub fn find_escape_radius(c_norm: f64) -> f64 {
         2.0
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{
+        select_area, snap_to_tiles, Fractal, FractalCalculator, Points, RenderMode, Step, Tiling,
+        MAX_POINTS, PROGRESSIVE_BLOCK_SIZE,
+    };
+    use crate::components::root::Config;
+    use crate::work::complex::Complex;
+    use crate::work::tile_cache::{TileCache, TILE_SIZE};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct Dummy;
+
+    impl Step for Dummy {
+        fn step(&self, z: &Complex, _c: &Complex) -> Complex {
+            *z
+        }
+    }
+
+    impl Fractal for Dummy {
+        fn get_scale(&self, _config: &Config, _canvas_width: u32, _canvas_height: u32) -> Complex {
+            Complex::new(1.0, 1.0)
+        }
+        fn get_offset(&self, _config: &Config) -> Complex {
+            Complex::new(0.0, 0.0)
+        }
+        fn iterate_final(&self, calc: &Complex) -> (u32, Complex) {
+            (1, *calc)
+        }
+        fn cache_key(&self) -> String {
+            "dummy".to_owned()
+        }
+    }
+
+    fn pixel_counts(width: u32, height: u32, start_block_size: u32) -> Vec<u32> {
+        let mut calculator = FractalCalculator {
+            fractal: Box::new(Dummy),
+            res: Points::default(),
+            x_curr: 0,
+            y_curr: 0,
+            width,
+            height,
+            block_size: start_block_size,
+            start_block_size,
+            scale: Complex::new(1.0, 1.0),
+            offset: Complex::new(0.0, 0.0),
+            tiling: None,
+            tile_cache: Rc::new(RefCell::new(TileCache::new(0))),
+            with_angles: false,
+            done: false,
+        };
+
+        let mut counts = vec![0u32; (width * height) as usize];
+        loop {
+            counts[(calculator.y_curr * width + calculator.x_curr) as usize] += 1;
+            if !calculator.next_pixel() {
+                break;
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn test_points_set_span() {
+        let mut points = Points::default();
+        points.set_span(12, 4, &[1, 2, 3, 4, 5, 6], &[]);
+        assert_eq!(points.num_points, 6);
+        assert_eq!(points.block_size, 1);
+        assert_eq!(points.coords[0], (0, 3));
+        assert_eq!(points.coords[3], (3, 3));
+        assert_eq!(points.coords[4], (0, 4));
+        assert_eq!(points.values[5], 6);
+        assert_eq!(points.angles[5], 0.0);
+
+        points.set_span(0, 4, &[1, 2], &[0.5, -0.5]);
+        assert_eq!(points.num_points, 2);
+        assert_eq!(points.angles[1], -0.5);
+    }
+
+    #[test]
+    fn test_spans_of_wide_rows() {
+        // rows wider than a batch are split across several spans
+        let (width, height) = (MAX_POINTS as u32 + 1000, 2);
+        let calculator = FractalCalculator::new(&Config::std_cfg(), width, height);
+        let mut points = Points::default();
+        let mut counts = vec![0u32; (width * height) as usize];
+        for start in (0..width * height).step_by(MAX_POINTS) {
+            let (values, angles, _) = calculator.calculate_span(start, MAX_POINTS as u32);
+            points.set_span(start, width, &values, &angles);
+            for (x, y) in &points.coords[0..points.num_points] {
+                counts[(y * width + x) as usize] += 1;
+            }
+        }
+        assert!(counts.iter().all(|count| *count == 1));
+
+        points.set_span(MAX_POINTS as u32, width, &[1; 1001], &[]);
+        assert_eq!(points.coords[0], (MAX_POINTS as u32, 0));
+        assert_eq!(points.coords[999], (width - 1, 0));
+        assert_eq!(points.coords[1000], (0, 1));
+    }
+
+    #[test]
+    fn test_scanline_visits_every_pixel_once() {
+        assert!(pixel_counts(37, 19, 1).iter().all(|count| *count == 1));
+    }
+
+    #[test]
+    fn test_progressive_visits_every_pixel_once() {
+        assert!(pixel_counts(37, 19, PROGRESSIVE_BLOCK_SIZE)
+            .iter()
+            .all(|count| *count == 1));
+        assert!(pixel_counts(1024, 787, PROGRESSIVE_BLOCK_SIZE)
+            .iter()
+            .all(|count| *count == 1));
+    }
+
+    #[test]
+    fn test_tiling_covers_canvas() {
+        let (width, height) = (100u32, 70u32);
+        let scale = Complex::new(0.01, 0.01);
+        let offset = Complex::new(-0.505, -0.3);
+        let mut tiling = Tiling::new(&Dummy, &scale, &offset, width, height);
+        assert!((tiling.grid_size - 0.01).abs() < 0.0003);
+
+        let mut counts = vec![0u32; (width * height) as usize];
+        loop {
+            let key = tiling.tile_key();
+            let mut points = Points::default();
+            let (values, angles, _) = tiling.calculate_tile(&key, &Dummy, true);
+            assert_eq!(values.len(), (TILE_SIZE * TILE_SIZE) as usize);
+            assert_eq!(angles.len(), values.len());
+            tiling.copy_to_points(&key, &values, &angles, width, height, &mut points);
+            for (x, y) in points.coords[0..points.num_points].iter() {
+                counts[(y * width + x) as usize] += 1;
+            }
+            if !tiling.next_tile() {
+                break;
+            }
+        }
+        assert!(counts.iter().all(|count| *count == 1));
+    }
+
+    #[test]
+    fn test_select_area() {
+        let (width, height) = (100u32, 70u32);
+        let (min, max) = (Complex::new(-2.0, -1.2), Complex::new(1.0, 0.9));
+        let (c_min, c_max) = select_area(
+            &min,
+            &max,
+            width,
+            height,
+            RenderMode::Scanline,
+            (10, 7, 60, 42),
+        );
+        assert!((c_min.real() + 1.7).abs() < 1e-12 && (c_min.imag() + 0.99).abs() < 1e-12);
+        assert!((c_max.real() + 0.2).abs() < 1e-12 && (c_max.imag() - 0.06).abs() < 1e-12);
+
+        // tiled mode selects in the snapped view, selecting all of it keeps the view in place
+        let (min, max) = select_area(
+            &min,
+            &max,
+            width,
+            height,
+            RenderMode::Tiled,
+            (0, 0, 100, 70),
+        );
+        let scale = Complex::new(
+            (max.real() - min.real()) / f64::from(width),
+            (max.imag() - min.imag()) / f64::from(height),
+        );
+        let (snapped_scale, snapped_offset) = snap_to_tiles(&scale, &min);
+        assert!((snapped_scale.real() - scale.real()).abs() < 1e-12);
+        assert!((snapped_scale.imag() - scale.imag()).abs() < 1e-12);
+        assert!((snapped_offset.real() - min.real()).abs() < 1e-12);
+        assert!((snapped_offset.imag() - min.imag()).abs() < 1e-12);
+        let (again_min, again_max) = select_area(
+            &min,
+            &max,
+            width,
+            height,
+            RenderMode::Tiled,
+            (0, 0, 100, 70),
+        );
+        assert!((again_min.real() - min.real()).abs() < 1e-12);
+        assert!((again_max.imag() - max.imag()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,151 @@
+use super::OrbitMap;
+use crate::work::complex::Complex;
+use serde::{Deserialize, Serialize};
+
+pub const ATTRACTOR_DEFAULT_POINTS: u32 = 20_000_000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AttractorType {
+    /// x' = sin(a y) + c cos(a x), y' = sin(b x) + d cos(b y)
+    Clifford,
+    /// x' = sin(a y) - cos(b x), y' = sin(c x) - cos(d y)
+    DeJong,
+    /// x' = y - sign(x) sqrt(|b x - c|), y' = a - x
+    Hopalong,
+}
+
+impl AttractorType {
+    #[inline]
+    pub fn step(self, params: &[f64; 4], x: f64, y: f64) -> (f64, f64) {
+        let [a, b, c, d] = *params;
+        match self {
+            AttractorType::Clifford => (
+                (a * y).sin() + c * (a * x).cos(),
+                (b * x).sin() + d * (b * y).cos(),
+            ),
+            AttractorType::DeJong => ((a * y).sin() - (b * x).cos(), (c * x).sin() - (d * y).cos()),
+            AttractorType::Hopalong => (y - x.signum() * (b * x - c).abs().sqrt(), a - x),
+        }
+    }
+
+    /// Parameters a - d and the area (x_min, x_max) showing a typical attractor of this type
+    pub fn defaults(self) -> ([f64; 4], Complex, Complex) {
+        match self {
+            AttractorType::Clifford => (
+                [-1.4, 1.6, 1.0, 0.7],
+                Complex::new(-1.6, -1.5),
+                Complex::new(2.1, 1.7),
+            ),
+            AttractorType::DeJong => (
+                [1.4, -2.3, 2.4, -2.1],
+                Complex::new(-2.1, -2.1),
+                Complex::new(2.1, 2.1),
+            ),
+            AttractorType::Hopalong => (
+                [-55.0, -1.0, -42.0, 0.0],
+                Complex::new(-220.0, -225.0),
+                Complex::new(170.0, 165.0),
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct AttractorCfg {
+    pub attractor: AttractorType,
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub x_max: Complex,
+    pub x_min: Complex,
+    /// the number of points to plot before the attractor is complete
+    pub max_points: u32,
+    pub color_cfg_name: Option<String>,
+}
+
+impl AttractorCfg {
+    pub fn params(&self) -> [f64; 4] {
+        [self.a, self.b, self.c, self.d]
+    }
+}
+
+impl Default for AttractorCfg {
+    fn default() -> Self {
+        let attractor = AttractorType::Clifford;
+        let ([a, b, c, d], x_min, x_max) = attractor.defaults();
+        Self {
+            attractor,
+            a,
+            b,
+            c,
+            d,
+            x_max,
+            x_min,
+            max_points: ATTRACTOR_DEFAULT_POINTS,
+            color_cfg_name: None,
+        }
+    }
+}
+
+/// The map of a strange attractor with fixed parameters
+pub struct AttractorMap {
+    attractor: AttractorType,
+    params: [f64; 4],
+}
+
+impl AttractorMap {
+    pub fn new(cfg: &AttractorCfg) -> Self {
+        info!(
+            "creating attractor with: {:?}, params: {:?}, x_max: {}, x_min: {}",
+            cfg.attractor,
+            cfg.params(),
+            cfg.x_max,
+            cfg.x_min
+        );
+        Self {
+            attractor: cfg.attractor,
+            params: cfg.params(),
+        }
+    }
+}
+
+impl OrbitMap for AttractorMap {
+    #[inline]
+    fn next(&mut self, x: f64, y: f64) -> (f64, f64) {
+        self.attractor.step(&self.params, x, y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AttractorType;
+
+    #[test]
+    fn test_defaults_stay_in_view() {
+        for attractor in [
+            AttractorType::Clifford,
+            AttractorType::DeJong,
+            AttractorType::Hopalong,
+        ] {
+            let (params, x_min, x_max) = attractor.defaults();
+            let (mut x, mut y) = (0.1, 0.1);
+            for idx in 0..100_000 {
+                let next = attractor.step(&params, x, y);
+                x = next.0;
+                y = next.1;
+                if idx >= 100 {
+                    assert!(x >= x_min.real() && x <= x_max.real(), "{:?}", attractor);
+                    assert!(y >= x_min.imag() && y <= x_max.imag(), "{:?}", attractor);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_clifford_step() {
+        let (x, y) = AttractorType::Clifford.step(&[1.0, 1.0, 1.0, 1.0], 0.0, 0.0);
+        assert!((x - 1.0).abs() < 1e-12);
+        assert!((y - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,189 @@
+use super::{
+    create_fractal, FractalType, JULIA_DEFAULT_X_MAX, JULIA_DEFAULT_X_MIN,
+    MANDELBROT_DEFAULT_C_MAX, MANDELBROT_DEFAULT_C_MIN,
+};
+use crate::components::root::Config;
+use crate::work::complex::Complex;
+use serde::{Deserialize, Serialize};
+
+pub const AUTO_ITER_DEFAULT_FACTOR: f64 = 4.0;
+pub const AUTO_ITER_DEFAULT_MIN: u32 = 100;
+pub const AUTO_ITER_DEFAULT_MAX: u32 = 100_000;
+
+const ITERATIONS_PER_FACTOR: f64 = 100.0;
+// the view is sampled on a grid of SAMPLE_GRID x SAMPLE_GRID points
+const SAMPLE_GRID: u32 = 16;
+const SAMPLE_PERCENTILE: f64 = 0.95;
+// iterations of all samples together, the sampling runs on the main thread before rendering
+const SAMPLE_ITERATION_BUDGET: u32 = 10_000_000;
+
+/// Choose max_iterations from the zoom depth of the view instead of using a fixed value
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct AutoIterations {
+    pub enabled: bool,
+    pub factor: f64,
+    pub min: u32,
+    pub max: u32,
+    /// sample the view before rendering to refine the estimate
+    pub sample: bool,
+}
+
+impl AutoIterations {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min > self.max {
+            Err(format!(
+                "invalid auto iterations range, min {} is above max {}",
+                self.min, self.max
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// iterations = factor * 100 * (1 + log2(zoom)) where zoom is the default view width
+    /// divided by the view width, clamped to min..=max
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn estimate(&self, default_width: f64, view_width: f64) -> u32 {
+        let zoom = (default_width / view_width.abs()).max(1.0);
+        let iterations = self.factor * ITERATIONS_PER_FACTOR * (1.0 + zoom.log2());
+        self.clamp(iterations.max(0.0).min(f64::from(u32::MAX)) as u32)
+    }
+
+    /// Raise the estimate to twice the escape count of most points that escaped in a sample
+    /// of the view calculated with `sample_max` iterations
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn refine(&self, estimate: u32, samples: &[u32], sample_max: u32) -> u32 {
+        let mut escaped = samples
+            .iter()
+            .filter(|value| **value <= sample_max)
+            .copied()
+            .collect::<Vec<u32>>();
+        if escaped.is_empty() {
+            return estimate;
+        }
+        escaped.sort_unstable();
+        let idx = ((escaped.len() - 1) as f64 * SAMPLE_PERCENTILE).round() as usize;
+        self.clamp(estimate.max(escaped[idx].saturating_mul(2)))
+    }
+
+    #[inline]
+    fn clamp(&self, iterations: u32) -> u32 {
+        iterations.max(self.min).min(self.max)
+    }
+}
+
+impl Default for AutoIterations {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            factor: AUTO_ITER_DEFAULT_FACTOR,
+            min: AUTO_ITER_DEFAULT_MIN,
+            max: AUTO_ITER_DEFAULT_MAX,
+            sample: false,
+        }
+    }
+}
+
+/// Return a copy of the config with max_iterations of the active fractal replaced by the
+/// automatically chosen value, or an unchanged copy if auto iterations are disabled
+pub fn resolve_auto_iterations(config: &Config, canvas_width: u32, canvas_height: u32) -> Config {
+    let (auto_iterations, default_width, view_width) = match config.active_config {
+        FractalType::Mandelbrot => (
+            &config.mandelbrot_cfg.auto_iterations,
+            MANDELBROT_DEFAULT_C_MAX.0 - MANDELBROT_DEFAULT_C_MIN.0,
+            config.mandelbrot_cfg.c_max.real() - config.mandelbrot_cfg.c_min.real(),
+        ),
+        FractalType::JuliaSet => (
+            &config.julia_set_cfg.auto_iterations,
+            JULIA_DEFAULT_X_MAX.0 - JULIA_DEFAULT_X_MIN.0,
+            config.julia_set_cfg.x_max.real() - config.julia_set_cfg.x_min.real(),
+        ),
+        _ => return config.clone(),
+    };
+
+    let mut res = config.clone();
+    if !auto_iterations.enabled {
+        return res;
+    }
+
+    let mut iterations = auto_iterations.estimate(default_width, view_width);
+    if auto_iterations.sample {
+        let sample_max = auto_iterations
+            .max
+            .min(SAMPLE_ITERATION_BUDGET / (SAMPLE_GRID * SAMPLE_GRID));
+        res.set_max_iterations(sample_max);
+        let fractal = create_fractal(&res);
+        let scale = fractal.get_scale(&res, canvas_width, canvas_height);
+        let offset = fractal.get_offset(&res);
+        let mut samples = Vec::with_capacity((SAMPLE_GRID * SAMPLE_GRID) as usize);
+        for row in 0..SAMPLE_GRID {
+            for col in 0..SAMPLE_GRID {
+                // sample at the centers of the grid cells
+                let x = f64::from(canvas_width) * (f64::from(col) + 0.5) / f64::from(SAMPLE_GRID);
+                let y = f64::from(canvas_height) * (f64::from(row) + 0.5) / f64::from(SAMPLE_GRID);
+                samples.push(fractal.iterate(&Complex::new(
+                    x.mul_add(scale.real(), offset.real()),
+                    y.mul_add(scale.imag(), offset.imag()),
+                )));
+            }
+        }
+        iterations = auto_iterations.refine(iterations, &samples, sample_max);
+    }
+
+    info!("resolve_auto_iterations: using {} iterations", iterations);
+    res.set_max_iterations(iterations);
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::AutoIterations;
+
+    #[test]
+    fn test_estimate() {
+        let auto_iterations = AutoIterations::default();
+        assert_eq!(auto_iterations.estimate(2.0, 2.0), 400);
+        assert_eq!(auto_iterations.estimate(2.0, 4.0), 400);
+        assert_eq!(auto_iterations.estimate(2.0, 2.0 / 1024.0), 4400);
+        assert_eq!(auto_iterations.estimate(2.0, 1e-300), 100_000);
+
+        let auto_iterations = AutoIterations {
+            factor: 0.1,
+            ..AutoIterations::default()
+        };
+        assert_eq!(auto_iterations.estimate(2.0, 2.0), 100);
+    }
+
+    #[test]
+    fn test_refine() {
+        let auto_iterations = AutoIterations::default();
+        // no escaped samples - keep estimate
+        assert_eq!(auto_iterations.refine(400, &[1001, 1001], 1000), 400);
+        // samples escape early - keep estimate
+        assert_eq!(auto_iterations.refine(400, &[3, 10, 1001], 1000), 400);
+        // samples escape late - raise
+        assert_eq!(auto_iterations.refine(400, &[300, 900, 1001], 1000), 1800);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(AutoIterations::default().validate().is_ok());
+        let inverted = AutoIterations {
+            min: 5000,
+            max: 1000,
+            ..AutoIterations::default()
+        };
+        assert!(inverted.validate().is_err());
+        let single = AutoIterations {
+            min: 1000,
+            max: 1000,
+            ..AutoIterations::default()
+        };
+        assert!(single.validate().is_ok());
+        assert_eq!(single.estimate(2.0, 1e-10), 1000);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::work::complex::Complex;
+use serde::{Deserialize, Serialize};
+
+/// The escape radius of the Mandelbrot set, orbits leaving the disc of radius 2 diverge
+pub const BAILOUT_DEFAULT_RADIUS: f64 = 2.0;
+/// Orbits that never pass the test of their shape, like real orbits for the imaginary part
+/// test, escape once their length passes this radius
+const BAILOUT_FALLBACK_RADIUS: f64 = 1e10;
+
+/// The test deciding that an orbit has escaped
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum BailoutShape {
+    /// |z| >= R
+    #[default]
+    Circle,
+    /// max(|Re z|, |Im z|) >= R
+    Square,
+    /// |Re z| >= R
+    Real,
+    /// |Im z| >= R
+    Imag,
+    /// |Re z| + |Im z| >= R
+    Manhattan,
+    /// |Re z Im z| >= R^2
+    Product,
+}
+
+impl BailoutShape {
+    pub const ALL: [BailoutShape; 6] = [
+        BailoutShape::Circle,
+        BailoutShape::Square,
+        BailoutShape::Real,
+        BailoutShape::Imag,
+        BailoutShape::Manhattan,
+        BailoutShape::Product,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BailoutShape::Circle => "Circle",
+            BailoutShape::Square => "Square",
+            BailoutShape::Real => "Real Part",
+            BailoutShape::Imag => "Imaginary Part",
+            BailoutShape::Manhattan => "Manhattan",
+            BailoutShape::Product => "Product",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bailout {
+    pub shape: BailoutShape,
+    pub radius: f64,
+}
+
+impl Bailout {
+    /// The same shape with the radius raised to at least `radius`
+    pub fn at_least(self, radius: f64) -> Self {
+        Self {
+            shape: self.shape,
+            radius: self.radius.max(radius),
+        }
+    }
+
+    #[inline]
+    pub fn escaped(&self, z: &Complex) -> bool {
+        let (real, imag) = (z.real().abs(), z.imag().abs());
+        let escaped = match self.shape {
+            BailoutShape::Circle => z.square_length() >= self.radius * self.radius,
+            BailoutShape::Square => real.max(imag) >= self.radius,
+            BailoutShape::Real => real >= self.radius,
+            BailoutShape::Imag => imag >= self.radius,
+            BailoutShape::Manhattan => real + imag >= self.radius,
+            BailoutShape::Product => real * imag >= self.radius * self.radius,
+        };
+        escaped || z.square_length() >= BAILOUT_FALLBACK_RADIUS * BAILOUT_FALLBACK_RADIUS
+    }
+}
+
+impl Default for Bailout {
+    fn default() -> Self {
+        Self {
+            shape: BailoutShape::default(),
+            radius: BAILOUT_DEFAULT_RADIUS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Bailout, BailoutShape};
+    use crate::work::complex::Complex;
+
+    #[test]
+    fn test_shapes() {
+        let bailout = |shape| Bailout { shape, radius: 2.0 };
+        let z = Complex::new(1.2, -1.2);
+        assert!(!bailout(BailoutShape::Circle).escaped(&z));
+        assert!(!bailout(BailoutShape::Square).escaped(&z));
+        assert!(!bailout(BailoutShape::Real).escaped(&z));
+        assert!(!bailout(BailoutShape::Imag).escaped(&z));
+        assert!(bailout(BailoutShape::Manhattan).escaped(&z));
+        assert!(!bailout(BailoutShape::Product).escaped(&z));
+
+        let z = Complex::new(0.5, 2.5);
+        assert!(bailout(BailoutShape::Circle).escaped(&z));
+        assert!(bailout(BailoutShape::Square).escaped(&z));
+        assert!(!bailout(BailoutShape::Real).escaped(&z));
+        assert!(bailout(BailoutShape::Imag).escaped(&z));
+        assert!(!bailout(BailoutShape::Product).escaped(&z));
+
+        // real orbits never pass the imaginary part test, they escape at the fallback radius
+        assert!(!bailout(BailoutShape::Imag).escaped(&Complex::new(1e9, 0.0)));
+        assert!(bailout(BailoutShape::Imag).escaped(&Complex::new(1e11, 0.0)));
+    }
+
+    #[test]
+    fn test_at_least() {
+        // only the angle colorings raise the configured radius
+        let bailout = Bailout {
+            shape: BailoutShape::Square,
+            radius: 1.0,
+        };
+        assert_eq!(bailout.at_least(100.0).radius, 100.0);
+        assert_eq!(bailout.at_least(100.0).shape, BailoutShape::Square);
+        let bailout = Bailout {
+            shape: BailoutShape::Square,
+            radius: 200.0,
+        };
+        assert_eq!(bailout.at_least(100.0), bailout);
+    }
+}
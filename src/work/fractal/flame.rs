@@ -0,0 +1,307 @@
+use super::ifs::cumulative_probabilities;
+use super::{AffineTransform, OrbitMap, XorShiftRng};
+use crate::work::complex::Complex;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+pub const FLAME_DEFAULT_POINTS: u32 = 20_000_000;
+pub const FLAME_DEFAULT_GAMMA: f64 = 2.2;
+
+const RNG_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The nonlinear functions of "The Fractal Flame Algorithm" by Scott Draves and Erik Reckase
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Variation {
+    Linear,
+    Sinusoidal,
+    Spherical,
+    Swirl,
+    Horseshoe,
+    Polar,
+    Handkerchief,
+    Heart,
+    Disc,
+    Spiral,
+    Hyperbolic,
+    Diamond,
+}
+
+impl Variation {
+    pub const ALL: [Variation; 12] = [
+        Variation::Linear,
+        Variation::Sinusoidal,
+        Variation::Spherical,
+        Variation::Swirl,
+        Variation::Horseshoe,
+        Variation::Polar,
+        Variation::Handkerchief,
+        Variation::Heart,
+        Variation::Disc,
+        Variation::Spiral,
+        Variation::Hyperbolic,
+        Variation::Diamond,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Variation::Linear => "Linear",
+            Variation::Sinusoidal => "Sinusoidal",
+            Variation::Spherical => "Spherical",
+            Variation::Swirl => "Swirl",
+            Variation::Horseshoe => "Horseshoe",
+            Variation::Polar => "Polar",
+            Variation::Handkerchief => "Handkerchief",
+            Variation::Heart => "Heart",
+            Variation::Disc => "Disc",
+            Variation::Spiral => "Spiral",
+            Variation::Hyperbolic => "Hyperbolic",
+            Variation::Diamond => "Diamond",
+        }
+    }
+
+    #[inline]
+    pub fn apply(self, x: f64, y: f64) -> (f64, f64) {
+        let r2 = x.mul_add(x, y * y).max(f64::EPSILON);
+        let r = r2.sqrt();
+        // the angle is measured from the y axis as in the paper
+        let theta = x.atan2(y);
+        match self {
+            Variation::Linear => (x, y),
+            Variation::Sinusoidal => (x.sin(), y.sin()),
+            Variation::Spherical => (x / r2, y / r2),
+            Variation::Swirl => {
+                let (sin, cos) = r2.sin_cos();
+                (x.mul_add(sin, -y * cos), x.mul_add(cos, y * sin))
+            }
+            Variation::Horseshoe => ((x - y) * (x + y) / r, 2.0 * x * y / r),
+            Variation::Polar => (theta / PI, r - 1.0),
+            Variation::Handkerchief => (r * (theta + r).sin(), r * (theta - r).cos()),
+            Variation::Heart => (r * (theta * r).sin(), -r * (theta * r).cos()),
+            Variation::Disc => {
+                let (sin, cos) = (PI * r).sin_cos();
+                (theta / PI * sin, theta / PI * cos)
+            }
+            Variation::Spiral => ((theta.cos() + r.sin()) / r, (theta.sin() - r.cos()) / r),
+            Variation::Hyperbolic => (theta.sin() / r, r * theta.cos()),
+            Variation::Diamond => (theta.sin() * r.cos(), theta.cos() * r.sin()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct WeightedVariation {
+    pub variation: Variation,
+    pub weight: f64,
+}
+
+impl WeightedVariation {
+    pub fn new(variation: Variation, weight: f64) -> Self {
+        Self { variation, weight }
+    }
+}
+
+/// A transform of a flame: the affine part is applied first, followed by the weighted sum of
+/// the variations. The probability of the affine part is the weight of the transform in the
+/// chaos game.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct FlameTransform {
+    pub affine: AffineTransform,
+    pub variations: Vec<WeightedVariation>,
+    /// the palette index of the transform in 0.0..=1.0
+    pub color: f64,
+}
+
+impl FlameTransform {
+    pub fn new(affine: AffineTransform, variations: Vec<WeightedVariation>, color: f64) -> Self {
+        Self {
+            affine,
+            variations,
+            color,
+        }
+    }
+
+    #[inline]
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let (x, y) = self.affine.apply(x, y);
+        self.variations
+            .iter()
+            .fold((0.0, 0.0), |(sum_x, sum_y), variation| {
+                let (var_x, var_y) = variation.variation.apply(x, y);
+                (
+                    variation.weight.mul_add(var_x, sum_x),
+                    variation.weight.mul_add(var_y, sum_y),
+                )
+            })
+    }
+}
+
+impl Default for FlameTransform {
+    fn default() -> Self {
+        FlameTransform::new(
+            AffineTransform::default(),
+            vec![WeightedVariation::new(Variation::Linear, 1.0)],
+            0.5,
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct FlameCfg {
+    pub transforms: Vec<FlameTransform>,
+    /// gamma of the log density tone mapping
+    pub gamma: f64,
+    pub x_max: Complex,
+    pub x_min: Complex,
+    /// the number of points to plot before the flame is complete
+    pub max_points: u32,
+    pub color_cfg_name: Option<String>,
+}
+
+impl Default for FlameCfg {
+    fn default() -> Self {
+        Self {
+            transforms: vec![
+                FlameTransform::new(
+                    AffineTransform::new([0.5, 0.0, 0.0, 0.5, -0.5, -0.5], 1.0),
+                    vec![
+                        WeightedVariation::new(Variation::Linear, 0.5),
+                        WeightedVariation::new(Variation::Spherical, 0.5),
+                    ],
+                    0.0,
+                ),
+                FlameTransform::new(
+                    AffineTransform::new([0.5, 0.0, 0.0, 0.5, 0.5, -0.5], 1.0),
+                    vec![WeightedVariation::new(Variation::Swirl, 1.0)],
+                    0.5,
+                ),
+                FlameTransform::new(
+                    AffineTransform::new([0.5, 0.0, 0.0, 0.5, 0.0, 0.5], 1.0),
+                    vec![WeightedVariation::new(Variation::Sinusoidal, 1.0)],
+                    1.0,
+                ),
+            ],
+            gamma: FLAME_DEFAULT_GAMMA,
+            x_max: Complex::new(1.4, 1.0),
+            x_min: Complex::new(-1.6, -1.8),
+            max_points: FLAME_DEFAULT_POINTS,
+            color_cfg_name: None,
+        }
+    }
+}
+
+/// Plays the chaos game with the transforms of a flame, the color index of the orbit moves
+/// halfway towards the color of every transform applied
+pub struct FlameMap {
+    transforms: Vec<FlameTransform>,
+    cumulative: Vec<f64>,
+    rng: XorShiftRng,
+    color: f64,
+}
+
+impl FlameMap {
+    pub fn new(cfg: &FlameCfg) -> Self {
+        info!(
+            "creating flame with {} transforms, gamma: {}, x_max: {}, x_min: {}",
+            cfg.transforms.len(),
+            cfg.gamma,
+            cfg.x_max,
+            cfg.x_min
+        );
+
+        let transforms = if cfg.transforms.is_empty() {
+            vec![FlameTransform::default()]
+        } else {
+            cfg.transforms.clone()
+        };
+
+        Self {
+            cumulative: cumulative_probabilities(
+                &transforms
+                    .iter()
+                    .map(|transform| transform.affine.probability)
+                    .collect::<Vec<f64>>(),
+            ),
+            transforms,
+            rng: XorShiftRng::new(RNG_SEED),
+            color: 0.5,
+        }
+    }
+}
+
+impl OrbitMap for FlameMap {
+    #[inline]
+    fn next(&mut self, x: f64, y: f64) -> (f64, f64) {
+        let pick = self.rng.next_f64();
+        let idx = self
+            .cumulative
+            .iter()
+            .position(|limit| pick < *limit)
+            .unwrap_or(self.transforms.len() - 1);
+        let transform = &self.transforms[idx];
+        self.color = (self.color + transform.color) * 0.5;
+        let (x, y) = transform.apply(x, y);
+        if x.is_finite() && y.is_finite() {
+            (x, y)
+        } else {
+            // some variations are singular, restart the orbit at a random point
+            (
+                self.rng.next_f64().mul_add(2.0, -1.0),
+                self.rng.next_f64().mul_add(2.0, -1.0),
+            )
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn color(&self) -> Option<f32> {
+        Some(self.color as f32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FlameCfg, FlameMap, Variation};
+    use crate::work::fractal::OrbitMap;
+
+    #[test]
+    fn test_variations() {
+        assert_eq!(Variation::Linear.apply(0.3, -0.2), (0.3, -0.2));
+        let (x, y) = Variation::Spherical.apply(1.0, 1.0);
+        assert!((x - 0.5).abs() < 1e-12 && (y - 0.5).abs() < 1e-12);
+        let (x, y) = Variation::Horseshoe.apply(0.0, 2.0);
+        assert!((x + 2.0).abs() < 1e-12 && y.abs() < 1e-12);
+        for variation in Variation::ALL {
+            let (x, y) = variation.apply(0.0, 0.0);
+            assert!(x.is_finite() && y.is_finite(), "{:?}", variation);
+        }
+    }
+
+    #[test]
+    fn test_default_flame() {
+        let cfg = FlameCfg::default();
+        let mut map = FlameMap::new(&cfg);
+        let (mut x, mut y) = (0.1, 0.1);
+        let mut inside = 0;
+        for _ in 0..100_000 {
+            let next = map.next(x, y);
+            x = next.0;
+            y = next.1;
+            let color = map.color().unwrap();
+            assert!((0.0..=1.0).contains(&color));
+            if x >= cfg.x_min.real()
+                && x <= cfg.x_max.real()
+                && y >= cfg.x_min.imag()
+                && y <= cfg.x_max.imag()
+            {
+                inside += 1;
+            }
+        }
+        assert!(inside > 90_000, "only {} points in view", inside);
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let cfg = FlameCfg::default();
+        let json = serde_json::to_string(&cfg).unwrap();
+        assert!(serde_json::from_str::<FlameCfg>(&json).unwrap() == cfg);
+    }
+}
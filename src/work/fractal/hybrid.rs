@@ -0,0 +1,295 @@
+use crate::components::root::Config;
+use crate::work::{
+    complex::Complex,
+    fractal::{Fractal, Lambda, Mandelbrot, Step, ANGLE_ESCAPE_RADIUS},
+    transfer::Transfer,
+};
+use serde::{Deserialize, Serialize};
+
+pub const HYBRID_DEFAULT_C_MAX: (f64, f64) = (0.9, 1.05);
+pub const HYBRID_DEFAULT_C_MIN: (f64, f64) = (-2.1, -1.35);
+pub const HYBRID_DEFAULT_ITERATIONS: u32 = 600;
+
+const HYBRID_ESCAPE_RADIUS: f64 = 4.0;
+
+/// The formulas a hybrid fractal can be built from
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HybridFormula {
+    /// z -> z^p + c
+    Mandelbrot,
+    /// z -> (|re z| + i |im z|)^p + c
+    BurningShip,
+    /// z -> conj(z)^p + c
+    Tricorn,
+    /// z -> c z (1 - z), the power is ignored
+    Lambda,
+}
+
+impl HybridFormula {
+    pub const ALL: [HybridFormula; 4] = [
+        HybridFormula::Mandelbrot,
+        HybridFormula::BurningShip,
+        HybridFormula::Tricorn,
+        HybridFormula::Lambda,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HybridFormula::Mandelbrot => "Mandelbrot",
+            HybridFormula::BurningShip => "Burning Ship",
+            HybridFormula::Tricorn => "Tricorn",
+            HybridFormula::Lambda => "Lambda",
+        }
+    }
+
+    /// The critical point of the formula, hybrids start their orbits at the critical point of
+    /// the first step
+    pub fn critical_point(self) -> Complex {
+        match self {
+            HybridFormula::Lambda => Complex::new(0.5, 0.0),
+            _ => Complex::new(0.0, 0.0),
+        }
+    }
+
+    fn create_step(self, power: u32) -> Box<dyn Step> {
+        match self {
+            HybridFormula::Mandelbrot => Box::new(Mandelbrot::with_power(power)),
+            HybridFormula::BurningShip => Box::new(BurningShip { power }),
+            HybridFormula::Tricorn => Box::new(Tricorn { power }),
+            HybridFormula::Lambda => Box::new(Lambda::default()),
+        }
+    }
+}
+
+struct BurningShip {
+    power: u32,
+}
+
+impl Step for BurningShip {
+    #[inline]
+    fn step(&self, z: &Complex, c: &Complex) -> Complex {
+        Complex::new(z.real().abs(), z.imag().abs()).powi(self.power) + *c
+    }
+}
+
+struct Tricorn {
+    power: u32,
+}
+
+impl Step for Tricorn {
+    #[inline]
+    fn step(&self, z: &Complex, c: &Complex) -> Complex {
+        Complex::new(z.real(), -z.imag()).powi(self.power) + *c
+    }
+}
+
+/// One step of the sequence of a hybrid fractal
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct HybridStep {
+    pub formula: HybridFormula,
+    pub power: u32,
+    /// a fixed c turns the step into a Julia step, `None` takes c from the pixel
+    pub c: Option<Complex>,
+}
+
+impl HybridStep {
+    pub fn new(formula: HybridFormula, power: u32) -> Self {
+        Self {
+            formula,
+            power,
+            c: None,
+        }
+    }
+}
+
+impl Default for HybridStep {
+    fn default() -> Self {
+        Self::new(HybridFormula::Mandelbrot, 2)
+    }
+}
+
+/// Applies the steps in sequence, the sequence repeats until the orbit escapes. Every step
+/// counts as one iteration.
+pub struct Hybrid {
+    steps: Vec<(Box<dyn Step>, Option<Complex>)>,
+    start: Complex,
+    iterations: u32,
+    max: f64,
+    key: String,
+}
+
+impl Hybrid {
+    pub fn new(config: &Config) -> Self {
+        info!(
+            "creating fractal with: c_max: {}, c_min: {}, steps: {}",
+            config.hybrid_cfg.c_max,
+            config.hybrid_cfg.c_min,
+            config.hybrid_cfg.steps.len()
+        );
+
+        let max = if config.coloring.needs_angles() {
+            ANGLE_ESCAPE_RADIUS
+        } else {
+            HYBRID_ESCAPE_RADIUS
+        };
+
+        Self::with_steps(
+            &config.hybrid_cfg.steps,
+            config.hybrid_cfg.max_iterations,
+            max * max,
+        )
+    }
+
+    fn with_steps(steps: &[HybridStep], iterations: u32, max: f64) -> Self {
+        let key = steps
+            .iter()
+            .map(|step| match step.c {
+                Some(c) => format!("{:?}^{}+{}", step.formula, step.power, c),
+                None => format!("{:?}^{}", step.formula, step.power),
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+
+        Self {
+            steps: steps
+                .iter()
+                .map(|step| (step.formula.create_step(step.power), step.c))
+                .collect(),
+            start: steps.first().map_or_else(
+                || Complex::new(0.0, 0.0),
+                |step| step.formula.critical_point(),
+            ),
+            iterations,
+            max,
+            key,
+        }
+    }
+}
+
+impl Step for Hybrid {
+    /// One pass through the whole sequence of steps
+    fn step(&self, z: &Complex, c: &Complex) -> Complex {
+        self.steps.iter().fold(*z, |z, (step, fixed_c)| {
+            step.step(&z, fixed_c.as_ref().unwrap_or(c))
+        })
+    }
+}
+
+impl Fractal for Hybrid {
+    fn get_scale(&self, config: &Config, canvas_width: u32, canvas_height: u32) -> Complex {
+        Complex::new(
+            (config.hybrid_cfg.c_max.real() - config.hybrid_cfg.c_min.real())
+                / f64::from(canvas_width),
+            (config.hybrid_cfg.c_max.imag() - config.hybrid_cfg.c_min.imag())
+                / f64::from(canvas_height),
+        )
+    }
+
+    fn get_offset(&self, config: &Config) -> Complex {
+        config.hybrid_cfg.c_min
+    }
+
+    fn iterate_final(&self, c: &Complex) -> (u32, Complex) {
+        let mut z = self.start;
+        if self.steps.is_empty() {
+            return (self.iterations + 1, z);
+        }
+
+        let mut last: Option<u32> = None;
+        for (idx, (step, fixed_c)) in (1..=self.iterations).zip(self.steps.iter().cycle()) {
+            z = step.step(&z, fixed_c.as_ref().unwrap_or(c));
+            if z.square_length() >= self.max {
+                last = Some(idx);
+                break;
+            }
+        }
+        (last.unwrap_or(self.iterations + 1), z)
+    }
+
+    fn cache_key(&self) -> String {
+        format!("hybrid:{}:{}:{}", self.iterations, self.max, self.key)
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct HybridCfg {
+    pub steps: Vec<HybridStep>,
+    pub max_iterations: u32,
+    pub c_max: Complex,
+    pub c_min: Complex,
+    pub color_cfg_name: Option<String>,
+    #[serde(default)]
+    pub transfer: Transfer,
+}
+
+impl HybridCfg {
+    /// Two Mandelbrot steps followed by one Burning Ship step
+    pub fn default_steps() -> Vec<HybridStep> {
+        vec![
+            HybridStep::new(HybridFormula::Mandelbrot, 2),
+            HybridStep::new(HybridFormula::Mandelbrot, 2),
+            HybridStep::new(HybridFormula::BurningShip, 2),
+        ]
+    }
+}
+
+impl Default for HybridCfg {
+    fn default() -> Self {
+        Self {
+            steps: HybridCfg::default_steps(),
+            max_iterations: HYBRID_DEFAULT_ITERATIONS,
+            c_max: Complex::new(HYBRID_DEFAULT_C_MAX.0, HYBRID_DEFAULT_C_MAX.1),
+            c_min: Complex::new(HYBRID_DEFAULT_C_MIN.0, HYBRID_DEFAULT_C_MIN.1),
+            color_cfg_name: None,
+            transfer: Transfer::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Hybrid, HybridFormula, HybridStep};
+    use crate::work::complex::Complex;
+    use crate::work::fractal::{Fractal, Mandelbrot, Step};
+
+    #[test]
+    fn test_single_step_matches_mandelbrot() {
+        let hybrid =
+            Hybrid::with_steps(&[HybridStep::new(HybridFormula::Mandelbrot, 3)], 100, 16.0);
+        let mandelbrot = Mandelbrot::with_power(3);
+        for c in [Complex::new(0.3, 0.6), Complex::new(0.5, 0.6)] {
+            let mut z = Complex::new(0.0, 0.0);
+            let mut expected = 101;
+            for idx in 1..=100 {
+                z = mandelbrot.step(&z, &c);
+                if z.square_length() >= 16.0 {
+                    expected = idx;
+                    break;
+                }
+            }
+            assert_eq!(hybrid.iterate(&c), expected);
+        }
+    }
+
+    #[test]
+    fn test_sequence() {
+        let steps = [
+            HybridStep::new(HybridFormula::Mandelbrot, 2),
+            HybridStep::new(HybridFormula::BurningShip, 2),
+            HybridStep {
+                c: Some(Complex::new(0.25, 0.0)),
+                ..HybridStep::new(HybridFormula::Tricorn, 2)
+            },
+        ];
+        let hybrid = Hybrid::with_steps(&steps, 100, 16.0);
+        let c = Complex::new(-0.5, 0.5);
+        // z1 = c, z2 = (0.5 + 0.5i)^2 + c = -0.5 + i, z3 = conj(z2)^2 + 0.25
+        let z3 = hybrid.step(&Complex::new(0.0, 0.0), &c);
+        assert_eq!(
+            z3,
+            Complex::new(-0.5, -1.0) * Complex::new(-0.5, -1.0) + Complex::new(0.25, 0.0)
+        );
+        assert_eq!(hybrid.iterate(&Complex::new(0.0, 0.0)), 101);
+        assert!(hybrid.iterate(&Complex::new(1.0, 1.0)) <= 100);
+    }
+}
@@ -0,0 +1,231 @@
+use super::{OrbitMap, XorShiftRng};
+use crate::work::complex::Complex;
+use serde::{Deserialize, Serialize};
+
+pub const IFS_DEFAULT_POINTS: u32 = 10_000_000;
+
+const RNG_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+/// The affine map (x, y) -> (a x + b y + e, c x + d y + f), picked with the given probability
+/// by the chaos game
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct AffineTransform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+    pub probability: f64,
+}
+
+impl AffineTransform {
+    pub fn new(coeffs: [f64; 6], probability: f64) -> Self {
+        let [a, b, c, d, e, f] = coeffs;
+        Self {
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+            probability,
+        }
+    }
+
+    #[inline]
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a.mul_add(x, self.b.mul_add(y, self.e)),
+            self.c.mul_add(x, self.d.mul_add(y, self.f)),
+        )
+    }
+}
+
+impl Default for AffineTransform {
+    fn default() -> Self {
+        AffineTransform::new([0.5, 0.0, 0.0, 0.5, 0.0, 0.0], 1.0)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum IfsPreset {
+    BarnsleyFern,
+    Sierpinski,
+    LevyDragon,
+}
+
+impl IfsPreset {
+    /// The transforms and the area (x_min, x_max) showing the complete fractal
+    pub fn transforms(self) -> (Vec<AffineTransform>, Complex, Complex) {
+        match self {
+            IfsPreset::BarnsleyFern => (
+                vec![
+                    AffineTransform::new([0.0, 0.0, 0.0, 0.16, 0.0, 0.0], 0.01),
+                    AffineTransform::new([0.85, 0.04, -0.04, 0.85, 0.0, 1.6], 0.85),
+                    AffineTransform::new([0.2, -0.26, 0.23, 0.22, 0.0, 1.6], 0.07),
+                    AffineTransform::new([-0.15, 0.28, 0.26, 0.24, 0.0, 0.44], 0.07),
+                ],
+                Complex::new(-5.2, -0.2),
+                Complex::new(5.7, 10.2),
+            ),
+            IfsPreset::Sierpinski => (
+                vec![
+                    AffineTransform::new([0.5, 0.0, 0.0, 0.5, 0.0, 0.0], 1.0 / 3.0),
+                    AffineTransform::new([0.5, 0.0, 0.0, 0.5, 0.5, 0.0], 1.0 / 3.0),
+                    AffineTransform::new([0.5, 0.0, 0.0, 0.5, 0.25, 0.5], 1.0 / 3.0),
+                ],
+                Complex::new(-0.1, -0.05),
+                Complex::new(1.1, 1.05),
+            ),
+            IfsPreset::LevyDragon => (
+                vec![
+                    AffineTransform::new([0.5, -0.5, 0.5, 0.5, 0.0, 0.0], 0.5),
+                    AffineTransform::new([0.5, 0.5, -0.5, 0.5, 0.5, 0.5], 0.5),
+                ],
+                Complex::new(-0.7, -0.45),
+                Complex::new(1.7, 1.2),
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct IfsCfg {
+    pub transforms: Vec<AffineTransform>,
+    pub x_max: Complex,
+    pub x_min: Complex,
+    /// the number of points to plot before the fractal is complete
+    pub max_points: u32,
+    pub color_cfg_name: Option<String>,
+}
+
+impl IfsCfg {
+    pub fn from_preset(preset: IfsPreset) -> Self {
+        let (transforms, x_min, x_max) = preset.transforms();
+        Self {
+            transforms,
+            x_max,
+            x_min,
+            max_points: IFS_DEFAULT_POINTS,
+            color_cfg_name: None,
+        }
+    }
+}
+
+impl Default for IfsCfg {
+    fn default() -> Self {
+        IfsCfg::from_preset(IfsPreset::BarnsleyFern)
+    }
+}
+
+/// Plays the chaos game: every step applies one of the transforms picked at random
+/// by probability
+pub struct IfsMap {
+    transforms: Vec<AffineTransform>,
+    cumulative: Vec<f64>,
+    rng: XorShiftRng,
+}
+
+impl IfsMap {
+    pub fn new(cfg: &IfsCfg) -> Self {
+        info!(
+            "creating IFS with {} transforms, x_max: {}, x_min: {}",
+            cfg.transforms.len(),
+            cfg.x_max,
+            cfg.x_min
+        );
+
+        let transforms = if cfg.transforms.is_empty() {
+            vec![AffineTransform::default()]
+        } else {
+            cfg.transforms.clone()
+        };
+
+        Self {
+            cumulative: cumulative_probabilities(
+                &transforms
+                    .iter()
+                    .map(|transform| transform.probability)
+                    .collect::<Vec<f64>>(),
+            ),
+            transforms,
+            rng: XorShiftRng::new(RNG_SEED),
+        }
+    }
+}
+
+impl OrbitMap for IfsMap {
+    #[inline]
+    fn next(&mut self, x: f64, y: f64) -> (f64, f64) {
+        let pick = self.rng.next_f64();
+        let idx = self
+            .cumulative
+            .iter()
+            .position(|limit| pick < *limit)
+            .unwrap_or(self.transforms.len() - 1);
+        self.transforms[idx].apply(x, y)
+    }
+}
+
+/// Normalized cumulative probabilities for picking a map, all maps are equally likely if no
+/// probabilities are given
+#[allow(clippy::cast_precision_loss)]
+pub fn cumulative_probabilities(probabilities: &[f64]) -> Vec<f64> {
+    let total: f64 = probabilities.iter().map(|p| p.max(0.0)).sum();
+    let mut sum = 0.0;
+    probabilities
+        .iter()
+        .map(|probability| {
+            sum += if total > 0.0 {
+                probability.max(0.0) / total
+            } else {
+                1.0 / probabilities.len() as f64
+            };
+            sum
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{cumulative_probabilities, IfsCfg, IfsMap, IfsPreset};
+    use crate::work::fractal::OrbitMap;
+
+    #[test]
+    fn test_cumulative_probabilities() {
+        assert_eq!(cumulative_probabilities(&[1.0, 3.0]), vec![0.25, 1.0]);
+        assert_eq!(cumulative_probabilities(&[0.0, 0.0]), vec![0.5, 1.0]);
+        assert_eq!(cumulative_probabilities(&[-1.0, 2.0]), vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_presets_stay_in_view() {
+        for preset in [
+            IfsPreset::BarnsleyFern,
+            IfsPreset::Sierpinski,
+            IfsPreset::LevyDragon,
+        ] {
+            let cfg = IfsCfg::from_preset(preset);
+            let mut map = IfsMap::new(&cfg);
+            let (mut x, mut y) = (0.1, 0.1);
+            for idx in 0..100_000 {
+                let next = map.next(x, y);
+                x = next.0;
+                y = next.1;
+                if idx >= 100 {
+                    assert!(
+                        x >= cfg.x_min.real() && x <= cfg.x_max.real(),
+                        "{:?}",
+                        preset
+                    );
+                    assert!(
+                        y >= cfg.x_min.imag() && y <= cfg.x_max.imag(),
+                        "{:?}",
+                        preset
+                    );
+                }
+            }
+        }
+    }
+}
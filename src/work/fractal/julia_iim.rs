@@ -0,0 +1,135 @@
+use super::{find_escape_radius, MAX_DURATION};
+use crate::components::root::Config;
+use crate::work::{complex::Complex, stats::Stats};
+
+pub const IIM_DEFAULT_MAX_HITS: u32 = 4;
+
+// preimages are followed at most MAX_DEPTH levels deep
+const MAX_DEPTH: u32 = 1000;
+// resolution of the grid that limits the density of points outside the canvas
+const OUTSIDE_GRID: u32 = 512;
+const CHECK_INTERVAL: usize = 1000;
+
+/// Plots the boundary of a Julia set by inverse iteration z -> ±sqrt(z - c).
+/// This is the modified IIM: the tree of preimages is traversed depth first and a branch is
+/// dropped as soon as its pixel has been hit max_hits times.
+pub struct IimCalculator {
+    c: Complex,
+    scale: Complex,
+    offset: Complex,
+    width: u32,
+    height: u32,
+    radius: f64,
+    max_hits: u32,
+    hits: Vec<u32>,
+    outside_hits: Vec<u32>,
+    stack: Vec<(Complex, u32)>,
+    res: Vec<(u32, u32)>,
+    done: bool,
+}
+
+impl IimCalculator {
+    pub fn new(config: &Config, canvas_width: u32, canvas_height: u32) -> Self {
+        let cfg = &config.julia_set_cfg;
+        info!(
+            "creating inverse iteration calculator with: c: {}, max hits: {}",
+            cfg.c, cfg.iim_max_hits
+        );
+
+        let scale = Complex::new(
+            (cfg.x_max.real() - cfg.x_min.real()) / f64::from(canvas_width),
+            (cfg.x_max.imag() - cfg.x_min.imag()) / f64::from(canvas_height),
+        );
+
+        // the repelling fixed point 1/2 + sqrt(1/4 - c) lies on the Julia set
+        let beta = (Complex::new(0.25, 0.0) - cfg.c).sqrt() + Complex::new(0.5, 0.0);
+
+        Self {
+            c: cfg.c,
+            scale,
+            offset: cfg.x_min,
+            width: canvas_width,
+            height: canvas_height,
+            radius: find_escape_radius(cfg.c.norm()),
+            max_hits: cfg.iim_max_hits.max(1),
+            hits: vec![0; (canvas_width * canvas_height) as usize],
+            outside_hits: vec![0; (OUTSIDE_GRID * OUTSIDE_GRID) as usize],
+            stack: vec![(beta, 0)],
+            res: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Follow preimages for up to MAX_DURATION ms, returns the pixels plotted for the first time
+    pub fn calculate(&mut self, stats: Option<&mut Stats>) -> &[(u32, u32)] {
+        let performance = web_sys::window()
+            .expect("Window not found")
+            .performance()
+            .expect("performance should be available");
+
+        let start = performance.now();
+        self.res.clear();
+
+        let mut steps = 0usize;
+        while let Some((z, depth)) = self.stack.pop() {
+            steps += 1;
+            if self.hit(&z) && depth < MAX_DEPTH {
+                let root = (z - self.c).sqrt();
+                self.stack.push((-root, depth + 1));
+                self.stack.push((root, depth + 1));
+            }
+
+            if steps % CHECK_INTERVAL == 0 && performance.now() - start >= MAX_DURATION {
+                break;
+            }
+        }
+
+        if self.stack.is_empty() {
+            self.done = true;
+        }
+
+        if let Some(stats) = stats {
+            stats.update(steps, self.res.len(), start);
+        }
+
+        &self.res
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Count a visit of z and plot its pixel on the first visit. Returns false if the pixel is
+    /// saturated and the preimages of z need not be followed.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn hit(&mut self, z: &Complex) -> bool {
+        let x = (z.real() - self.offset.real()) / self.scale.real();
+        let y = (z.imag() - self.offset.imag()) / self.scale.imag();
+
+        let (counter, plot) =
+            if x >= 0.0 && x < f64::from(self.width) && y >= 0.0 && y < f64::from(self.height) {
+                let (x, y) = (x as u32, y as u32);
+                (&mut self.hits[(y * self.width + x) as usize], Some((x, y)))
+            } else {
+                // the Julia set lies within the escape radius
+                let grid = |value: f64| {
+                    ((value + self.radius) / (2.0 * self.radius) * f64::from(OUTSIDE_GRID))
+                        .max(0.0)
+                        .min(f64::from(OUTSIDE_GRID - 1)) as u32
+                };
+                let idx = (grid(z.imag()) * OUTSIDE_GRID + grid(z.real())) as usize;
+                (&mut self.outside_hits[idx], None)
+            };
+
+        if *counter >= self.max_hits {
+            return false;
+        }
+        *counter += 1;
+        if *counter == 1 {
+            if let Some(plot) = plot {
+                self.res.push(plot);
+            }
+        }
+        true
+    }
+}
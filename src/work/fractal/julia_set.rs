@@ -1,7 +1,10 @@
 // use wasm_bindgen::prelude::web_sys;
-use super::find_escape_radius;
 use crate::components::root::Config;
-use crate::work::{complex::Complex, fractal::Fractal};
+use crate::work::{
+    complex::Complex,
+    fractal::{AutoIterations, Bailout, Fractal, Step, ANGLE_ESCAPE_RADIUS, IIM_DEFAULT_MAX_HITS},
+    transfer::Transfer,
+};
 use serde::{Deserialize, Serialize};
 
 pub const JULIA_DEFAULT_X_MAX: (f64, f64) = (1.5, 1.0);
@@ -12,8 +15,9 @@ pub const JULIA_DEFAULT_ITERATIONS: u32 = 400;
 
 pub struct JuliaSet {
     c: Complex,
-    max: f64,
+    bailout: Bailout,
     iterations: u32,
+    needs_angles: bool,
 }
 
 impl JuliaSet {
@@ -23,16 +27,31 @@ impl JuliaSet {
             config.julia_set_cfg.x_max, config.julia_set_cfg.x_min, config.julia_set_cfg.c
         );
 
-        let max = find_escape_radius(config.julia_set_cfg.c.norm());
+        // radii below the escape radius count orbits of the set as escaped, that is up to the
+        // user, the angles of the final orbit values need a large radius though
+        let bailout = if config.coloring.needs_angles() {
+            config.julia_set_cfg.bailout.at_least(ANGLE_ESCAPE_RADIUS)
+        } else {
+            config.julia_set_cfg.bailout
+        };
 
         Self {
             c: config.julia_set_cfg.c,
-            max: max * max,
+            bailout,
             iterations: config.julia_set_cfg.max_iterations,
+            needs_angles: config.coloring.needs_angles(),
         }
     }
 }
 
+impl Step for JuliaSet {
+    /// The constant of a Julia set is fixed, the pixel is the start of the orbit
+    #[inline]
+    fn step(&self, z: &Complex, _c: &Complex) -> Complex {
+        *z * *z + self.c
+    }
+}
+
 impl Fractal for JuliaSet {
     fn get_scale(&self, config: &Config, canvas_width: u32, canvas_height: u32) -> Complex {
         Complex::new(
@@ -47,20 +66,27 @@ impl Fractal for JuliaSet {
         config.julia_set_cfg.x_min.clone()
     }
 
-    fn iterate(&self, x: &Complex) -> u32 {
+    fn iterate_final(&self, x: &Complex) -> (u32, Complex) {
         let mut curr = *x;
         // log!(format!("iterate: start: {}", curr));
         let mut last: Option<u32> = None;
         for idx in 1..=self.iterations {
-            curr = curr * curr + self.c;
-            if curr.square_length() >= self.max {
+            curr = self.step(&curr, x);
+            if self.bailout.escaped(&curr) {
                 last = Some(idx);
                 break;
             }
         }
 
         // log!(format!("iterate: end:  {} norm: {} last: {:?}", curr, curr.square_length(), last));
-        last.unwrap_or(self.iterations + 1)
+        (last.unwrap_or(self.iterations + 1), curr)
+    }
+
+    fn cache_key(&self) -> String {
+        format!(
+            "julia_set:{}:{}:{:?}:{}:{}",
+            self.iterations, self.c, self.bailout.shape, self.bailout.radius, self.needs_angles
+        )
     }
 }
 
@@ -71,6 +97,20 @@ pub struct JuliaSetCfg {
     pub x_min: Complex,
     pub c: Complex,
     pub color_cfg_name: Option<String>,
+    #[serde(default)]
+    pub auto_iterations: AutoIterations,
+    #[serde(default)]
+    pub renderer: JuliaRenderer,
+    #[serde(default = "default_iim_max_hits")]
+    pub iim_max_hits: u32,
+    #[serde(default)]
+    pub bailout: Bailout,
+    #[serde(default)]
+    pub transfer: Transfer,
+}
+
+fn default_iim_max_hits() -> u32 {
+    IIM_DEFAULT_MAX_HITS
 }
 
 impl Default for JuliaSetCfg {
@@ -81,6 +121,38 @@ impl Default for JuliaSetCfg {
             x_min: Complex::new(JULIA_DEFAULT_X_MIN.0, JULIA_DEFAULT_X_MIN.1),
             c: Complex::new(JULIA_DEFAULT_C.0, JULIA_DEFAULT_C.1),
             color_cfg_name: None,
+            auto_iterations: AutoIterations::default(),
+            renderer: JuliaRenderer::default(),
+            iim_max_hits: IIM_DEFAULT_MAX_HITS,
+            bailout: Bailout::default(),
+            transfer: Transfer::default(),
         }
     }
 }
+
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum JuliaRenderer {
+    /// color every pixel by its escape time
+    #[default]
+    EscapeTime,
+    /// plot the boundary of the set by inverse iteration
+    InverseIteration,
+}
+
+#[cfg(test)]
+mod test {
+    use super::JuliaSet;
+    use crate::components::root::Config;
+    use crate::work::fractal::{ColoringMode, Fractal};
+
+    #[test]
+    fn test_cache_key_angles() {
+        // above the angle escape radius both coloring modes iterate alike but only one
+        // keeps the angles
+        let mut config = Config::std_cfg();
+        config.julia_set_cfg.bailout.radius = 200.0;
+        let iterations = JuliaSet::new(&config).cache_key();
+        config.coloring = ColoringMode::FieldLines;
+        assert_ne!(JuliaSet::new(&config).cache_key(), iterations);
+    }
+}
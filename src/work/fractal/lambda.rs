@@ -0,0 +1,283 @@
+use crate::components::root::Config;
+use crate::work::{
+    complex::Complex,
+    fractal::{Fractal, Step, ANGLE_ESCAPE_RADIUS},
+    transfer::Transfer,
+};
+use serde::{Deserialize, Serialize};
+
+pub const LAMBDA_DEFAULT_L_MAX: (f64, f64) = (4.2, 1.7);
+pub const LAMBDA_DEFAULT_L_MIN: (f64, f64) = (-2.2, -1.7);
+pub const LAMBDA_DEFAULT_ITERATIONS: u32 = 400;
+
+pub const LAMBDA_JULIA_DEFAULT_X_MAX: (f64, f64) = (1.3, 0.6);
+pub const LAMBDA_JULIA_DEFAULT_X_MIN: (f64, f64) = (-0.3, -0.6);
+pub const LAMBDA_JULIA_DEFAULT_LAMBDA: (f64, f64) = (2.553, -0.96);
+pub const LAMBDA_JULIA_DEFAULT_ITERATIONS: u32 = 400;
+
+/// The critical point of the logistic map, the orbits of the parameter plane start here
+const CRITICAL_POINT: f64 = 0.5;
+
+/// Orbits of z -> l z (1 - z) that leave the disc of radius 1 + 1 / |l| escape to infinity
+fn escape_radius(lambda: &Complex, needs_angles: bool) -> f64 {
+    let radius = 1.0 + lambda.norm().recip();
+    if needs_angles {
+        radius.max(ANGLE_ESCAPE_RADIUS)
+    } else {
+        radius.max(2.0)
+    }
+}
+
+/// The logistic map z -> l z (1 - z)
+#[inline]
+fn logistic(lambda: &Complex, z: &Complex) -> Complex {
+    lambda.mul_by(z) * (Complex::new(1.0, 0.0) - *z)
+}
+
+/// Iterate the steps of `fractal` for the pixel `c` starting at `start`, returns the iteration
+/// count and the final orbit value
+#[inline]
+fn iterate_steps(
+    fractal: &dyn Step,
+    c: &Complex,
+    start: &Complex,
+    max: f64,
+    iterations: u32,
+) -> (u32, Complex) {
+    let mut z = *start;
+    let mut last: Option<u32> = None;
+    for idx in 1..=iterations {
+        z = fractal.step(&z, c);
+        if z.square_length() >= max {
+            last = Some(idx);
+            break;
+        }
+    }
+    (last.unwrap_or(iterations + 1), z)
+}
+
+/// The parameter plane of the logistic map: every pixel is a l, iterated from the critical
+/// point 1/2
+pub struct Lambda {
+    iterations: u32,
+    needs_angles: bool,
+}
+
+impl Lambda {
+    pub fn new(config: &Config) -> Self {
+        info!(
+            "creating fractal with: l_max: {}, l_min: {}",
+            config.lambda_cfg.l_max, config.lambda_cfg.l_min,
+        );
+
+        Self {
+            iterations: config.lambda_cfg.max_iterations,
+            needs_angles: config.coloring.needs_angles(),
+        }
+    }
+}
+
+impl Default for Lambda {
+    /// The parameter plane with the default parameters, used as a step of hybrid fractals
+    fn default() -> Self {
+        Self {
+            iterations: LAMBDA_DEFAULT_ITERATIONS,
+            needs_angles: false,
+        }
+    }
+}
+
+impl Step for Lambda {
+    #[inline]
+    fn step(&self, z: &Complex, lambda: &Complex) -> Complex {
+        logistic(lambda, z)
+    }
+}
+
+impl Fractal for Lambda {
+    fn get_scale(&self, config: &Config, canvas_width: u32, canvas_height: u32) -> Complex {
+        Complex::new(
+            (config.lambda_cfg.l_max.real() - config.lambda_cfg.l_min.real())
+                / f64::from(canvas_width),
+            (config.lambda_cfg.l_max.imag() - config.lambda_cfg.l_min.imag())
+                / f64::from(canvas_height),
+        )
+    }
+
+    fn get_offset(&self, config: &Config) -> Complex {
+        config.lambda_cfg.l_min
+    }
+
+    fn iterate_final(&self, lambda: &Complex) -> (u32, Complex) {
+        let max = escape_radius(lambda, self.needs_angles);
+        iterate_steps(
+            self,
+            lambda,
+            &Complex::new(CRITICAL_POINT, 0.0),
+            max * max,
+            self.iterations,
+        )
+    }
+
+    fn cache_key(&self) -> String {
+        format!("lambda:{}:{}", self.iterations, self.needs_angles)
+    }
+}
+
+/// The dynamical plane of the logistic map for a fixed l, the counterpart of the Julia set
+pub struct LambdaJulia {
+    lambda: Complex,
+    max: f64,
+    iterations: u32,
+    needs_angles: bool,
+}
+
+impl LambdaJulia {
+    pub fn new(config: &Config) -> Self {
+        info!(
+            "creating fractal with: x_max: {}, x_min: {}, lambda: {}",
+            config.lambda_julia_cfg.x_max,
+            config.lambda_julia_cfg.x_min,
+            config.lambda_julia_cfg.lambda
+        );
+
+        let max = escape_radius(
+            &config.lambda_julia_cfg.lambda,
+            config.coloring.needs_angles(),
+        );
+
+        Self {
+            lambda: config.lambda_julia_cfg.lambda,
+            max: max * max,
+            iterations: config.lambda_julia_cfg.max_iterations,
+            needs_angles: config.coloring.needs_angles(),
+        }
+    }
+}
+
+impl Step for LambdaJulia {
+    #[inline]
+    fn step(&self, z: &Complex, _c: &Complex) -> Complex {
+        logistic(&self.lambda, z)
+    }
+}
+
+impl Fractal for LambdaJulia {
+    fn get_scale(&self, config: &Config, canvas_width: u32, canvas_height: u32) -> Complex {
+        Complex::new(
+            (config.lambda_julia_cfg.x_max.real() - config.lambda_julia_cfg.x_min.real())
+                / f64::from(canvas_width),
+            (config.lambda_julia_cfg.x_max.imag() - config.lambda_julia_cfg.x_min.imag())
+                / f64::from(canvas_height),
+        )
+    }
+
+    fn get_offset(&self, config: &Config) -> Complex {
+        config.lambda_julia_cfg.x_min
+    }
+
+    fn iterate_final(&self, x: &Complex) -> (u32, Complex) {
+        iterate_steps(self, x, x, self.max, self.iterations)
+    }
+
+    fn cache_key(&self) -> String {
+        format!(
+            "lambda_julia:{}:{}:{}:{}",
+            self.iterations, self.lambda, self.max, self.needs_angles
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct LambdaCfg {
+    pub max_iterations: u32,
+    pub l_max: Complex,
+    pub l_min: Complex,
+    pub color_cfg_name: Option<String>,
+    #[serde(default)]
+    pub transfer: Transfer,
+}
+
+impl Default for LambdaCfg {
+    fn default() -> Self {
+        Self {
+            max_iterations: LAMBDA_DEFAULT_ITERATIONS,
+            l_max: Complex::new(LAMBDA_DEFAULT_L_MAX.0, LAMBDA_DEFAULT_L_MAX.1),
+            l_min: Complex::new(LAMBDA_DEFAULT_L_MIN.0, LAMBDA_DEFAULT_L_MIN.1),
+            color_cfg_name: None,
+            transfer: Transfer::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct LambdaJuliaCfg {
+    pub max_iterations: u32,
+    pub x_max: Complex,
+    pub x_min: Complex,
+    pub lambda: Complex,
+    pub color_cfg_name: Option<String>,
+    #[serde(default)]
+    pub transfer: Transfer,
+}
+
+impl Default for LambdaJuliaCfg {
+    fn default() -> Self {
+        Self {
+            max_iterations: LAMBDA_JULIA_DEFAULT_ITERATIONS,
+            x_max: Complex::new(LAMBDA_JULIA_DEFAULT_X_MAX.0, LAMBDA_JULIA_DEFAULT_X_MAX.1),
+            x_min: Complex::new(LAMBDA_JULIA_DEFAULT_X_MIN.0, LAMBDA_JULIA_DEFAULT_X_MIN.1),
+            lambda: Complex::new(LAMBDA_JULIA_DEFAULT_LAMBDA.0, LAMBDA_JULIA_DEFAULT_LAMBDA.1),
+            color_cfg_name: None,
+            transfer: Transfer::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Lambda, LambdaJulia};
+    use crate::components::root::Config;
+    use crate::work::complex::Complex;
+    use crate::work::fractal::{ColoringMode, Fractal};
+
+    #[test]
+    fn test_parameter_plane() {
+        let lambda = Lambda {
+            iterations: 200,
+            needs_angles: false,
+        };
+        // attracting fixed point for |l - 1| < 1 and period 2 for l = 3.2
+        assert_eq!(lambda.iterate(&Complex::new(1.5, 0.3)), 201);
+        assert_eq!(lambda.iterate(&Complex::new(3.2, 0.0)), 201);
+        // the real segment of the set ends at l = 4 and l = -2
+        assert!(lambda.iterate(&Complex::new(4.1, 0.0)) <= 200);
+        assert!(lambda.iterate(&Complex::new(-2.1, 0.0)) <= 200);
+        assert!(lambda.iterate(&Complex::new(1.0, 2.5)) <= 200);
+    }
+
+    #[test]
+    fn test_dynamical_plane() {
+        // for l = 2 the fixed point 1/2 attracts the interval 0 < z < 1
+        let julia = LambdaJulia {
+            lambda: Complex::new(2.0, 0.0),
+            max: 4.0,
+            iterations: 200,
+            needs_angles: false,
+        };
+        assert_eq!(julia.iterate(&Complex::new(0.3, 0.0)), 201);
+        assert_eq!(julia.iterate(&Complex::new(0.9, 0.1)), 201);
+        assert!(julia.iterate(&Complex::new(1.2, 0.0)) <= 200);
+        assert!(julia.iterate(&Complex::new(-0.2, 0.0)) <= 200);
+    }
+
+    #[test]
+    fn test_cache_key_angles() {
+        // for small l the escape radius passes the angle escape radius in both coloring modes
+        let mut config = Config::std_cfg();
+        config.lambda_julia_cfg.lambda = Complex::new(0.005, 0.0);
+        let iterations = LambdaJulia::new(&config).cache_key();
+        config.coloring = ColoringMode::FieldLines;
+        assert_ne!(LambdaJulia::new(&config).cache_key(), iterations);
+    }
+}
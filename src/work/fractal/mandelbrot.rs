@@ -1,7 +1,11 @@
 use crate::components::root::Config;
 use serde::{Deserialize, Serialize};
 
-use crate::work::{complex::Complex, fractal::Fractal};
+use crate::work::{
+    complex::Complex,
+    fractal::{AutoIterations, Bailout, Fractal, Step, ANGLE_ESCAPE_RADIUS},
+    transfer::Transfer,
+};
 
 pub const MANDELBROT_DEFAULT_C_MAX: (f64, f64) = (0.47, 1.12);
 pub const MANDELBROT_DEFAULT_C_MIN: (f64, f64) = (-2.00, -1.12);
@@ -10,38 +14,70 @@ pub const MANDELBROT_DEFAULT_ITERATIONS: u32 = 400;
 pub struct Mandelbrot {
     iterations: u32,
     power: u32,
+    z0: Complex,
+    bailout: Bailout,
+    needs_angles: bool,
 }
 
 impl Mandelbrot {
     pub fn new(config: &Config) -> Self {
         info!(
-            "creating fractal with: x_max: {}, x_min: {}",
-            config.mandelbrot_cfg.c_max, config.mandelbrot_cfg.c_min,
+            "creating fractal with: x_max: {}, x_min: {}, z0: {}",
+            config.mandelbrot_cfg.c_max, config.mandelbrot_cfg.c_min, config.mandelbrot_cfg.z0,
         );
 
+        // radii below the escape radius count orbits of the set as escaped, that is up to the
+        // user, the angles of the final orbit values need a large radius though
+        let bailout = if config.coloring.needs_angles() {
+            config.mandelbrot_cfg.bailout.at_least(ANGLE_ESCAPE_RADIUS)
+        } else {
+            config.mandelbrot_cfg.bailout
+        };
+
         Self {
             iterations: config.mandelbrot_cfg.max_iterations,
             power: config.mandelbrot_cfg.power,
+            z0: config.mandelbrot_cfg.z0,
+            bailout,
+            needs_angles: config.coloring.needs_angles(),
+        }
+    }
+
+    /// A Mandelbrot set with the default parameters and the given power, used as a step of
+    /// hybrid fractals
+    pub fn with_power(power: u32) -> Self {
+        Self {
+            iterations: MANDELBROT_DEFAULT_ITERATIONS,
+            power,
+            z0: critical_point(),
+            bailout: Bailout::default(),
+            needs_angles: false,
         }
     }
 }
 
+impl Step for Mandelbrot {
+    #[inline]
+    fn step(&self, z: &Complex, c: &Complex) -> Complex {
+        z.powi(self.power) + *c
+    }
+}
+
 impl Fractal for Mandelbrot {
-    fn iterate(&self, c: &Complex) -> u32 {
-        let max = 4.0;
-        let mut x = Complex::new(0.0, 0.0);
+    fn iterate_final(&self, c: &Complex) -> (u32, Complex) {
+        let mut x = self.z0;
         // log!(format!("iterate: start: {}", curr));
         let mut last: Option<u32> = None;
         for idx in 1..=self.iterations {
-            x = x.powi(self.power) + *c;
-            if x.square_length() >= max {
+            x = self.step(&x, c);
+            if self.bailout.escaped(&x) {
                 last = Some(idx);
                 break;
             }
         }
 
         // log!(format!("iterate: end:  {} norm: {} last: {:?}", curr, curr.square_length(), last));
-        last.unwrap_or(self.iterations + 1)
+        (last.unwrap_or(self.iterations + 1), x)
     }
 
     fn get_scale(&self, config: &Config, canvas_width: u32, canvas_height: u32) -> Complex {
@@ -56,6 +92,18 @@ impl Fractal for Mandelbrot {
     fn get_offset(&self, config: &Config) -> Complex {
         config.mandelbrot_cfg.c_min.clone()
     }
+
+    fn cache_key(&self) -> String {
+        format!(
+            "mandelbrot:{}:{}:{}:{:?}:{}:{}",
+            self.iterations,
+            self.power,
+            self.z0,
+            self.bailout.shape,
+            self.bailout.radius,
+            self.needs_angles
+        )
+    }
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Clone)]
@@ -65,6 +113,20 @@ pub struct MandelbrotCfg {
     pub c_min: Complex,
     pub power: u32,
     pub color_cfg_name: Option<String>,
+    #[serde(default)]
+    pub auto_iterations: AutoIterations,
+    /// start of the orbits, the critical point 0 gives the classic Mandelbrot set
+    #[serde(default = "critical_point")]
+    pub z0: Complex,
+    #[serde(default)]
+    pub bailout: Bailout,
+    #[serde(default)]
+    pub transfer: Transfer,
+}
+
+/// The only critical point of x^p + c for every power p, of multiplicity p - 1
+pub fn critical_point() -> Complex {
+    Complex::new(0.0, 0.0)
 }
 
 impl Default for MandelbrotCfg {
@@ -75,6 +137,66 @@ impl Default for MandelbrotCfg {
             c_min: Complex::new(MANDELBROT_DEFAULT_C_MIN.0, MANDELBROT_DEFAULT_C_MIN.1),
             power: 2,
             color_cfg_name: None,
+            auto_iterations: AutoIterations::default(),
+            z0: critical_point(),
+            bailout: Bailout::default(),
+            transfer: Transfer::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{critical_point, Mandelbrot, MandelbrotCfg};
+    use crate::components::root::Config;
+    use crate::work::complex::Complex;
+    use crate::work::fractal::{ColoringMode, Fractal};
+
+    #[test]
+    fn test_bailout_radius() {
+        // radii below the escape radius are kept, c = 1.2 escapes the unit disc at once
+        let mut config = Config::std_cfg();
+        config.mandelbrot_cfg.bailout.radius = 1.0;
+        let (iterations, _) = Mandelbrot::new(&config).iterate_final(&Complex::new(1.2, 0.0));
+        assert_eq!(iterations, 1);
+        config.mandelbrot_cfg.bailout.radius = 2.0;
+        let (iterations, _) = Mandelbrot::new(&config).iterate_final(&Complex::new(1.2, 0.0));
+        assert_eq!(iterations, 2);
+    }
+
+    #[test]
+    fn test_z0() {
+        let mut config = Config::std_cfg();
+        let c = Complex::new(0.0, 0.0);
+        // the critical point is a fixed point for c = 0
+        let (iterations, _) = Mandelbrot::new(&config).iterate_final(&c);
+        assert_eq!(iterations, config.mandelbrot_cfg.max_iterations + 1);
+        config.mandelbrot_cfg.z0 = Complex::new(1.5, 0.0);
+        let (iterations, _) = Mandelbrot::new(&config).iterate_final(&c);
+        assert_eq!(iterations, 1);
+    }
+
+    #[test]
+    fn test_z0_default() {
+        // configs stored before z0 was added start at the critical point
+        let cfg = MandelbrotCfg {
+            z0: Complex::new(1.5, 0.0),
+            ..MandelbrotCfg::default()
+        };
+        let mut value = serde_json::to_value(&cfg).expect("serialized config");
+        value.as_object_mut().expect("config object").remove("z0");
+        let cfg: MandelbrotCfg = serde_json::from_value(value).expect("valid config");
+        assert!(cfg.z0 == critical_point());
+    }
+
+    #[test]
+    fn test_cache_key_angles() {
+        // above the angle escape radius both coloring modes iterate alike but only one
+        // keeps the angles
+        let mut config = Config::std_cfg();
+        config.mandelbrot_cfg.bailout.radius = 200.0;
+        let iterations = Mandelbrot::new(&config).cache_key();
+        config.coloring = ColoringMode::FieldLines;
+        assert_ne!(Mandelbrot::new(&config).cache_key(), iterations);
+    }
+}
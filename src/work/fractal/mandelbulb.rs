@@ -0,0 +1,105 @@
+use super::{Camera, DistanceEstimator};
+use crate::work::vec3::Vec3;
+use serde::{Deserialize, Serialize};
+
+pub const MANDELBULB_DEFAULT_POWER: f64 = 8.0;
+pub const MANDELBULB_DEFAULT_ITERATIONS: u32 = 12;
+
+const BAILOUT: f64 = 2.0;
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct MandelbulbCfg {
+    pub power: f64,
+    pub max_iterations: u32,
+    pub camera: Camera,
+    pub color_cfg_name: Option<String>,
+}
+
+impl MandelbulbCfg {
+    pub fn default_camera() -> Camera {
+        Camera::looking_at_origin(4.0, 30.0, 20.0, 45.0)
+    }
+}
+
+impl Default for MandelbulbCfg {
+    fn default() -> Self {
+        Self {
+            power: MANDELBULB_DEFAULT_POWER,
+            max_iterations: MANDELBULB_DEFAULT_ITERATIONS,
+            camera: MandelbulbCfg::default_camera(),
+            color_cfg_name: None,
+        }
+    }
+}
+
+/// The Mandelbulb: z -> z^n + c with the power taken in spherical coordinates
+pub struct Mandelbulb {
+    power: f64,
+    max_iterations: u32,
+}
+
+impl Mandelbulb {
+    pub fn new(cfg: &MandelbulbCfg) -> Self {
+        info!(
+            "creating mandelbulb with power: {}, iterations: {}, camera: {:?}",
+            cfg.power, cfg.max_iterations, cfg.camera
+        );
+        Self {
+            power: cfg.power,
+            max_iterations: cfg.max_iterations,
+        }
+    }
+}
+
+impl DistanceEstimator for Mandelbulb {
+    /// The distance estimate 0.5 * ln(r) * r / dr with the running derivative dr, the palette
+    /// index is the smallest radius of the orbit
+    fn estimate(&self, point: &Vec3) -> (f64, f64) {
+        let mut z = *point;
+        let mut dr = 1.0;
+        let mut radius = z.length();
+        let mut trap = radius;
+
+        for _ in 0..self.max_iterations {
+            if radius > BAILOUT || radius == 0.0 {
+                break;
+            }
+            let theta = (z.z() / radius).acos() * self.power;
+            let phi = z.y().atan2(z.x()) * self.power;
+            dr = radius.powf(self.power - 1.0) * self.power * dr + 1.0;
+            z = Vec3::new(
+                theta.sin() * phi.cos(),
+                phi.sin() * theta.sin(),
+                theta.cos(),
+            ) * radius.powf(self.power)
+                + *point;
+            radius = z.length();
+            trap = trap.min(radius);
+        }
+
+        if radius == 0.0 {
+            (0.0, 0.0)
+        } else {
+            (0.5 * radius.ln() * radius / dr, trap.min(1.0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Mandelbulb, MandelbulbCfg};
+    use crate::work::fractal::DistanceEstimator;
+    use crate::work::vec3::Vec3;
+
+    #[test]
+    fn test_estimate() {
+        let bulb = Mandelbulb::new(&MandelbulbCfg::default());
+        // the origin and points close to it are part of the set
+        assert!(bulb.estimate(&Vec3::new(0.0, 0.0, 0.0)).0 <= 0.0);
+        assert!(bulb.estimate(&Vec3::new(0.1, 0.1, 0.1)).0 <= 0.0);
+        // the bulb fits into a sphere of radius 1.2
+        let (distance, trap) = bulb.estimate(&Vec3::new(0.0, 0.0, 3.0));
+        assert!(distance > 1.0 && distance < 3.0, "{}", distance);
+        assert!((0.0..=1.0).contains(&trap));
+    }
+}
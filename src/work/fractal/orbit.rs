@@ -0,0 +1,174 @@
+use super::{AttractorMap, FlameMap, FractalType, IfsMap, MAX_DURATION};
+use crate::components::root::Config;
+use crate::work::{complex::Complex, density::DensityBuffer, stats::Stats};
+
+// the first points of the orbit are not plotted until it has settled on the attractor
+const SETTLE_ITERATIONS: u32 = 100;
+const CHECK_INTERVAL: u32 = 10_000;
+const START: (f64, f64) = (0.1, 0.1);
+
+/// A map of the plane whose orbit of a single point is plotted, such as a strange attractor or
+/// an iterated function system
+pub trait OrbitMap {
+    /// Map a point of the orbit to the next one
+    fn next(&mut self, x: f64, y: f64) -> (f64, f64);
+
+    /// The palette index in 0.0..=1.0 of the last point returned by `next`, maps without
+    /// colors are drawn by density only
+    fn color(&self) -> Option<f32> {
+        None
+    }
+}
+
+/// Follows a single orbit and accumulates its points in a density buffer.
+/// Every call to calculate adds the points of one time slice, so the picture gets denser
+/// over time. Unlike the escape time fractals the y axis points up.
+pub struct OrbitCalculator {
+    map: Box<dyn OrbitMap>,
+    scale: Complex,
+    x_min: f64,
+    y_max: f64,
+    x: f64,
+    y: f64,
+    points: u32,
+    max_points: u32,
+    density: DensityBuffer,
+}
+
+impl OrbitCalculator {
+    pub fn new(config: &Config, canvas_width: u32, canvas_height: u32) -> Self {
+        let (mut map, x_min, x_max, max_points, gamma): (
+            Box<dyn OrbitMap>,
+            Complex,
+            Complex,
+            u32,
+            f64,
+        ) = match config.active_config {
+            FractalType::Attractor => (
+                Box::new(AttractorMap::new(&config.attractor_cfg)),
+                config.attractor_cfg.x_min,
+                config.attractor_cfg.x_max,
+                config.attractor_cfg.max_points,
+                1.0,
+            ),
+            FractalType::Ifs => (
+                Box::new(IfsMap::new(&config.ifs_cfg)),
+                config.ifs_cfg.x_min,
+                config.ifs_cfg.x_max,
+                config.ifs_cfg.max_points,
+                1.0,
+            ),
+            FractalType::Flame => (
+                Box::new(FlameMap::new(&config.flame_cfg)),
+                config.flame_cfg.x_min,
+                config.flame_cfg.x_max,
+                config.flame_cfg.max_points,
+                config.flame_cfg.gamma,
+            ),
+            fractal_type => panic!("{:?} is not an orbit fractal", fractal_type),
+        };
+
+        let (mut x, mut y) = START;
+        for _ in 0..SETTLE_ITERATIONS {
+            let next = map.next(x, y);
+            x = next.0;
+            y = next.1;
+        }
+
+        Self {
+            map,
+            scale: Complex::new(
+                (x_max.real() - x_min.real()) / f64::from(canvas_width),
+                (x_max.imag() - x_min.imag()) / f64::from(canvas_height),
+            ),
+            x_min: x_min.real(),
+            y_max: x_max.imag(),
+            x,
+            y,
+            points: 0,
+            max_points,
+            density: DensityBuffer::new(canvas_width, canvas_height).with_gamma(gamma),
+        }
+    }
+
+    pub fn calculate(&mut self, stats: Option<&mut Stats>) -> &DensityBuffer {
+        let performance = web_sys::window()
+            .expect("Window not found")
+            .performance()
+            .expect("performance should be available");
+
+        let start = performance.now();
+        let start_points = self.points;
+
+        while self.points < self.max_points {
+            let batch = CHECK_INTERVAL.min(self.max_points - self.points);
+            for _ in 0..batch {
+                let (x, y) = self.map.next(self.x, self.y);
+                self.x = x;
+                self.y = y;
+                let (canvas_x, canvas_y) = (
+                    (x - self.x_min) / self.scale.real(),
+                    (self.y_max - y) / self.scale.imag(),
+                );
+                match self.map.color() {
+                    Some(color) => self.density.add_colored(canvas_x, canvas_y, color),
+                    None => self.density.add(canvas_x, canvas_y),
+                };
+            }
+            self.points += batch;
+
+            if performance.now() - start >= MAX_DURATION {
+                break;
+            }
+        }
+
+        if let Some(stats) = stats {
+            let points = (self.points - start_points) as usize;
+            stats.update(points, points, start);
+        }
+
+        &self.density
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.points >= self.max_points
+    }
+}
+
+/// xorshift64 pseudo random numbers, good enough to pick maps in the chaos game
+pub struct XorShiftRng {
+    state: u64,
+}
+
+impl XorShiftRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    /// A random number in 0.0..1.0
+    #[allow(clippy::cast_precision_loss)]
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::XorShiftRng;
+
+    #[test]
+    fn test_rng_range() {
+        let mut rng = XorShiftRng::new(0);
+        let mut sum = 0.0;
+        for _ in 0..10_000 {
+            let value = rng.next_f64();
+            assert!((0.0..1.0).contains(&value));
+            sum += value;
+        }
+        assert!((sum / 10_000.0 - 0.5).abs() < 0.02);
+    }
+}
@@ -0,0 +1,119 @@
+use super::{Camera, DistanceEstimator};
+use crate::work::{quaternion::Quaternion, vec3::Vec3};
+use serde::{Deserialize, Serialize};
+
+pub const QUATERNION_JULIA_DEFAULT_ITERATIONS: u32 = 16;
+
+const BAILOUT: f64 = 4.0;
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct QuaternionJuliaCfg {
+    pub c: Quaternion,
+    /// the k component of all points of the rendered 3D slice
+    pub slice: f64,
+    pub max_iterations: u32,
+    pub camera: Camera,
+    pub color_cfg_name: Option<String>,
+}
+
+impl QuaternionJuliaCfg {
+    pub fn default_c() -> Quaternion {
+        Quaternion::new(-0.2, 0.8, 0.0, 0.0)
+    }
+
+    pub fn default_camera() -> Camera {
+        Camera::looking_at_origin(4.0, 30.0, 20.0, 45.0)
+    }
+}
+
+impl Default for QuaternionJuliaCfg {
+    fn default() -> Self {
+        Self {
+            c: QuaternionJuliaCfg::default_c(),
+            slice: 0.0,
+            max_iterations: QUATERNION_JULIA_DEFAULT_ITERATIONS,
+            camera: QuaternionJuliaCfg::default_camera(),
+            color_cfg_name: None,
+        }
+    }
+}
+
+/// The Julia set of q -> q^2 + c over the quaternions, the point (x, y, z) of the 3D slice is
+/// the quaternion x + i y + j z + k slice
+pub struct QuaternionJulia {
+    c: Quaternion,
+    slice: f64,
+    max_iterations: u32,
+}
+
+impl QuaternionJulia {
+    pub fn new(cfg: &QuaternionJuliaCfg) -> Self {
+        info!(
+            "creating quaternion julia set with c: {}, slice: {}, iterations: {}, camera: {:?}",
+            cfg.c, cfg.slice, cfg.max_iterations, cfg.camera
+        );
+        Self {
+            c: cfg.c,
+            slice: cfg.slice,
+            max_iterations: cfg.max_iterations,
+        }
+    }
+}
+
+impl DistanceEstimator for QuaternionJulia {
+    /// The distance estimate 0.5 * |q| * ln|q| / |q'| with the running derivative
+    /// q' -> 2 q q', the palette index is the smallest norm of the orbit
+    fn estimate(&self, point: &Vec3) -> (f64, f64) {
+        let mut q = Quaternion::new(point.x(), point.y(), point.z(), self.slice);
+        let mut derivative = 1.0;
+        let mut square_norm = q.square_length();
+        let mut trap = square_norm;
+
+        for _ in 0..self.max_iterations {
+            if square_norm > BAILOUT * BAILOUT {
+                break;
+            }
+            derivative *= 2.0 * square_norm.sqrt();
+            q = q.square() + self.c;
+            square_norm = q.square_length();
+            trap = trap.min(square_norm);
+        }
+
+        let norm = square_norm.sqrt();
+        if norm == 0.0 || derivative == 0.0 {
+            (0.0, 0.0)
+        } else {
+            (0.5 * norm * norm.ln() / derivative, trap.sqrt().min(1.0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{QuaternionJulia, QuaternionJuliaCfg};
+    use crate::work::fractal::DistanceEstimator;
+    use crate::work::quaternion::Quaternion;
+    use crate::work::vec3::Vec3;
+
+    #[test]
+    fn test_estimate() {
+        let julia = QuaternionJulia::new(&QuaternionJuliaCfg::default());
+        // the orbit of the origin stays bounded for this c
+        assert!(julia.estimate(&Vec3::new(0.0, 0.0, 0.0)).0 <= 0.0);
+        let (distance, trap) = julia.estimate(&Vec3::new(0.0, 0.0, 3.0));
+        assert!(distance > 0.5 && distance < 3.0, "{}", distance);
+        assert!((0.0..=1.0).contains(&trap));
+    }
+
+    #[test]
+    fn test_slice_matches_complex_julia() {
+        // in the plane j = k = 0 the quaternion iteration is the complex iteration, c = -1
+        // has the period 2 orbit 0 -> -1 -> 0
+        let julia = QuaternionJulia::new(&QuaternionJuliaCfg {
+            c: Quaternion::new(-1.0, 0.0, 0.0, 0.0),
+            ..QuaternionJuliaCfg::default()
+        });
+        assert!(julia.estimate(&Vec3::new(0.0, 0.0, 0.0)).0 <= 0.0);
+        assert!(julia.estimate(&Vec3::new(2.0, 0.0, 0.0)).0 > 0.0);
+    }
+}
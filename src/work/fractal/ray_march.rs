@@ -0,0 +1,309 @@
+use super::{FractalType, Mandelbulb, QuaternionJulia, MAX_DURATION};
+use crate::components::root::Config;
+use crate::work::{stats::Stats, vec3::Vec3};
+use serde::{Deserialize, Serialize};
+
+const MAX_STEPS: u32 = 256;
+const MAX_DISTANCE: f64 = 20.0;
+/// the smallest distance that counts as a hit, the actual limit grows with the size of a pixel
+/// at the distance of the ray
+const MIN_HIT_DISTANCE: f64 = 1e-5;
+const AMBIENT: f32 = 0.15;
+const AO_SAMPLES: u32 = 5;
+const AO_STEP: f64 = 0.02;
+const AO_STRENGTH: f64 = 6.0;
+
+/// Position and orientation of the camera of the 3D fractals
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub struct Camera {
+    pub position: Vec3,
+    /// rotation around the y axis in degrees, 0 looks along the z axis
+    pub yaw: f64,
+    /// rotation above the horizon in degrees
+    pub pitch: f64,
+    /// horizontal field of view in degrees
+    pub fov: f64,
+}
+
+impl Camera {
+    /// A camera at `distance` from the origin looking at it
+    pub fn looking_at_origin(distance: f64, yaw: f64, pitch: f64, fov: f64) -> Self {
+        let mut camera = Self {
+            position: Vec3::new(0.0, 0.0, 0.0),
+            yaw,
+            pitch,
+            fov,
+        };
+        camera.position = -camera.forward() * distance;
+        camera
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        let (yaw, pitch) = (self.yaw.to_radians(), self.pitch.to_radians());
+        Vec3::new(
+            pitch.cos() * yaw.sin(),
+            pitch.sin(),
+            pitch.cos() * yaw.cos(),
+        )
+    }
+
+    /// Rotate the camera around the origin keeping its distance, the camera ends up looking at
+    /// the origin
+    pub fn orbit(&self, delta_yaw: f64, delta_pitch: f64) -> Self {
+        Self::looking_at_origin(
+            self.position.length(),
+            (self.yaw + delta_yaw) % 360.0,
+            (self.pitch + delta_pitch).clamp(-89.0, 89.0),
+            self.fov,
+        )
+    }
+
+    /// The forward, right and up vectors of the view
+    fn basis(&self) -> (Vec3, Vec3, Vec3) {
+        let forward = self.forward();
+        let right = Vec3::new(0.0, 1.0, 0.0).cross(&forward).normalized();
+        let up = forward.cross(&right);
+        (forward, right, up)
+    }
+}
+
+/// A 3D fractal given by a distance estimator
+pub trait DistanceEstimator {
+    /// A lower bound of the distance from `point` to the fractal and a palette index in
+    /// 0.0..=1.0 taken from an orbit trap
+    fn estimate(&self, point: &Vec3) -> (f64, f64);
+}
+
+/// Brightness and palette index per pixel, `None` for pixels whose ray missed the fractal or
+/// that are not rendered yet
+pub struct ShadedImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<Option<(f32, f32)>>,
+}
+
+impl ShadedImage {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![None; (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Brightness and palette index of pixel `idx`
+    #[inline]
+    pub fn pixel(&self, idx: usize) -> Option<(f32, f32)> {
+        self.pixels[idx]
+    }
+}
+
+/// Renders a 3D fractal by sphere tracing a ray per pixel, shaded with lambert lighting from a
+/// light attached to the camera and ambient occlusion. Every call to calculate renders the
+/// rows of one time slice from the top.
+pub struct RayMarchCalculator {
+    estimator: Box<dyn DistanceEstimator>,
+    position: Vec3,
+    forward: Vec3,
+    right: Vec3,
+    up: Vec3,
+    light: Vec3,
+    /// half the width of the view plane at distance 1
+    view_width: f64,
+    y_curr: u32,
+    image: ShadedImage,
+}
+
+impl RayMarchCalculator {
+    pub fn new(config: &Config, canvas_width: u32, canvas_height: u32) -> Self {
+        let (estimator, camera): (Box<dyn DistanceEstimator>, Camera) = match config.active_config {
+            FractalType::Mandelbulb => (
+                Box::new(Mandelbulb::new(&config.mandelbulb_cfg)),
+                config.mandelbulb_cfg.camera,
+            ),
+            FractalType::QuaternionJulia => (
+                Box::new(QuaternionJulia::new(&config.quaternion_julia_cfg)),
+                config.quaternion_julia_cfg.camera,
+            ),
+            fractal_type => panic!("{:?} is not a 3D fractal", fractal_type),
+        };
+
+        let (forward, right, up) = camera.basis();
+        Self {
+            estimator,
+            position: camera.position,
+            forward,
+            right,
+            up,
+            light: (up * 0.6 + right * 0.4 - forward * 0.5).normalized(),
+            view_width: (camera.fov.clamp(1.0, 179.0) * 0.5).to_radians().tan(),
+            y_curr: 0,
+            image: ShadedImage::new(canvas_width, canvas_height),
+        }
+    }
+
+    pub fn calculate(&mut self, stats: Option<&mut Stats>) -> &ShadedImage {
+        let performance = web_sys::window()
+            .expect("Window not found")
+            .performance()
+            .expect("performance should be available");
+
+        let start = performance.now();
+        let mut points = 0;
+        let mut iterations = 0;
+
+        while self.y_curr < self.image.height {
+            for x in 0..self.image.width {
+                let (pixel, steps) = self.render_pixel(x, self.y_curr);
+                self.image.pixels[(self.y_curr * self.image.width + x) as usize] = pixel;
+                iterations += steps as usize;
+            }
+            points += self.image.width as usize;
+            self.y_curr += 1;
+
+            if performance.now() - start >= MAX_DURATION {
+                break;
+            }
+        }
+
+        if let Some(stats) = stats {
+            stats.update(iterations, points, start);
+        }
+
+        &self.image
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.y_curr >= self.image.height
+    }
+
+    /// Shade pixel (x, y), returns the pixel and the number of march steps
+    #[allow(clippy::cast_possible_truncation)]
+    fn render_pixel(&self, x: u32, y: u32) -> (Option<(f32, f32)>, u32) {
+        let width = f64::from(self.image.width);
+        let height = f64::from(self.image.height);
+        let u = (2.0 * (f64::from(x) + 0.5) / width - 1.0) * self.view_width;
+        let v = (1.0 - 2.0 * (f64::from(y) + 0.5) / height) * self.view_width * height / width;
+        let direction = (self.forward + self.right * u + self.up * v).normalized();
+        let pixel_size = 2.0 * self.view_width / width;
+
+        let (hit, steps) = march(
+            self.estimator.as_ref(),
+            &self.position,
+            &direction,
+            pixel_size,
+        );
+        let pixel = hit.map(|(distance, color)| {
+            let point = self.position + direction * distance;
+            let epsilon = (distance * pixel_size).max(MIN_HIT_DISTANCE);
+            let normal = normal(self.estimator.as_ref(), &point, epsilon);
+            let diffuse = normal.dot(&self.light).max(0.0) as f32;
+            let occlusion = ambient_occlusion(self.estimator.as_ref(), &point, &normal) as f32;
+            (
+                (AMBIENT + (1.0 - AMBIENT) * diffuse) * occlusion,
+                color as f32,
+            )
+        });
+        (pixel, steps)
+    }
+}
+
+/// Sphere tracing: step along the ray by the estimated distance until the distance is below the
+/// size of a pixel. Returns the distance along the ray and the palette index of the hit.
+fn march(
+    estimator: &dyn DistanceEstimator,
+    origin: &Vec3,
+    direction: &Vec3,
+    pixel_size: f64,
+) -> (Option<(f64, f64)>, u32) {
+    let mut distance = 0.0;
+    for step in 0..MAX_STEPS {
+        let (estimate, color) = estimator.estimate(&(*origin + *direction * distance));
+        if estimate < (distance * pixel_size).max(MIN_HIT_DISTANCE) {
+            return (Some((distance, color)), step + 1);
+        }
+        distance += estimate;
+        if distance > MAX_DISTANCE {
+            return (None, step + 1);
+        }
+    }
+    (None, MAX_STEPS)
+}
+
+/// The surface normal from the central differences of the distance estimate
+fn normal(estimator: &dyn DistanceEstimator, point: &Vec3, epsilon: f64) -> Vec3 {
+    let diff = |offset: Vec3| {
+        estimator.estimate(&(*point + offset)).0 - estimator.estimate(&(*point - offset)).0
+    };
+    Vec3::new(
+        diff(Vec3::new(epsilon, 0.0, 0.0)),
+        diff(Vec3::new(0.0, epsilon, 0.0)),
+        diff(Vec3::new(0.0, 0.0, epsilon)),
+    )
+    .normalized()
+}
+
+/// 1.0 for an open surface, smaller if the fractal is close along the normal
+fn ambient_occlusion(estimator: &dyn DistanceEstimator, point: &Vec3, normal: &Vec3) -> f64 {
+    let mut occlusion = 0.0;
+    let mut weight = 0.5;
+    for sample in 1..=AO_SAMPLES {
+        let offset = AO_STEP * f64::from(sample);
+        let distance = estimator.estimate(&(*point + *normal * offset)).0;
+        occlusion += weight * (offset - distance).max(0.0);
+        weight *= 0.5;
+    }
+    (1.0 - AO_STRENGTH * occlusion).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{march, normal, Camera, DistanceEstimator};
+    use crate::work::vec3::Vec3;
+
+    struct Sphere;
+
+    impl DistanceEstimator for Sphere {
+        fn estimate(&self, point: &Vec3) -> (f64, f64) {
+            (point.length() - 1.0, 0.5)
+        }
+    }
+
+    #[test]
+    fn test_camera() {
+        let camera = Camera::looking_at_origin(3.0, 0.0, 0.0, 60.0);
+        assert!((camera.position - Vec3::new(0.0, 0.0, -3.0)).length() < 1e-12);
+        let (forward, right, up) = camera.basis();
+        assert!((forward - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-12);
+        assert!((right - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-12);
+        assert!((up - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-12);
+
+        let orbited = camera.orbit(90.0, 0.0);
+        assert!((orbited.position - Vec3::new(-3.0, 0.0, 0.0)).length() < 1e-12);
+        assert!((orbited.position.length() - 3.0).abs() < 1e-12);
+        assert_eq!(camera.orbit(0.0, 120.0).pitch, 89.0);
+    }
+
+    #[test]
+    fn test_march_sphere() {
+        let origin = Vec3::new(0.0, 0.0, -3.0);
+        let (hit, _) = march(&Sphere, &origin, &Vec3::new(0.0, 0.0, 1.0), 0.001);
+        let (distance, color) = hit.expect("ray should hit the sphere");
+        assert!((distance - 2.0).abs() < 1e-2);
+        assert_eq!(color, 0.5);
+
+        let (hit, _) = march(&Sphere, &origin, &Vec3::new(0.0, 1.0, 0.0), 0.001);
+        assert!(hit.is_none());
+
+        let normal = normal(&Sphere, &Vec3::new(0.0, 0.0, -1.0), 1e-5);
+        assert!((normal - Vec3::new(0.0, 0.0, -1.0)).length() < 1e-6);
+    }
+}
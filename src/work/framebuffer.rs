@@ -0,0 +1,130 @@
+/// An RGBA image of the canvas kept in wasm memory. Drawing writes into the buffer, only the
+/// rows changed since the last blit have to be copied to the canvas.
+pub struct FrameBuffer {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+    /// first and one past the last row changed since the last `take_dirty_rows`
+    dirty: Option<(u32, u32)>,
+}
+
+impl FrameBuffer {
+    pub fn new(width: u32, height: u32, color: [u8; 4]) -> Self {
+        let mut res = Self {
+            width: 0,
+            height: 0,
+            data: Vec::new(),
+            dirty: None,
+        };
+        res.clear(width, height, color);
+        res
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Resize the buffer and fill it with `color`
+    pub fn clear(&mut self, width: u32, height: u32, color: [u8; 4]) {
+        self.width = width;
+        self.height = height;
+        self.data.clear();
+        self.data.reserve((width * height * 4) as usize);
+        for _ in 0..width * height {
+            self.data.extend_from_slice(&color);
+        }
+        self.mark_dirty(0, height);
+    }
+
+    /// Fill the square of `size` pixels at `x`, `y`, clipped at the edges of the buffer
+    #[inline]
+    pub fn fill_block(&mut self, x: u32, y: u32, size: u32, color: &[u8; 4]) {
+        let (x_end, y_end) = ((x + size).min(self.width), (y + size).min(self.height));
+        if x >= x_end || y >= y_end {
+            return;
+        }
+        for row in y..y_end {
+            let start = ((row * self.width + x) * 4) as usize;
+            let end = ((row * self.width + x_end) * 4) as usize;
+            for pixel in self.data[start..end].chunks_exact_mut(4) {
+                pixel.copy_from_slice(color);
+            }
+        }
+        self.mark_dirty(y, y_end);
+    }
+
+    /// Set every pixel to the color `color_of` returns for its index, resizing the buffer to
+    /// `width` by `height` if needed
+    pub fn fill_with<F>(&mut self, width: u32, height: u32, mut color_of: F)
+    where
+        F: FnMut(usize) -> [u8; 4],
+    {
+        if width != self.width || height != self.height {
+            self.width = width;
+            self.height = height;
+            self.data.resize((width * height * 4) as usize, 0);
+        }
+        for (idx, pixel) in self.data.chunks_exact_mut(4).enumerate() {
+            pixel.copy_from_slice(&color_of(idx));
+        }
+        self.mark_dirty(0, height);
+    }
+
+    /// The rows changed since the last call, as first and one past the last row
+    pub fn take_dirty_rows(&mut self) -> Option<(u32, u32)> {
+        self.dirty.take()
+    }
+
+    /// The pixels of the rows `start` up to `end`
+    pub fn rows(&self, start: u32, end: u32) -> &[u8] {
+        &self.data[(start * self.width * 4) as usize..(end * self.width * 4) as usize]
+    }
+
+    #[inline]
+    fn mark_dirty(&mut self, start: u32, end: u32) {
+        self.dirty = Some(match self.dirty {
+            Some((dirty_start, dirty_end)) => (dirty_start.min(start), dirty_end.max(end)),
+            None => (start, end),
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FrameBuffer;
+
+    const BLACK: [u8; 4] = [0, 0, 0, 0xFF];
+    const RED: [u8; 4] = [0xFF, 0, 0, 0xFF];
+
+    #[test]
+    fn test_fill_block() {
+        let mut frame = FrameBuffer::new(4, 3, BLACK);
+        assert_eq!(frame.take_dirty_rows(), Some((0, 3)));
+        assert_eq!(frame.take_dirty_rows(), None);
+
+        frame.fill_block(3, 1, 2, &RED);
+        assert_eq!(frame.take_dirty_rows(), Some((1, 3)));
+        assert_eq!(&frame.rows(0, 1)[12..16], &BLACK);
+        assert_eq!(&frame.rows(1, 2)[8..16], &[BLACK, RED].concat()[..]);
+        assert_eq!(&frame.rows(2, 3)[12..16], &RED);
+
+        frame.fill_block(0, 0, 1, &RED);
+        frame.fill_block(0, 2, 1, &RED);
+        assert_eq!(frame.take_dirty_rows(), Some((0, 3)));
+        frame.fill_block(4, 0, 1, &RED);
+        assert_eq!(frame.take_dirty_rows(), None);
+    }
+
+    #[test]
+    fn test_fill_with() {
+        let mut frame = FrameBuffer::new(2, 2, BLACK);
+        frame.take_dirty_rows();
+        frame.fill_with(3, 1, |idx| [idx as u8, 0, 0, 0xFF]);
+        assert_eq!(frame.width(), 3);
+        assert_eq!(frame.take_dirty_rows(), Some((0, 1)));
+        assert_eq!(
+            frame.rows(0, 1),
+            &[0, 0, 0, 0xFF, 1, 0, 0, 0xFF, 2, 0, 0, 0xFF]
+        );
+    }
+}
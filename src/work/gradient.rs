@@ -0,0 +1,366 @@
+use super::colors::{HslColor, OkLabColor, OkLchColor, RgbColor};
+use serde::{Deserialize, Serialize};
+
+/// The color space the colors between two stops are interpolated in
+#[derive(PartialEq, Clone, Copy, Default, Serialize, Deserialize, Debug)]
+pub enum Interpolation {
+    #[default]
+    Rgb,
+    /// Along the shorter way around the hue circle
+    Hsl,
+    /// Perceptually uniform, avoids the muddy middles of RGB
+    OkLab,
+    /// Perceptually uniform along the shorter way around the hue circle
+    OkLch,
+}
+
+impl Interpolation {
+    pub const ALL: [Interpolation; 4] = [
+        Interpolation::Rgb,
+        Interpolation::Hsl,
+        Interpolation::OkLab,
+        Interpolation::OkLch,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Interpolation::Rgb => "RGB",
+            Interpolation::Hsl => "HSL",
+            Interpolation::OkLab => "OKLab",
+            Interpolation::OkLch => "OKLCh",
+        }
+    }
+
+    /// The color `factor` (0.0..=1.0) of the way from `from` to `to`
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn mix(self, from: &RgbColor, to: &RgbColor, factor: f32) -> RgbColor {
+        let lerp = |from: f32, to: f32| from + (to - from) * factor;
+        match self {
+            Interpolation::Rgb => {
+                let channel = |from: u8, to: u8| lerp(f32::from(from), f32::from(to)).round() as u8;
+                RgbColor::new(
+                    channel(from.red(), to.red()),
+                    channel(from.green(), to.green()),
+                    channel(from.blue(), to.blue()),
+                )
+            }
+            Interpolation::Hsl => {
+                let (from, to) = (from.to_hsl(), to.to_hsl());
+                let hue_delta = shortest_hue_delta(from.hue(), to.hue());
+                HslColor::new(
+                    (from.hue() + hue_delta * factor).rem_euclid(360.0),
+                    lerp(from.saturation(), to.saturation()),
+                    lerp(from.lightness(), to.lightness()),
+                )
+                .to_rgb()
+            }
+            Interpolation::OkLab => {
+                let (from, to) = (from.to_oklab(), to.to_oklab());
+                OkLabColor::new(
+                    lerp(from.lightness(), to.lightness()),
+                    lerp(from.a(), to.a()),
+                    lerp(from.b(), to.b()),
+                )
+                .to_rgb()
+            }
+            Interpolation::OkLch => {
+                let (from, to) = (from.to_oklch(), to.to_oklch());
+                OkLchColor::new(
+                    lerp(from.lightness(), to.lightness()),
+                    lerp(from.chroma(), to.chroma()),
+                    from.hue() + shortest_hue_delta(from.hue(), to.hue()) * factor,
+                )
+                .to_rgb()
+            }
+        }
+    }
+}
+
+/// The signed difference of two hues in degrees along the shorter way around the circle
+fn shortest_hue_delta(from: f32, to: f32) -> f32 {
+    let delta = to - from;
+    if delta > 180.0 {
+        delta - 360.0
+    } else if delta < -180.0 {
+        delta + 360.0
+    } else {
+        delta
+    }
+}
+
+/// How positions outside of 0.0..=1.0 map onto the gradient
+#[derive(PartialEq, Clone, Copy, Default, Serialize, Deserialize, Debug)]
+pub enum RepeatMode {
+    /// Positions beyond the ends get the color of the end
+    #[default]
+    Clamp,
+    /// The gradient starts over at every whole number
+    Repeat,
+    /// The gradient runs back and forth
+    Mirror,
+}
+
+impl RepeatMode {
+    pub const ALL: [RepeatMode; 3] = [RepeatMode::Clamp, RepeatMode::Repeat, RepeatMode::Mirror];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RepeatMode::Clamp => "Clamp",
+            RepeatMode::Repeat => "Repeat",
+            RepeatMode::Mirror => "Mirror",
+        }
+    }
+
+    /// Map `position` into 0.0..=1.0
+    #[inline]
+    pub fn apply(self, position: f32) -> f32 {
+        match self {
+            RepeatMode::Clamp => position.clamp(0.0, 1.0),
+            RepeatMode::Repeat => {
+                let wrapped = position.rem_euclid(1.0);
+                // keep the end color at the end of every repetition
+                if wrapped == 0.0 && position > 0.0 {
+                    1.0
+                } else {
+                    wrapped
+                }
+            }
+            RepeatMode::Mirror => {
+                let wrapped = position.rem_euclid(2.0);
+                if wrapped > 1.0 {
+                    2.0 - wrapped
+                } else {
+                    wrapped
+                }
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct ColorStop {
+    /// Position of the stop in 0.0..=1.0
+    pub position: f32,
+    pub color: RgbColor,
+    /// The interpolation towards the next stop
+    #[serde(default)]
+    pub interpolation: Interpolation,
+}
+
+impl ColorStop {
+    pub fn new(position: f32, color: RgbColor, interpolation: Interpolation) -> Self {
+        Self {
+            position,
+            color,
+            interpolation,
+        }
+    }
+}
+
+/// A palette of any number of color stops, the stops are kept sorted by position
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Gradient {
+    stops: Vec<ColorStop>,
+    repeat: RepeatMode,
+    /// The number of times the gradient is run through from start to end of the palette
+    scale: f32,
+}
+
+impl Gradient {
+    pub fn new(mut stops: Vec<ColorStop>, repeat: RepeatMode, scale: f32) -> Self {
+        for stop in &mut stops {
+            stop.position = stop.position.clamp(0.0, 1.0);
+        }
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+        Self {
+            stops,
+            repeat,
+            scale,
+        }
+    }
+
+    pub fn stops(&self) -> &[ColorStop] {
+        &self.stops
+    }
+
+    pub fn repeat(&self) -> RepeatMode {
+        self.repeat
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// The color at `percent` (0.0..=1.0) of the palette
+    #[inline]
+    pub fn percent_of(&self, percent: f32) -> RgbColor {
+        self.color_at(self.repeat.apply(percent * self.scale))
+    }
+
+    /// The color at `position` (0.0..=1.0) between the stops, ignoring repeat mode and scale
+    #[inline]
+    pub fn color_at(&self, position: f32) -> RgbColor {
+        match self.stops.iter().position(|stop| stop.position > position) {
+            Some(0) => self.stops[0].color.clone(),
+            Some(idx) => {
+                let (from, to) = (&self.stops[idx - 1], &self.stops[idx]);
+                from.interpolation.mix(
+                    &from.color,
+                    &to.color,
+                    (position - from.position) / (to.position - from.position),
+                )
+            }
+            None => self
+                .stops
+                .last()
+                .map_or_else(|| RgbColor::new(0, 0, 0), |stop| stop.color.clone()),
+        }
+    }
+}
+
+impl Default for Gradient {
+    /// The classic blue and gold palette of many fractal programs
+    fn default() -> Self {
+        let stop = |position, red, green, blue| {
+            ColorStop::new(
+                position,
+                RgbColor::new(red, green, blue),
+                Interpolation::OkLab,
+            )
+        };
+        Self::new(
+            vec![
+                stop(0.0, 0, 7, 100),
+                stop(0.16, 32, 107, 203),
+                stop(0.42, 237, 255, 255),
+                stop(0.6425, 255, 170, 0),
+                stop(0.8575, 0, 2, 0),
+                stop(1.0, 0, 7, 100),
+            ],
+            RepeatMode::Clamp,
+            1.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ColorStop, Gradient, Interpolation, RepeatMode};
+    use crate::work::colors::{ColorRange, RgbColor};
+
+    fn black_to_white(interpolation: Interpolation, repeat: RepeatMode, scale: f32) -> Gradient {
+        Gradient::new(
+            vec![
+                ColorStop::new(1.0, RgbColor::new(255, 255, 255), interpolation),
+                ColorStop::new(0.0, RgbColor::new(0, 0, 0), interpolation),
+            ],
+            repeat,
+            scale,
+        )
+    }
+
+    #[test]
+    fn test_stops() {
+        let gradient = Gradient::new(
+            vec![
+                ColorStop::new(0.5, RgbColor::new(0, 255, 0), Interpolation::Rgb),
+                ColorStop::new(0.25, RgbColor::new(255, 0, 0), Interpolation::Rgb),
+                ColorStop::new(0.75, RgbColor::new(0, 0, 255), Interpolation::Rgb),
+            ],
+            RepeatMode::Clamp,
+            1.0,
+        );
+        assert_eq!(gradient.stops()[0].position, 0.25);
+        assert_eq!(gradient.percent_of(0.0).to_string().as_str(), "#FF0000");
+        assert_eq!(gradient.percent_of(0.25).to_string().as_str(), "#FF0000");
+        assert_eq!(gradient.percent_of(0.375).to_string().as_str(), "#808000");
+        assert_eq!(gradient.percent_of(0.5).to_string().as_str(), "#00FF00");
+        assert_eq!(gradient.percent_of(0.625).to_string().as_str(), "#008080");
+        assert_eq!(gradient.percent_of(1.0).to_string().as_str(), "#0000FF");
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let (red, blue) = (RgbColor::new(255, 0, 0), RgbColor::new(0, 0, 255));
+        assert_eq!(
+            Interpolation::Rgb
+                .mix(&red, &blue, 0.5)
+                .to_string()
+                .as_str(),
+            "#800080"
+        );
+        // the shorter way from 0 to 240 degrees runs through magenta
+        assert_eq!(
+            Interpolation::Hsl
+                .mix(&red, &blue, 0.5)
+                .to_string()
+                .as_str(),
+            "#FF00FF"
+        );
+        let (black, white) = (RgbColor::new(0, 0, 0), RgbColor::new(255, 255, 255));
+        // the middle gray of OKLab is the perceived one, darker than the RGB average
+        let gray = Interpolation::OkLab.mix(&black, &white, 0.5);
+        assert!(gray.red() < 110 && gray.red() == gray.green() && gray.green() == gray.blue());
+        // OKLCh keeps the chroma where OKLab passes close to gray between opposite hues
+        let (yellow, purple) = (RgbColor::new(255, 255, 0), RgbColor::new(128, 0, 255));
+        assert!(
+            Interpolation::OkLch
+                .mix(&yellow, &purple, 0.5)
+                .to_oklab()
+                .to_oklch()
+                .chroma()
+                > Interpolation::OkLab
+                    .mix(&yellow, &purple, 0.5)
+                    .to_oklch()
+                    .chroma()
+        );
+        for interpolation in Interpolation::ALL.iter() {
+            assert_eq!(
+                interpolation.mix(&red, &blue, 0.0).to_string(),
+                red.to_string()
+            );
+            assert_eq!(
+                interpolation.mix(&red, &blue, 1.0).to_string(),
+                blue.to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_repeat_modes() {
+        let gradient = black_to_white(Interpolation::Rgb, RepeatMode::Clamp, 2.0);
+        assert_eq!(gradient.percent_of(0.25).to_string().as_str(), "#808080");
+        assert_eq!(gradient.percent_of(0.75).to_string().as_str(), "#FFFFFF");
+
+        let gradient = black_to_white(Interpolation::Rgb, RepeatMode::Repeat, 2.0);
+        assert_eq!(gradient.percent_of(0.5).to_string().as_str(), "#FFFFFF");
+        assert_eq!(gradient.percent_of(0.625).to_string().as_str(), "#404040");
+        assert_eq!(gradient.percent_of(1.0).to_string().as_str(), "#FFFFFF");
+
+        let gradient = black_to_white(Interpolation::Rgb, RepeatMode::Mirror, 2.0);
+        assert_eq!(gradient.percent_of(0.5).to_string().as_str(), "#FFFFFF");
+        assert_eq!(gradient.percent_of(0.625).to_string().as_str(), "#BFBFBF");
+        assert_eq!(gradient.percent_of(1.0).to_string().as_str(), "#000000");
+    }
+
+    #[test]
+    fn test_stored_palettes() {
+        // palettes stored before gradients existed
+        let range: ColorRange = serde_json::from_str(
+            r#"{"Hsl":{"dir":"Positive","start":{"hue":0.0,"saturation":1.0,"lightness":0.5},
+                "end":{"hue":300.0,"saturation":1.0,"lightness":0.5}}}"#,
+        )
+        .expect("stored HSL palette");
+        assert!(range == ColorRange::default());
+
+        let range = ColorRange::Gradient(black_to_white(
+            Interpolation::OkLab,
+            RepeatMode::Mirror,
+            3.0,
+        ));
+        let json = serde_json::to_string(&range).expect("serialized gradient");
+        let restored: ColorRange = serde_json::from_str(json.as_str()).expect("stored gradient");
+        assert!(restored == range);
+    }
+}
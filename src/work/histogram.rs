@@ -0,0 +1,105 @@
+/// The cumulative distribution of the iteration counts of an escape time image, mapping the
+/// counts through it spreads the palette evenly over the pixels
+pub struct Histogram {
+    cdf: Vec<f32>,
+}
+
+impl Histogram {
+    /// Values above `iterations` belong to pixels that did not escape and are not counted
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(values: impl Iterator<Item = u32>, iterations: u32) -> Self {
+        let mut counts = vec![0_u64; iterations as usize + 1];
+        for value in values {
+            if value <= iterations {
+                counts[value as usize] += 1;
+            }
+        }
+        let total = counts.iter().sum::<u64>().max(1) as f32;
+        let mut sum = 0;
+        let cdf = counts
+            .iter()
+            .map(|count| {
+                sum += count;
+                sum as f32 / total
+            })
+            .collect();
+        Self { cdf }
+    }
+
+    /// The share of the escaped pixels with at most `value` iterations, in 0.0..=1.0
+    #[inline]
+    pub fn percent(&self, value: u32) -> f32 {
+        self.cdf.get(value as usize).copied().unwrap_or(1.0)
+    }
+}
+
+/// The lowest and highest iteration counts of the escaped pixels of an image, mapping the counts
+/// into it stretches the palette over the iterations present in the view
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EscapeRange {
+    min: u32,
+    max: u32,
+}
+
+impl EscapeRange {
+    pub fn new(value: u32) -> Self {
+        Self {
+            min: value,
+            max: value,
+        }
+    }
+
+    pub fn include(&mut self, value: u32) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// The position of `value` between the lowest and highest count, in 0.0..=1.0
+    #[allow(clippy::cast_precision_loss)]
+    #[inline]
+    pub fn percent(&self, value: u32) -> f32 {
+        if self.max == self.min {
+            0.0
+        } else {
+            let value = value.max(self.min).min(self.max);
+            (value - self.min) as f32 / (self.max - self.min) as f32
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EscapeRange, Histogram};
+
+    #[test]
+    fn test_histogram() {
+        // most pixels escape after 10 iterations, the palette is spent on them
+        let values = [10, 10, 10, 10, 10, 10, 50, 90, 101, 101];
+        let histogram = Histogram::new(values.iter().copied(), 100);
+        assert_eq!(histogram.percent(0), 0.0);
+        assert_eq!(histogram.percent(9), 0.0);
+        assert_eq!(histogram.percent(10), 0.75);
+        assert_eq!(histogram.percent(49), 0.75);
+        assert_eq!(histogram.percent(50), 0.875);
+        assert_eq!(histogram.percent(90), 1.0);
+        assert_eq!(histogram.percent(100), 1.0);
+        assert_eq!(histogram.percent(101), 1.0);
+
+        let empty = Histogram::new([101, 101].iter().copied(), 100);
+        assert_eq!(empty.percent(50), 0.0);
+    }
+
+    #[test]
+    fn test_escape_range() {
+        let mut range = EscapeRange::new(870);
+        assert_eq!(range.percent(870), 0.0);
+        range.include(900);
+        range.include(850);
+        assert_eq!(range, EscapeRange { min: 850, max: 900 });
+        assert_eq!(range.percent(850), 0.0);
+        assert_eq!(range.percent(875), 0.5);
+        assert_eq!(range.percent(900), 1.0);
+        assert_eq!(range.percent(10), 0.0);
+        assert_eq!(range.percent(1000), 1.0);
+    }
+}
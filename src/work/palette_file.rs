@@ -0,0 +1,406 @@
+use super::colors::{ColorRange, RgbColor};
+use super::gradient::{ColorStop, Gradient, Interpolation, RepeatMode};
+
+/// The file types palettes can be imported from, for the accept attribute of file inputs
+pub const PALETTE_FILE_TYPES: &str = ".gpl,.ggr,.map";
+
+const GPL_HEADER: &str = "GIMP Palette";
+const GGR_HEADER: &str = "GIMP Gradient";
+
+/// The file formats of other programs palettes are imported from
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum PaletteFormat {
+    /// GIMP palette, a list of colors
+    Gpl,
+    /// GIMP gradient, a list of segments blending between two colors
+    Ggr,
+    /// Fractint and Ultra Fractal map, one color per line
+    Map,
+}
+
+impl PaletteFormat {
+    /// The format of a file by its extension or, failing that, by its first line
+    pub fn detect(file_name: &str, content: &str) -> Option<PaletteFormat> {
+        let extension = file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_lowercase());
+        match extension.as_deref() {
+            Some("gpl") => Some(PaletteFormat::Gpl),
+            Some("ggr") => Some(PaletteFormat::Ggr),
+            Some("map") => Some(PaletteFormat::Map),
+            _ => match content.lines().next().map(str::trim) {
+                Some(GPL_HEADER) => Some(PaletteFormat::Gpl),
+                Some(GGR_HEADER) => Some(PaletteFormat::Ggr),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// A palette read from a file
+pub struct ImportedPalette {
+    pub name: String,
+    pub range: ColorRange,
+}
+
+/// Parse a palette file, the palette is named after the name in the file or the file name.
+/// Errors are reported with the line they were found in.
+pub fn parse_palette_file(file_name: &str, content: &str) -> Result<ImportedPalette, Vec<String>> {
+    let format = PaletteFormat::detect(file_name, content).ok_or_else(|| {
+        vec![format!(
+            "{}: unknown palette format, expected a {} file",
+            file_name, PALETTE_FILE_TYPES
+        )]
+    })?;
+    let (name, stops) = match format {
+        PaletteFormat::Gpl => parse_gpl(content),
+        PaletteFormat::Ggr => parse_ggr(content),
+        PaletteFormat::Map => parse_map(content).map(|stops| (None, stops)),
+    }
+    .map_err(|errors| {
+        errors
+            .into_iter()
+            .map(|err| format!("{}: {}", file_name, err))
+            .collect::<Vec<String>>()
+    })?;
+
+    let name = name.filter(|name| !name.is_empty()).unwrap_or_else(|| {
+        let base = file_name
+            .rsplit(&['/', '\\'][..])
+            .next()
+            .unwrap_or(file_name);
+        base.rsplit_once('.')
+            .map_or(base, |(stem, _)| stem)
+            .to_owned()
+    });
+    Ok(ImportedPalette {
+        name,
+        range: ColorRange::Gradient(Gradient::new(stops, RepeatMode::Clamp, 1.0)),
+    })
+}
+
+/// Evenly spaced stops for a list of colors, a single color makes a plain palette
+#[allow(clippy::cast_precision_loss)]
+fn spread_stops(colors: Vec<RgbColor>) -> Vec<ColorStop> {
+    if colors.len() == 1 {
+        let color = colors[0].clone();
+        return vec![
+            ColorStop::new(0.0, color.clone(), Interpolation::Rgb),
+            ColorStop::new(1.0, color, Interpolation::Rgb),
+        ];
+    }
+    let last = (colors.len() - 1) as f32;
+    colors
+        .into_iter()
+        .enumerate()
+        .map(|(idx, color)| ColorStop::new(idx as f32 / last, color, Interpolation::Rgb))
+        .collect()
+}
+
+/// Parse the first `count` whitespace separated fields of `line` as numbers
+fn parse_numbers<T: std::str::FromStr>(
+    line: &str,
+    count: usize,
+    line_no: usize,
+) -> Result<Vec<T>, String> {
+    let numbers = line
+        .split_whitespace()
+        .take(count)
+        .map(|field| {
+            field
+                .parse::<T>()
+                .map_err(|_| format!("line {}: invalid number '{}'", line_no, field))
+        })
+        .collect::<Result<Vec<T>, String>>()?;
+    if numbers.len() < count {
+        Err(format!(
+            "line {}: expected {} numbers, found {}",
+            line_no,
+            count,
+            numbers.len()
+        ))
+    } else {
+        Ok(numbers)
+    }
+}
+
+/// A color of 8 bit channels from the first three fields of `line`
+fn parse_rgb(line: &str, line_no: usize) -> Result<RgbColor, String> {
+    let channels = parse_numbers::<u32>(line, 3, line_no)?;
+    let channel = |value: u32| {
+        u8::try_from(value)
+            .map_err(|_| format!("line {}: channel {} is out of 0..255", line_no, value))
+    };
+    Ok(RgbColor::new(
+        channel(channels[0])?,
+        channel(channels[1])?,
+        channel(channels[2])?,
+    ))
+}
+
+/// A line number (starting at 1) and content for every line that is neither empty nor a comment
+fn content_lines<'a>(
+    lines: impl Iterator<Item = (usize, &'a str)>,
+) -> impl Iterator<Item = (usize, &'a str)> {
+    lines
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// The value of a `key: value` header line
+fn header_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    line.strip_prefix(key)
+        .and_then(|rest| rest.trim_start().strip_prefix(':'))
+        .map(str::trim)
+}
+
+fn parse_gpl(content: &str) -> Result<(Option<String>, Vec<ColorStop>), Vec<String>> {
+    let mut lines = content.lines().enumerate();
+    match lines.next() {
+        Some((_, line)) if line.trim() == GPL_HEADER => (),
+        _ => return Err(vec![format!("line 1: expected '{}'", GPL_HEADER)]),
+    }
+
+    let mut name = None;
+    let mut colors = Vec::new();
+    let mut errors = Vec::new();
+    for (line_no, line) in content_lines(lines) {
+        if let Some(value) = header_value(line, "Name") {
+            name = Some(value.to_owned());
+        } else if header_value(line, "Columns").is_some() {
+            continue;
+        } else {
+            match parse_rgb(line, line_no) {
+                Ok(color) => colors.push(color),
+                Err(err) => errors.push(err),
+            }
+        }
+    }
+    if errors.is_empty() && colors.is_empty() {
+        errors.push("the palette contains no colors".to_owned());
+    }
+    if errors.is_empty() {
+        Ok((name, spread_stops(colors)))
+    } else {
+        Err(errors)
+    }
+}
+
+fn parse_map(content: &str) -> Result<Vec<ColorStop>, Vec<String>> {
+    let mut colors = Vec::new();
+    let mut errors = Vec::new();
+    for (line_no, line) in content_lines(content.lines().enumerate()) {
+        // the colors may be followed by a comment
+        match parse_rgb(line, line_no) {
+            Ok(color) => colors.push(color),
+            Err(err) => errors.push(err),
+        }
+    }
+    if errors.is_empty() && colors.is_empty() {
+        errors.push("the map contains no colors".to_owned());
+    }
+    if errors.is_empty() {
+        Ok(spread_stops(colors))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parse a GIMP gradient, every segment becomes a stop at each of its ends and one at its
+/// midpoint. Blend functions other than linear are approximated by linear blending, HSV
+/// coloring by blending in HSL.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn parse_ggr(content: &str) -> Result<(Option<String>, Vec<ColorStop>), Vec<String>> {
+    let mut lines = content.lines().enumerate();
+    match lines.next() {
+        Some((_, line)) if line.trim() == GGR_HEADER => (),
+        _ => return Err(vec![format!("line 1: expected '{}'", GGR_HEADER)]),
+    }
+    let mut lines = content_lines(lines);
+
+    let mut name = None;
+    let mut count_line = lines.next();
+    if let Some(value) = count_line.and_then(|(_, line)| header_value(line, "Name")) {
+        name = Some(value.to_owned());
+        count_line = lines.next();
+    }
+    let count = match count_line {
+        Some((line_no, line)) => line.parse::<usize>().map_err(|_| {
+            vec![format!(
+                "line {}: invalid number of segments '{}'",
+                line_no, line
+            )]
+        })?,
+        None => return Err(vec!["the number of segments is missing".to_owned()]),
+    };
+
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let mut stops = Vec::new();
+    let mut errors = Vec::new();
+    let mut segments = 0;
+    for (line_no, line) in lines {
+        segments += 1;
+        // left middle right, left RGBA, right RGBA, blend function and coloring
+        let values = match parse_numbers::<f32>(line, 13, line_no) {
+            Ok(values) => values,
+            Err(err) => {
+                errors.push(err);
+                continue;
+            }
+        };
+        let (left, middle, right) = (values[0], values[1], values[2]);
+        if !(0.0..=1.0).contains(&left) || left > middle || middle > right || right > 1.0 {
+            errors.push(format!(
+                "line {}: segment positions {} {} {} are out of order",
+                line_no, left, middle, right
+            ));
+            continue;
+        }
+        let interpolation = if values[12] > 0.0 {
+            Interpolation::Hsl
+        } else {
+            Interpolation::Rgb
+        };
+        let left_color = RgbColor::new(channel(values[3]), channel(values[4]), channel(values[5]));
+        let right_color = RgbColor::new(channel(values[7]), channel(values[8]), channel(values[9]));
+        let middle_color = interpolation.mix(&left_color, &right_color, 0.5);
+        stops.push(ColorStop::new(left, left_color, interpolation));
+        stops.push(ColorStop::new(middle, middle_color, interpolation));
+        stops.push(ColorStop::new(right, right_color, interpolation));
+    }
+    if segments != count {
+        errors.push(format!("expected {} segments, found {}", count, segments));
+    }
+    if errors.is_empty() && stops.is_empty() {
+        errors.push("the gradient contains no segments".to_owned());
+    }
+    if errors.is_empty() {
+        Ok((name, stops))
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_palette_file, PaletteFormat};
+    use crate::work::colors::ColorRange;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(
+            PaletteFormat::detect("fire.GPL", ""),
+            Some(PaletteFormat::Gpl)
+        );
+        assert_eq!(
+            PaletteFormat::detect("fire", "GIMP Gradient\n"),
+            Some(PaletteFormat::Ggr)
+        );
+        assert_eq!(
+            PaletteFormat::detect("maps/default.map", ""),
+            Some(PaletteFormat::Map)
+        );
+        assert_eq!(PaletteFormat::detect("fire.txt", "255 0 0"), None);
+    }
+
+    #[test]
+    fn test_gpl() {
+        let palette = parse_palette_file(
+            "fire.gpl",
+            "GIMP Palette\nName: Fire\nColumns: 3\n# comment\n  0   0   0\tblack\n255 128 0 orange\n\n255 255 255\n",
+        )
+        .expect("valid palette");
+        assert_eq!(palette.name.as_str(), "Fire");
+        assert_eq!(
+            palette.range.percent_of(0.0).to_string().as_str(),
+            "#000000"
+        );
+        assert_eq!(
+            palette.range.percent_of(0.5).to_string().as_str(),
+            "#FF8000"
+        );
+        assert_eq!(
+            palette.range.percent_of(1.0).to_string().as_str(),
+            "#FFFFFF"
+        );
+
+        let errors = parse_palette_file("bad.gpl", "GIMP Palette\n255 0 0\n255 0\n1 2 300\n")
+            .err()
+            .expect("invalid palette");
+        assert_eq!(
+            errors,
+            vec![
+                "bad.gpl: line 3: expected 3 numbers, found 2".to_owned(),
+                "bad.gpl: line 4: channel 300 is out of 0..255".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map() {
+        let palette =
+            parse_palette_file("dir/blues.map", "0 0 0 first\n0 0 255\n").expect("valid map");
+        assert_eq!(palette.name.as_str(), "blues");
+        assert_eq!(
+            palette.range.percent_of(0.5).to_string().as_str(),
+            "#000080"
+        );
+
+        let errors = parse_palette_file("x.map", "0 0 0\nzero 0 0\n")
+            .err()
+            .expect("invalid map");
+        assert_eq!(
+            errors,
+            vec!["x.map: line 2: invalid number 'zero'".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_ggr() {
+        let palette = parse_palette_file(
+            "sunset.ggr",
+            "GIMP Gradient\nName: Sunset\n2\n\
+             0.0 0.25 0.5 0 0 0 1 1 0 0 1 0 0\n\
+             0.5 0.75 1.0 0 0 1 1 1 1 1 1 0 0\n",
+        )
+        .expect("valid gradient");
+        assert_eq!(palette.name.as_str(), "Sunset");
+        if let ColorRange::Gradient(gradient) = &palette.range {
+            assert_eq!(gradient.stops().len(), 6);
+        } else {
+            panic!("expected a gradient");
+        }
+        assert_eq!(
+            palette.range.percent_of(0.0).to_string().as_str(),
+            "#000000"
+        );
+        assert_eq!(
+            palette.range.percent_of(0.25).to_string().as_str(),
+            "#800000"
+        );
+        // a hard edge between the segments
+        assert_eq!(
+            palette.range.percent_of(0.49).to_string().as_str(),
+            "#FA0000"
+        );
+        assert_eq!(
+            palette.range.percent_of(0.5).to_string().as_str(),
+            "#0000FF"
+        );
+        assert_eq!(
+            palette.range.percent_of(1.0).to_string().as_str(),
+            "#FFFFFF"
+        );
+
+        let errors = parse_palette_file(
+            "bad.ggr",
+            "GIMP Gradient\n2\n0.0 0.5 1.0 0 0 0 1 1 1 1 1 0 0\n",
+        )
+        .err()
+        .expect("invalid gradient");
+        assert_eq!(
+            errors,
+            vec!["bad.ggr: expected 2 segments, found 1".to_owned()]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use super::colors::ColorRange;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The version of the palette library format written by `PaletteLibrary::to_json`, raise it on
+/// changes older versions can not read
+pub const PALETTE_LIBRARY_VERSION: u32 = 1;
+pub const PALETTE_LIBRARY_FILE_NAME: &str = "palettes.json";
+pub const PALETTE_LIBRARY_FILE_TYPE: &str = ".json";
+
+/// What to do with an imported palette whose name is taken
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum ConflictMode {
+    /// Keep both, the imported palette gets a free name
+    #[default]
+    Rename,
+    /// Replace the existing palette
+    Overwrite,
+    /// Keep the existing palette
+    Skip,
+}
+
+impl ConflictMode {
+    pub const ALL: [ConflictMode; 3] = [
+        ConflictMode::Rename,
+        ConflictMode::Overwrite,
+        ConflictMode::Skip,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ConflictMode::Rename => "Rename",
+            ConflictMode::Overwrite => "Overwrite",
+            ConflictMode::Skip => "Skip",
+        }
+    }
+}
+
+/// A collection of named palettes as shared between browsers
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct PaletteLibrary {
+    version: u32,
+    palettes: BTreeMap<String, ColorRange>,
+}
+
+impl PaletteLibrary {
+    pub fn new(palettes: BTreeMap<String, ColorRange>) -> Self {
+        Self {
+            version: PALETTE_LIBRARY_VERSION,
+            palettes,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|err| format!("failed to serialize palettes: {}", err))
+    }
+
+    /// Read a library, libraries of a newer version than `PALETTE_LIBRARY_VERSION` are rejected
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|err| format!("invalid JSON: {}", err))?;
+        match value.get("version").and_then(serde_json::Value::as_u64) {
+            Some(version) if version > u64::from(PALETTE_LIBRARY_VERSION) => Err(format!(
+                "palette library version {} is newer than the supported version {}",
+                version, PALETTE_LIBRARY_VERSION
+            )),
+            Some(_) => serde_json::from_value(value)
+                .map_err(|err| format!("invalid palette library: {}", err)),
+            None => Err("not a palette library, the version is missing".to_owned()),
+        }
+    }
+
+    pub fn into_palettes(self) -> impl Iterator<Item = (String, ColorRange)> {
+        self.palettes.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PaletteLibrary, PALETTE_LIBRARY_VERSION};
+    use crate::work::colors::ColorRange;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut palettes = BTreeMap::new();
+        palettes.insert("default".to_owned(), ColorRange::default());
+        let library = PaletteLibrary::new(palettes);
+        let json = library.to_json().expect("serialized library");
+        assert!(json.contains(format!("\"version\": {}", PALETTE_LIBRARY_VERSION).as_str()));
+        let restored = PaletteLibrary::from_json(json.as_str()).expect("valid library");
+        assert!(restored == library);
+        assert_eq!(restored.into_palettes().count(), 1);
+    }
+
+    #[test]
+    fn test_versions() {
+        assert!(PaletteLibrary::from_json(r#"{"version":99,"palettes":{}}"#)
+            .err()
+            .expect("newer version")
+            .contains("newer"));
+        assert!(PaletteLibrary::from_json(r#"{"palettes":{}}"#).is_err());
+        assert!(PaletteLibrary::from_json("GIMP Palette").is_err());
+    }
+}
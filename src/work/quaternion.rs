@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// The quaternion r + i a + j b + k c
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Quaternion {
+    r: f64,
+    i: f64,
+    j: f64,
+    k: f64,
+}
+
+impl Quaternion {
+    pub fn new(r: f64, i: f64, j: f64, k: f64) -> Self {
+        Self { r, i, j, k }
+    }
+
+    #[inline]
+    pub fn r(&self) -> f64 {
+        self.r
+    }
+    #[inline]
+    pub fn i(&self) -> f64 {
+        self.i
+    }
+    #[inline]
+    pub fn j(&self) -> f64 {
+        self.j
+    }
+    #[inline]
+    pub fn k(&self) -> f64 {
+        self.k
+    }
+
+    #[inline]
+    pub fn square_length(&self) -> f64 {
+        self.r.mul_add(
+            self.r,
+            self.i
+                .mul_add(self.i, self.j.mul_add(self.j, self.k * self.k)),
+        )
+    }
+    #[cfg(test)]
+    #[inline]
+    pub fn norm(&self) -> f64 {
+        f64::sqrt(self.square_length())
+    }
+
+    #[cfg(test)]
+    #[inline]
+    pub fn conjugate(&self) -> Quaternion {
+        Self {
+            r: self.r,
+            i: -self.i,
+            j: -self.j,
+            k: -self.k,
+        }
+    }
+
+    /// The multiplicative inverse, the inverse of zero has infinite components
+    #[cfg(test)]
+    pub fn inverse(&self) -> Quaternion {
+        self.conjugate() * self.square_length().recip()
+    }
+
+    /// The Hamilton product, it is not commutative
+    #[inline]
+    pub fn mul_by(&self, other: &Quaternion) -> Quaternion {
+        Self {
+            r: self.r * other.r - self.i * other.i - self.j * other.j - self.k * other.k,
+            i: self.r * other.i + self.i * other.r + self.j * other.k - self.k * other.j,
+            j: self.r * other.j - self.i * other.k + self.j * other.r + self.k * other.i,
+            k: self.r * other.k + self.i * other.j - self.j * other.i + self.k * other.r,
+        }
+    }
+
+    /// The square, cheaper than `mul_by` as the imaginary parts commute with themselves
+    #[inline]
+    pub fn square(&self) -> Quaternion {
+        let double_r = 2.0 * self.r;
+        Self {
+            r: self.r * self.r - self.i * self.i - self.j * self.j - self.k * self.k,
+            i: double_r * self.i,
+            j: double_r * self.j,
+            k: double_r * self.k,
+        }
+    }
+}
+
+impl Add for Quaternion {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            r: self.r + other.r,
+            i: self.i + other.i,
+            j: self.j + other.j,
+            k: self.k + other.k,
+        }
+    }
+}
+
+impl AddAssign for Quaternion {
+    fn add_assign(&mut self, other: Self) {
+        self.r += other.r;
+        self.i += other.i;
+        self.j += other.j;
+        self.k += other.k;
+    }
+}
+
+impl Sub for Quaternion {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self {
+            r: self.r - other.r,
+            i: self.i - other.i,
+            j: self.j - other.j,
+            k: self.k - other.k,
+        }
+    }
+}
+
+impl SubAssign for Quaternion {
+    fn sub_assign(&mut self, other: Self) {
+        self.r -= other.r;
+        self.i -= other.i;
+        self.j -= other.j;
+        self.k -= other.k;
+    }
+}
+
+impl Neg for Quaternion {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            r: -self.r,
+            i: -self.i,
+            j: -self.j,
+            k: -self.k,
+        }
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self::Output {
+        self.mul_by(&other)
+    }
+}
+
+impl MulAssign for Quaternion {
+    fn mul_assign(&mut self, other: Self) {
+        *self = self.mul_by(&other);
+    }
+}
+
+impl Mul<f64> for Quaternion {
+    type Output = Self;
+    fn mul(self, other: f64) -> Self::Output {
+        Self {
+            r: self.r * other,
+            i: self.i * other,
+            j: self.j * other,
+            k: self.k * other,
+        }
+    }
+}
+
+impl MulAssign<f64> for Quaternion {
+    fn mul_assign(&mut self, other: f64) {
+        self.r *= other;
+        self.i *= other;
+        self.j *= other;
+        self.k *= other;
+    }
+}
+
+impl Display for Quaternion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}+i{}+j{}+k{})", self.r, self.i, self.j, self.k)
+    }
+}
+
+impl Debug for Quaternion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}+i{}+j{}+k{})", self.r, self.i, self.j, self.k)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Quaternion;
+
+    #[test]
+    fn test_units() {
+        let one = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+        let i = Quaternion::new(0.0, 1.0, 0.0, 0.0);
+        let j = Quaternion::new(0.0, 0.0, 1.0, 0.0);
+        let k = Quaternion::new(0.0, 0.0, 0.0, 1.0);
+        // i^2 = j^2 = k^2 = ijk = -1
+        assert_eq!(i * i, -one);
+        assert_eq!(j * j, -one);
+        assert_eq!(k * k, -one);
+        assert_eq!(i * j * k, -one);
+        assert_eq!(i * j, k);
+        assert_eq!(j * i, -k);
+        assert_eq!(j * k, i);
+        assert_eq!(k * i, j);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let p = Quaternion::new(1.0, 2.0, 3.0, 4.0);
+        let q = Quaternion::new(-0.5, 0.25, 2.0, -1.0);
+        assert_eq!(p + q, Quaternion::new(0.5, 2.25, 5.0, 3.0));
+        assert_eq!(p - q, Quaternion::new(1.5, 1.75, 1.0, 5.0));
+        assert_eq!(p * 2.0, Quaternion::new(2.0, 4.0, 6.0, 8.0));
+        assert_eq!(p.conjugate(), Quaternion::new(1.0, -2.0, -3.0, -4.0));
+        assert_eq!(p.square_length(), 30.0);
+        assert_eq!(p.square(), p * p);
+        assert_eq!(q.square(), q * q);
+
+        let mut sum = p;
+        sum += q;
+        sum -= q;
+        assert_eq!(sum, p);
+        let mut product = p;
+        product *= q;
+        assert_eq!(product, p.mul_by(&q));
+        product *= 0.5;
+        assert_eq!(product, p.mul_by(&q) * 0.5);
+
+        // |pq| = |p||q|
+        assert!(((p * q).norm() - p.norm() * q.norm()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let p = Quaternion::new(1.0, 2.0, 3.0, 4.0);
+        let one = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+        assert!((p * p.inverse() - one).norm() < 1e-12);
+        assert!((p.inverse() * p - one).norm() < 1e-12);
+    }
+}
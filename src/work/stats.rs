@@ -38,6 +38,18 @@ impl Stats {
         self.total_time = end - self.start_time;
     }
 
+    /// Update with results that were calculated elsewhere (in a web worker) in `duration` ms
+    pub fn update_with_duration(&mut self, iterations: usize, points: usize, duration: f64) {
+        let performance = web_sys::window()
+            .expect("Window not found")
+            .performance()
+            .expect("performance should be available");
+        self.iterations += iterations;
+        self.points += points;
+        self.time_in_fractal += duration;
+        self.total_time = performance.now() - self.start_time;
+    }
+
     pub fn format_stats(&self) -> String {
         format!(
             "\
@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// edge length of a tile in pixels
+pub const TILE_SIZE: u32 = 32;
+/// zoom levels per factor of two in pixel size
+pub const ZOOM_STEPS_PER_OCTAVE: f64 = 16.0;
+/// upper bound for the memory used by cached iteration values and angles
+pub const TILE_CACHE_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+// a tile is accounted with room for an angle per pixel, whether it holds angles or not
+const TILE_BYTES: usize =
+    (TILE_SIZE * TILE_SIZE) as usize * (std::mem::size_of::<u32>() + std::mem::size_of::<f32>());
+
+/// Identifies a tile in the fixed tiling of the complex plane.
+/// At zoom level `zoom` a pixel has the size `grid_size(zoom)` and tile (x, y) covers the grid
+/// points `x * TILE_SIZE .. (x + 1) * TILE_SIZE` horizontally and likewise vertically.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TileKey {
+    pub fractal: u64,
+    pub zoom: i32,
+    pub x: i64,
+    pub y: i64,
+}
+
+/// The zoom level whose grid size is closest to the given pixel size
+#[allow(clippy::cast_possible_truncation)]
+pub fn zoom_level(pixel_size: f64) -> i32 {
+    (-pixel_size.log2() * ZOOM_STEPS_PER_OCTAVE).round() as i32
+}
+
+/// The pixel size of the grid at zoom level `zoom`
+pub fn grid_size(zoom: i32) -> f64 {
+    (-f64::from(zoom) / ZOOM_STEPS_PER_OCTAVE).exp2()
+}
+
+struct CacheEntry {
+    values: Vec<u32>,
+    angles: Vec<f32>,
+    last_used: u64,
+}
+
+/// Per pixel iteration values and final angles (if calculated) of tiles with least recently
+/// used eviction
+pub struct TileCache {
+    tiles: HashMap<TileKey, CacheEntry>,
+    lru: BTreeMap<u64, TileKey>,
+    max_tiles: usize,
+    tick: u64,
+}
+
+impl TileCache {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            tiles: HashMap::new(),
+            lru: BTreeMap::new(),
+            max_tiles: (max_bytes / TILE_BYTES).max(1),
+            tick: 0,
+        }
+    }
+
+    /// Retrieve the values and angles of a tile and mark it as recently used
+    pub fn get(&mut self, key: &TileKey) -> Option<(&[u32], &[f32])> {
+        self.tick += 1;
+        let tick = self.tick;
+        let entry = self.tiles.get_mut(key)?;
+        self.lru.remove(&entry.last_used);
+        self.lru.insert(tick, *key);
+        entry.last_used = tick;
+        Some((entry.values.as_slice(), entry.angles.as_slice()))
+    }
+
+    pub fn insert(&mut self, key: TileKey, values: Vec<u32>, angles: Vec<f32>) {
+        debug_assert_eq!(values.len(), (TILE_SIZE * TILE_SIZE) as usize);
+        debug_assert!(angles.is_empty() || angles.len() == values.len());
+        self.tick += 1;
+        if let Some(old) = self.tiles.remove(&key) {
+            self.lru.remove(&old.last_used);
+        }
+
+        while self.tiles.len() >= self.max_tiles {
+            let oldest = self.lru.keys().next().copied();
+            if let Some(oldest) = oldest {
+                if let Some(old_key) = self.lru.remove(&oldest) {
+                    self.tiles.remove(&old_key);
+                }
+            } else {
+                break;
+            }
+        }
+
+        self.lru.insert(self.tick, key);
+        self.tiles.insert(
+            key,
+            CacheEntry {
+                values,
+                angles,
+                last_used: self.tick,
+            },
+        );
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.tiles.len()
+    }
+}
+
+impl Default for TileCache {
+    fn default() -> Self {
+        TileCache::new(TILE_CACHE_MAX_BYTES)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{grid_size, zoom_level, TileCache, TileKey, TILE_BYTES, TILE_SIZE};
+
+    fn key(x: i64) -> TileKey {
+        TileKey {
+            fractal: 1,
+            zoom: 0,
+            x,
+            y: 0,
+        }
+    }
+
+    fn tile(value: u32) -> Vec<u32> {
+        vec![value; (TILE_SIZE * TILE_SIZE) as usize]
+    }
+
+    #[test]
+    fn test_zoom_level() {
+        assert_eq!(zoom_level(1.0), 0);
+        assert_eq!(zoom_level(0.5), 16);
+        assert_eq!(grid_size(16), 0.5);
+        let pixel_size = 3.1 / 1024.0;
+        let grid = grid_size(zoom_level(pixel_size));
+        assert!((grid - pixel_size).abs() / pixel_size < 0.03);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = TileCache::new(2 * TILE_BYTES);
+        cache.insert(key(0), tile(0), Vec::new());
+        cache.insert(key(1), tile(1), Vec::new());
+        assert_eq!(cache.get(&key(0)).map(|(values, _)| values[0]), Some(0));
+        cache.insert(key(2), tile(2), Vec::new());
+        assert_eq!(cache.tile_count(), 2);
+        assert!(cache.get(&key(1)).is_none());
+        assert_eq!(cache.get(&key(0)).map(|(values, _)| values[0]), Some(0));
+        assert_eq!(cache.get(&key(2)).map(|(values, _)| values[0]), Some(2));
+    }
+
+    #[test]
+    fn test_insert_replaces() {
+        let mut cache = TileCache::new(2 * TILE_BYTES);
+        cache.insert(key(0), tile(0), Vec::new());
+        cache.insert(key(0), tile(5), Vec::new());
+        assert_eq!(cache.tile_count(), 1);
+        assert_eq!(cache.get(&key(0)).map(|(values, _)| values[0]), Some(5));
+    }
+
+    #[test]
+    fn test_keeps_angles() {
+        let mut cache = TileCache::new(2 * TILE_BYTES);
+        cache.insert(key(0), tile(0), vec![0.5; (TILE_SIZE * TILE_SIZE) as usize]);
+        cache.insert(key(1), tile(1), Vec::new());
+        assert_eq!(cache.get(&key(0)).map(|(_, angles)| angles[0]), Some(0.5));
+        assert_eq!(cache.get(&key(1)).map(|(_, angles)| angles.len()), Some(0));
+    }
+}
@@ -0,0 +1,137 @@
+use super::gradient::RepeatMode;
+use serde::{Deserialize, Serialize};
+
+/// The default number of iterations of one run through the palette in cyclic transfer
+pub const TRANSFER_DEFAULT_PERIOD: f32 = 64.0;
+/// The steepness of the logarithmic transfer
+const LOG_SCALE: f32 = 1023.0;
+
+/// The curve mapping the iteration count onto the palette position
+#[derive(PartialEq, Clone, Copy, Default, Serialize, Deserialize, Debug)]
+pub enum TransferFunction {
+    #[default]
+    Linear,
+    SquareRoot,
+    CubeRoot,
+    /// Spreads the low iteration counts, compresses the high ones
+    Logarithmic,
+    /// Runs through the palette every period iterations, independent of the maximum
+    Cyclic,
+}
+
+impl TransferFunction {
+    pub const ALL: [TransferFunction; 5] = [
+        TransferFunction::Linear,
+        TransferFunction::SquareRoot,
+        TransferFunction::CubeRoot,
+        TransferFunction::Logarithmic,
+        TransferFunction::Cyclic,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TransferFunction::Linear => "Linear",
+            TransferFunction::SquareRoot => "Square Root",
+            TransferFunction::CubeRoot => "Cube Root",
+            TransferFunction::Logarithmic => "Logarithmic",
+            TransferFunction::Cyclic => "Cyclic",
+        }
+    }
+}
+
+/// The stage between the iteration count of a point and its position in the palette
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Transfer {
+    pub function: TransferFunction,
+    /// The number of times the palette is run through
+    pub density: f32,
+    /// Iterations per run through the palette in cyclic transfer
+    pub period: f32,
+    /// Start of the cycle in cyclic transfer as fraction of the palette
+    pub offset: f32,
+}
+
+impl Transfer {
+    /// The palette position (0.0..=1.0) of a point at `percent` (0.0..=1.0) of the maximum
+    /// iterations, `iterations` is the smooth iteration count of the point
+    #[inline]
+    pub fn apply(&self, percent: f32, iterations: f32) -> f32 {
+        let position = match self.function {
+            TransferFunction::Linear => percent,
+            TransferFunction::SquareRoot => percent.max(0.0).sqrt(),
+            TransferFunction::CubeRoot => percent.cbrt(),
+            TransferFunction::Logarithmic => {
+                (1.0 + LOG_SCALE * percent.max(0.0)).ln() / (1.0 + LOG_SCALE).ln()
+            }
+            TransferFunction::Cyclic => {
+                (iterations / self.period.max(f32::EPSILON) + self.offset).rem_euclid(1.0)
+            }
+        };
+        if (self.density - 1.0).abs() < f32::EPSILON {
+            position
+        } else {
+            RepeatMode::Repeat.apply(position * self.density)
+        }
+    }
+}
+
+impl Default for Transfer {
+    fn default() -> Self {
+        Self {
+            function: TransferFunction::default(),
+            density: 1.0,
+            period: TRANSFER_DEFAULT_PERIOD,
+            offset: 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Transfer, TransferFunction};
+
+    fn transfer(function: TransferFunction, density: f32) -> Transfer {
+        Transfer {
+            function,
+            density,
+            ..Transfer::default()
+        }
+    }
+
+    #[test]
+    fn test_functions() {
+        for function in TransferFunction::ALL.iter().take(4) {
+            let transfer = transfer(*function, 1.0);
+            assert!(transfer.apply(0.0, 0.0).abs() < 1e-6);
+            assert!((transfer.apply(1.0, 100.0) - 1.0).abs() < 1e-6);
+        }
+        assert!((transfer(TransferFunction::Linear, 1.0).apply(0.25, 25.0) - 0.25).abs() < 1e-6);
+        assert!((transfer(TransferFunction::SquareRoot, 1.0).apply(0.25, 25.0) - 0.5).abs() < 1e-6);
+        assert!((transfer(TransferFunction::CubeRoot, 1.0).apply(0.125, 25.0) - 0.5).abs() < 1e-6);
+        // the logarithm lifts the low iteration counts the most
+        assert!(transfer(TransferFunction::Logarithmic, 1.0).apply(0.01, 1.0) > 0.3);
+    }
+
+    #[test]
+    fn test_cyclic() {
+        let cyclic = Transfer {
+            function: TransferFunction::Cyclic,
+            density: 1.0,
+            period: 10.0,
+            offset: 0.5,
+        };
+        // independent of the maximum iterations
+        assert!((cyclic.apply(0.1, 25.0) - 0.0).abs() < 1e-6);
+        assert!((cyclic.apply(0.9, 25.0) - 0.0).abs() < 1e-6);
+        assert!((cyclic.apply(0.9, 27.0) - 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_density() {
+        let linear = transfer(TransferFunction::Linear, 2.0);
+        assert!((linear.apply(0.25, 0.0) - 0.5).abs() < 1e-6);
+        assert!((linear.apply(0.75, 0.0) - 0.5).abs() < 1e-6);
+        assert!((linear.apply(1.0, 0.0) - 1.0).abs() < 1e-6);
+    }
+}
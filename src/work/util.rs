@@ -2,7 +2,7 @@
 #![allow(dead_code)]
 use yew::prelude::*;
 
-use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
 
 pub fn get_f64_from_ref(node_ref: &NodeRef, name: &str) -> Result<f64, String> {
     match node_ref.cast::<HtmlInputElement>() {
@@ -30,6 +30,53 @@ pub fn get_u32_from_ref(node_ref: &NodeRef, name: &str) -> Result<u32, String> {
     }
 }
 
+pub fn get_string_from_ref(node_ref: &NodeRef, name: &str) -> Result<String, String> {
+    match node_ref.cast::<HtmlInputElement>() {
+        Some(element) => Ok(element.value()),
+        None => Err(format!(
+            "Could not cast NodeRef to HtmlInputElement for value {}",
+            name
+        )),
+    }
+}
+
+pub fn get_checked_from_ref(node_ref: &NodeRef, name: &str) -> Result<bool, String> {
+    match node_ref.cast::<HtmlInputElement>() {
+        Some(element) => Ok(element.checked()),
+        None => Err(format!(
+            "Could not cast NodeRef to HtmlInputElement for value {}",
+            name
+        )),
+    }
+}
+
+pub fn get_select_value_from_ref(node_ref: &NodeRef, name: &str) -> Result<String, String> {
+    match node_ref.cast::<HtmlSelectElement>() {
+        Some(element) => Ok(element.value()),
+        None => Err(format!(
+            "Could not cast NodeRef to HtmlSelectElement for value {}",
+            name
+        )),
+    }
+}
+
+pub fn set_checked_on_input_ref(
+    node_ref: &NodeRef,
+    name: &str,
+    checked: bool,
+) -> Result<(), String> {
+    match node_ref.cast::<HtmlInputElement>() {
+        Some(element) => {
+            element.set_checked(checked);
+            Ok(())
+        }
+        None => Err(format!(
+            "Could not cast NodeRef to HtmlInputElement for value {}",
+            name
+        )),
+    }
+}
+
 pub fn set_value_on_input_ref(node_ref: &NodeRef, name: &str, value: &str) -> Result<(), String> {
     match node_ref.cast::<HtmlInputElement>() {
         Some(element) => {
@@ -43,6 +90,19 @@ pub fn set_value_on_input_ref(node_ref: &NodeRef, name: &str, value: &str) -> Re
     }
 }
 
+pub fn set_value_on_select_ref(node_ref: &NodeRef, name: &str, value: &str) -> Result<(), String> {
+    match node_ref.cast::<HtmlSelectElement>() {
+        Some(element) => {
+            element.set_value(value);
+            Ok(())
+        }
+        None => Err(format!(
+            "Could not cast NodeRef to HtmlSelectElement for value {}",
+            name
+        )),
+    }
+}
+
 pub fn set_value_on_txt_area_ref(
     node_ref: &NodeRef,
     name: &str,
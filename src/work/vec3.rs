@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Vec3 {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl Vec3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    #[inline]
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+    #[inline]
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+    #[inline]
+    pub fn z(&self) -> f64 {
+        self.z
+    }
+
+    #[inline]
+    pub fn dot(&self, other: &Vec3) -> f64 {
+        self.x
+            .mul_add(other.x, self.y.mul_add(other.y, self.z * other.z))
+    }
+
+    #[inline]
+    pub fn cross(&self, other: &Vec3) -> Vec3 {
+        Vec3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    #[inline]
+    pub fn length(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    /// The vector scaled to length 1, the zero vector stays unchanged
+    #[inline]
+    pub fn normalized(&self) -> Vec3 {
+        let length = self.length();
+        if length > 0.0 {
+            *self * length.recip()
+        } else {
+            *self
+        }
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+}
+
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+        self.z += other.z;
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self {
+            x: self.x - other.x,
+            y: self.y - other.y,
+            z: self.z - other.z,
+        }
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+impl Mul<f64> for Vec3 {
+    type Output = Self;
+    fn mul(self, other: f64) -> Self::Output {
+        Self {
+            x: self.x * other,
+            y: self.y * other,
+            z: self.z * other,
+        }
+    }
+}
+
+impl Display for Vec3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl Debug for Vec3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Vec3;
+
+    #[test]
+    fn test_dot_cross() {
+        let x = Vec3::new(1.0, 0.0, 0.0);
+        let y = Vec3::new(0.0, 1.0, 0.0);
+        assert_eq!(x.dot(&y), 0.0);
+        assert_eq!(x.cross(&y), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(y.cross(&x), Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!(
+            Vec3::new(1.0, 2.0, 3.0).dot(&Vec3::new(4.0, 5.0, 6.0)),
+            32.0
+        );
+    }
+
+    #[test]
+    fn test_normalized() {
+        let v = Vec3::new(3.0, 0.0, 4.0);
+        assert_eq!(v.length(), 5.0);
+        assert!((v.normalized() - Vec3::new(0.6, 0.0, 0.8)).length() < 1e-12);
+        let zero = Vec3::new(0.0, 0.0, 0.0);
+        assert_eq!(zero.normalized(), zero);
+    }
+}